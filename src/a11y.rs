@@ -0,0 +1,85 @@
+//! Keeps what assistive technology reads stable while frames are scrambled.
+
+use wasm_bindgen::prelude::*;
+use web_sys::Element;
+
+use crate::scramble::AriaLive;
+
+/// Inline style hiding an element visually while leaving it in the accessibility tree.
+const VISUALLY_HIDDEN: &str = "position:absolute;width:1px;height:1px;margin:-1px;padding:0;\
+    border:0;overflow:hidden;clip:rect(0 0 0 0);white-space:nowrap";
+
+/// Creates a visually hidden `<span>` holding `text`, for screen readers only.
+pub(crate) fn visually_hidden_span(element: &Element, text: &str) -> Result<Element, JsValue> {
+    let document = element
+        .owner_document()
+        .ok_or_else(|| JsError::new("Element has no owner document"))?;
+    let span = document.create_element("span")?;
+    span.set_attribute("style", VISUALLY_HIDDEN)?;
+    span.set_text_content(Some(text));
+    Ok(span)
+}
+
+/// Hides an animating element from assistive technology and puts a visually hidden copy of
+/// the final text next to it. Dropping it restores the element.
+pub(crate) struct StableLabel {
+    element: Element,
+    label: Element,
+    aria_hidden: Option<String>,
+}
+
+impl StableLabel {
+    pub(crate) fn new(element: &Element, text: &str) -> Result<StableLabel, JsValue> {
+        let label = visually_hidden_span(element, text)?;
+        element.after_with_node_1(&label)?;
+
+        let aria_hidden = element.get_attribute("aria-hidden");
+        element.set_attribute("aria-hidden", "true")?;
+
+        Ok(StableLabel {
+            element: element.clone(),
+            label,
+            aria_hidden,
+        })
+    }
+}
+
+impl Drop for StableLabel {
+    fn drop(&mut self) {
+        self.label.remove();
+        let _ = match &self.aria_hidden {
+            Some(value) => self.element.set_attribute("aria-hidden", value),
+            None => self.element.remove_attribute("aria-hidden"),
+        };
+    }
+}
+
+/// Visually hidden live region placed next to the target, announcing the final text once a
+/// run completes. Dropping it removes the region.
+pub(crate) struct Announcer {
+    region: Element,
+}
+
+impl Announcer {
+    pub(crate) fn new(element: &Element, politeness: AriaLive) -> Result<Announcer, JsValue> {
+        let region = visually_hidden_span(element, "")?;
+        region.set_attribute("aria-live", politeness.as_str())?;
+        region.set_attribute("aria-atomic", "true")?;
+        element.after_with_node_1(&region)?;
+        Ok(Announcer { region })
+    }
+
+    pub(crate) fn clear(&self) {
+        self.region.set_text_content(None);
+    }
+
+    pub(crate) fn announce(&self, text: &str) {
+        self.region.set_text_content(Some(text));
+    }
+}
+
+impl Drop for Announcer {
+    fn drop(&mut self) {
+        self.region.remove();
+    }
+}
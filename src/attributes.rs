@@ -0,0 +1,251 @@
+//! Builds `UseScrambleProps` from HTML attributes for declarative usage.
+
+use std::str::FromStr;
+use wasm_bindgen::prelude::*;
+use web_sys::Element;
+
+use crate::scramble::UseScrambleProps;
+
+/// Attribute names (without prefix) that map onto `UseScrambleProps` fields.
+pub(crate) const PROP_ATTRIBUTES: &[&str] = &[
+    "max-length",
+    "overlength",
+    "speed",
+    "max-fps",
+    "tick",
+    "step",
+    "chance",
+    "seed",
+    "scramble",
+    "scramble-jitter",
+    "range",
+    "ignore",
+    "ignore-words",
+    "overdrive",
+    "overdrive-step",
+    "overflow",
+    "pipeline",
+    "placeholder-char",
+    "mismatch",
+    "whitespace",
+    "dir",
+    "transform",
+    "mask",
+    "hover-replay",
+    "triggers",
+    "spoiler",
+    "conceal-after",
+    "scroll-scrub",
+    "hover-scrub",
+    "hover-scrub-radius",
+    "hover-scrub-settle",
+    "css-vars",
+    "content-selector",
+    "shared-target",
+    "play-on-mount",
+    "pause-when-hidden",
+    "pause-on-blur",
+    "idle",
+    "virtualize",
+    "spans",
+    "reduced-motion",
+    "a11y",
+    "aria-live",
+    "announce-on-complete",
+    "debug",
+    "overlay",
+];
+
+fn parse_attribute<T>(element: &Element, name: &str) -> Result<Option<T>, JsError>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    match element.get_attribute(name) {
+        Some(value) => {
+            value.trim().parse().map(Some).map_err(|e| {
+                JsError::new(&format!("Invalid {} attribute \"{}\": {}", name, value, e))
+            })
+        }
+        None => Ok(None),
+    }
+}
+
+/// Boolean attributes are true when present, unless set to "false".
+fn parse_flag(element: &Element, name: &str) -> Option<bool> {
+    element
+        .get_attribute(name)
+        .map(|value| value.trim() != "false")
+}
+
+/// Reads `<prefix><name>` attributes (e.g. `data-scramble-speed`) into props for `text`.
+/// Attributes that are absent keep their default values.
+pub(crate) fn props_from_attributes(
+    element: &Element,
+    prefix: &str,
+    text: String,
+) -> Result<UseScrambleProps, JsError> {
+    let name = |attribute: &str| format!("{}{}", prefix, attribute);
+    let mut props = UseScrambleProps {
+        text,
+        ..Default::default()
+    };
+
+    props.max_length = parse_attribute(element, &name("max-length"))?;
+    if let Some(overlength) = parse_attribute(element, &name("overlength"))? {
+        props.overlength = overlength;
+    }
+    if let Some(speed) = parse_attribute(element, &name("speed"))? {
+        props.speed = speed;
+    }
+    props.max_fps = parse_attribute(element, &name("max-fps"))?;
+    if let Some(tick) = parse_attribute(element, &name("tick"))? {
+        props.tick = tick;
+    }
+    if let Some(step) = parse_attribute(element, &name("step"))? {
+        props.step = step;
+    }
+    if let Some(chance) = parse_attribute(element, &name("chance"))? {
+        props.chance = chance;
+    }
+    if let Some(seed) = parse_attribute(element, &name("seed"))? {
+        props.seed = seed;
+    }
+    if let Some(scramble) = parse_attribute(element, &name("scramble"))? {
+        props.scramble = scramble;
+    }
+    if let Some(scramble_jitter) = parse_attribute(element, &name("scramble-jitter"))? {
+        props.scramble_jitter = scramble_jitter;
+    }
+    if let Some(range) = parse_attribute(element, &name("range"))? {
+        props.range = range;
+    }
+    if let Some(ignore) = element.get_attribute(&name("ignore")) {
+        props.ignore = ignore.chars().collect();
+    }
+    if let Some(words) = element.get_attribute(&name("ignore-words")) {
+        props.ignore_words = words
+            .split(',')
+            .map(str::trim)
+            .filter(|word| !word.is_empty())
+            .map(String::from)
+            .collect();
+    }
+    if let Some(overdrive) = element.get_attribute(&name("overdrive")) {
+        // A bare `overdrive` attribute turns the default sweep on
+        props.overdrive = if overdrive.trim().is_empty() {
+            true.into()
+        } else {
+            overdrive.parse().map_err(|e: String| JsError::new(&e))?
+        };
+    }
+    props.overdrive_step = parse_attribute(element, &name("overdrive-step"))?;
+    if let Some(overflow) = parse_flag(element, &name("overflow")) {
+        props.overflow = overflow;
+    }
+    if let Some(pipeline) = element.get_attribute(&name("pipeline")) {
+        // Stage names with their defaults, e.g. `sweep scramble glitch`
+        props.pipeline = pipeline
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|stage| !stage.is_empty())
+            .map(str::parse)
+            .collect::<Result<_, String>>()
+            .map_err(|e| JsError::new(&e))?;
+    }
+    if let Some(placeholder) = element.get_attribute(&name("placeholder-char")) {
+        // Not trimmed, so that a space or a non-breaking space can be the placeholder
+        let placeholder = placeholder.parse().map_err(|e| {
+            JsError::new(&format!(
+                "Invalid placeholder-char attribute \"{}\": {}",
+                placeholder, e
+            ))
+        })?;
+        props.placeholder_char = Some(placeholder);
+    }
+    if let Some(mismatch) = parse_attribute(element, &name("mismatch"))? {
+        props.mismatch = mismatch;
+    }
+    if let Some(whitespace) = parse_attribute(element, &name("whitespace"))? {
+        props.whitespace = whitespace;
+    }
+    if let Some(dir) = parse_attribute(element, &name("dir"))? {
+        props.dir = dir;
+    }
+    props.transform = parse_attribute(element, &name("transform"))?;
+    props.mask = parse_attribute(element, &name("mask"))?;
+    if let Some(hover_replay) = parse_flag(element, &name("hover-replay")) {
+        props.hover_replay = hover_replay;
+    }
+    if let Some(scroll_scrub) = parse_flag(element, &name("scroll-scrub")) {
+        props.scroll_scrub = scroll_scrub;
+    }
+    if let Some(hover_scrub) = parse_flag(element, &name("hover-scrub")) {
+        props.hover_scrub = hover_scrub;
+    }
+    if let Some(radius) = parse_attribute(element, &name("hover-scrub-radius"))? {
+        props.hover_scrub_radius = radius;
+    }
+    if let Some(settle) = parse_attribute(element, &name("hover-scrub-settle"))? {
+        props.hover_scrub_settle = settle;
+    }
+    if let Some(css_vars) = parse_flag(element, &name("css-vars")) {
+        props.css_vars = css_vars;
+    }
+    if let Some(triggers) = element.get_attribute(&name("triggers")) {
+        // A space or comma separated list, e.g. `focus click`
+        props.triggers = triggers
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|trigger| !trigger.is_empty())
+            .map(str::parse)
+            .collect::<Result<_, String>>()
+            .map_err(|e| JsError::new(&e))?;
+    }
+    if let Some(spoiler) = parse_flag(element, &name("spoiler")) {
+        props.spoiler = spoiler;
+    }
+    props.conceal_after = parse_attribute(element, &name("conceal-after"))?;
+    props.play_on_mount = parse_flag(element, &name("play-on-mount"));
+    if let Some(pause_when_hidden) = parse_flag(element, &name("pause-when-hidden")) {
+        props.pause_when_hidden = pause_when_hidden;
+    }
+    if let Some(pause_on_blur) = parse_flag(element, &name("pause-on-blur")) {
+        props.pause_on_blur = pause_on_blur;
+    }
+    if let Some(shared_target) = parse_attribute(element, &name("shared-target"))? {
+        props.shared_target = shared_target;
+    }
+    if let Some(idle) = parse_flag(element, &name("idle")) {
+        props.idle = idle;
+    }
+    if let Some(virtualize) = parse_flag(element, &name("virtualize")) {
+        props.virtualize = virtualize;
+    }
+    if let Some(spans) = parse_flag(element, &name("spans")) {
+        props.spans = spans;
+    }
+    if let Some(reduced_motion) = parse_attribute(element, &name("reduced-motion"))? {
+        props.reduced_motion = reduced_motion;
+    }
+    if let Some(a11y) = parse_flag(element, &name("a11y")) {
+        props.a11y = a11y;
+    }
+    props.aria_live = parse_attribute(element, &name("aria-live"))?;
+    if let Some(announce) = parse_flag(element, &name("announce-on-complete")) {
+        props.announce_on_complete = announce;
+    }
+    if let Some(debug) = parse_flag(element, &name("debug")) {
+        props.debug = debug;
+    }
+    if let Some(overlay) = parse_flag(element, &name("overlay")) {
+        props.overlay = overlay;
+    }
+    if let Some(selector) = element.get_attribute(&name("content-selector")) {
+        props.content_selector = Some(selector.trim().to_string());
+    }
+    if let Some(target) = element.get_attribute(&name("target")) {
+        props.target = target.trim().to_string().into();
+    }
+
+    props.validate()?;
+    Ok(props)
+}
@@ -0,0 +1,76 @@
+//! Right-to-left text.
+//!
+//! Scrambled characters take part in bidi layout like any others: Latin letters drawn in the
+//! middle of Hebrew or Arabic start LTR runs of their own, and the words around them jump
+//! about from frame to frame. In RTL text, the positions that aren't strong LTR draw letters
+//! of the text's own script instead.
+//!
+//! Scripts are told apart by Unicode block, which is close enough to the bidi classes for
+//! picking characters to draw.
+
+use std::ops::RangeInclusive;
+
+pub(crate) const HEBREW_LETTERS: &[RangeInclusive<u32>] = &[0x05D0..=0x05EA];
+
+/// Arabic letters, without the tatweel between them.
+pub(crate) const ARABIC_LETTERS: &[RangeInclusive<u32>] = &[0x0621..=0x063A, 0x0641..=0x064A];
+
+/// Blocks of right-to-left scripts, with the letters drawn for each.
+const RTL_BLOCKS: &[(RangeInclusive<u32>, &[RangeInclusive<u32>])] = &[
+    (0x0590..=0x05FF, HEBREW_LETTERS),
+    (0x0600..=0x06FF, ARABIC_LETTERS),
+    // Syriac
+    (0x0700..=0x074F, &[0x0710..=0x072F]),
+    (0x0750..=0x077F, ARABIC_LETTERS),
+    // Thaana
+    (0x0780..=0x07BF, &[0x0780..=0x07A5]),
+    // N'Ko
+    (0x07C0..=0x07FF, &[0x07CA..=0x07EA]),
+    (0x0800..=0x089F, &[]),
+    (0x08A0..=0x08FF, ARABIC_LETTERS),
+    (0xFB1D..=0xFB4F, HEBREW_LETTERS),
+    (0xFB50..=0xFDFF, ARABIC_LETTERS),
+    (0xFE70..=0xFEFF, ARABIC_LETTERS),
+    (0x10800..=0x10FFF, &[]),
+    (0x1E800..=0x1EFFF, &[]),
+];
+
+/// Block of the right-to-left script `c` belongs to, if any.
+fn rtl_block(c: char) -> Option<&'static (RangeInclusive<u32>, &'static [RangeInclusive<u32>])> {
+    RTL_BLOCKS
+        .iter()
+        .find(|(block, _)| block.contains(&(c as u32)))
+}
+
+/// Whether `c` is a letter of a right-to-left script.
+pub(crate) fn is_strong_rtl(c: char) -> bool {
+    c.is_alphabetic() && rtl_block(c).is_some()
+}
+
+/// Whether `c` is a letter of a left-to-right script, which starts an LTR run in RTL text.
+pub(crate) fn is_strong_ltr(c: char) -> bool {
+    c.is_alphabetic() && rtl_block(c).is_none()
+}
+
+/// Whether `text` reads right to left, going by its first letter like `dir="auto"`.
+pub(crate) fn is_rtl_text(text: &str) -> bool {
+    text.chars()
+        .find(|c| c.is_alphabetic())
+        .is_some_and(is_strong_rtl)
+}
+
+/// Letters of the script of the first right-to-left letter in `text`, or `None` when it has
+/// none, or none this module knows letters for.
+pub(crate) fn rtl_letters(text: &str) -> Option<Vec<char>> {
+    let (_, letters) = text
+        .chars()
+        .filter(|&c| is_strong_rtl(c))
+        .find_map(rtl_block)
+        .filter(|(_, letters)| !letters.is_empty())?;
+    Some(
+        letters
+            .iter()
+            .flat_map(|range| range.clone().filter_map(char::from_u32))
+            .collect(),
+    )
+}
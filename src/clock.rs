@@ -0,0 +1,71 @@
+//! Time keeping shared by everything that drives an engine. The scheduler, the worker, the
+//! hooks and the CLI all turn timestamps into frame deltas with a [`Clock`], and
+//! [`ScrambleEngine::advance`](crate::ScrambleEngine::advance) turns those deltas into ticks,
+//! so every driver reveals text at the same pace.
+
+/// Longest gap between two timestamps that counts in full, in milliseconds. Browsers throttle
+/// timers in background tabs and workers to about once a second, and the engine catches up on
+/// the ticks those gaps skip. Longer gaps, such as a suspended tab or a paused debugger, only
+/// count this much, so the reveal doesn't jump to its end.
+pub const MAX_CATCH_UP_MS: f64 = 1000.0;
+
+/// Turns timestamps into the time elapsed between frames.
+///
+/// Timestamps are in milliseconds from any origin, e.g. those passed to `requestAnimationFrame`
+/// callbacks, so tests can feed it made up ones.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Clock {
+    last_time: Option<f64>,
+}
+
+impl Clock {
+    pub fn new() -> Clock {
+        Clock::default()
+    }
+
+    /// The current time in milliseconds: `performance.now()` in browsers and workers, or
+    /// `Date.now()` where it is missing, and the time since the first call in native builds.
+    #[cfg(all(feature = "web", target_arch = "wasm32"))]
+    pub fn now() -> f64 {
+        use wasm_bindgen::JsCast;
+
+        js_sys::Reflect::get(&js_sys::global(), &"performance".into())
+            .ok()
+            .and_then(|performance| performance.dyn_into::<web_sys::Performance>().ok())
+            .map_or_else(js_sys::Date::now, |performance| performance.now())
+    }
+
+    /// The current time in milliseconds: `performance.now()` in browsers and workers, or
+    /// `Date.now()` where it is missing, and the time since the first call in native builds.
+    #[cfg(not(all(feature = "web", target_arch = "wasm32")))]
+    pub fn now() -> f64 {
+        use std::sync::OnceLock;
+        use std::time::Instant;
+
+        static START: OnceLock<Instant> = OnceLock::new();
+        START.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0
+    }
+
+    /// Milliseconds since the previous timestamp, or 0 for the first one. Timestamps that go
+    /// back in time count as 0, and gaps are capped at [`MAX_CATCH_UP_MS`].
+    pub fn delta_to(&mut self, timestamp: f64) -> f64 {
+        let Some(last) = self.last_time else {
+            self.last_time = Some(timestamp);
+            return 0.0;
+        };
+        self.last_time = Some(last.max(timestamp));
+        (timestamp - last).clamp(0.0, MAX_CATCH_UP_MS)
+    }
+
+    /// Milliseconds since the previous call, like [`delta_to`](Self::delta_to) the current
+    /// time.
+    pub fn delta(&mut self) -> f64 {
+        self.delta_to(Clock::now())
+    }
+
+    /// Forgets the previous timestamp, so time spent until the next one doesn't count, e.g.
+    /// while paused.
+    pub fn reset(&mut self) {
+        self.last_time = None;
+    }
+}
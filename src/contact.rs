@@ -0,0 +1,84 @@
+//! Email addresses and phone numbers kept from scrapers until a visitor asks for them.
+//!
+//! The document only ever holds noise as long as the value: the value itself lives in the
+//! animation's props, and is written out by the reveal a trigger starts. Links get their
+//! `mailto:` or `tel:` href once the reveal completes, so there is none to scrape either.
+
+use wasm_bindgen::prelude::*;
+use web_sys::Element;
+
+use crate::random_int;
+use crate::scramble::{Trigger, UseScrambleProps};
+use crate::web::ScrambleText;
+
+/// Link to `value`: `mailto:` for an email address, `tel:` for a phone number, and `None`
+/// for anything else.
+fn contact_href(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.contains('@') {
+        return Some(format!("mailto:{}", value));
+    }
+    let is_phone = value.chars().any(|c| c.is_ascii_digit())
+        && value
+            .chars()
+            .all(|c| c.is_ascii_digit() || " +-().".contains(c));
+    is_phone.then(|| {
+        let number: String = value
+            .chars()
+            .filter(|&c| c.is_ascii_digit() || c == '+')
+            .collect();
+        format!("tel:{}", number)
+    })
+}
+
+/// Random letters in place of every character of `value` but spaces.
+fn noise(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if c == ' ' {
+                c
+            } else {
+                char::from(random_int(b'a' as i32, b'z' as i32) as u8)
+            }
+        })
+        .collect()
+}
+
+/// Shows noise in `element` in place of `value`, an email address or phone number, and
+/// reveals it on `trigger` ("click" by default). When `element` is a link and `link` isn't
+/// false, its href is removed and set to a `mailto:` or `tel:` link once the reveal
+/// completes, so the first click reveals and the next one follows it.
+///
+/// The value isn't readable by screen readers either until revealed.
+#[wasm_bindgen]
+pub fn obfuscate_contact(
+    element: Element,
+    value: String,
+    trigger: Option<String>,
+    link: Option<bool>,
+) -> Result<ScrambleText, JsError> {
+    let trigger = match trigger {
+        Some(trigger) => trigger.parse().map_err(|e: String| JsError::new(&e))?,
+        None => Trigger::Click,
+    };
+    let href = contact_href(&value).filter(|_| link != Some(false) && element.local_name() == "a");
+    if href.is_some() {
+        element
+            .remove_attribute("href")
+            .map_err(|e| JsError::new(&format!("Failed to remove the href: {:?}", e)))?;
+    }
+
+    element.set_text_content(Some(&noise(&value)));
+    let props = UseScrambleProps {
+        text: value,
+        triggers: vec![trigger],
+        play_on_mount: Some(false),
+        ..Default::default()
+    };
+    let scramble = ScrambleText::with_props(element.into(), props)?;
+    if let Some(href) = href {
+        scramble.link_on_reveal(href);
+    }
+    Ok(scramble)
+}
@@ -0,0 +1,108 @@
+//! Dioxus port of the react `useScramble` hook.
+//!
+//! ```ignore
+//! let props = UseScrambleProps { text: "Hello".into(), ..Default::default() };
+//! let scramble = use_scramble(props).expect("valid props");
+//! rsx! {
+//!     span {
+//!         onmounted: scramble.onmounted,
+//!         onmouseenter: move |_| scramble.replay.call(()),
+//!         "{scramble.text}"
+//!     }
+//! }
+//! ```
+//!
+//! Frames are computed by the [`ScrambleEngine`] in Rust and timed by `requestAnimationFrame`
+//! through `document::eval`, so the hook works in the web and desktop (WebView) renderers
+//! alike and never touches the DOM directly.
+
+use ::dioxus::core::Task;
+use ::dioxus::document;
+use ::dioxus::prelude::*;
+
+use crate::clock::Clock;
+use crate::engine::ScrambleEngine;
+use crate::error::ScrambleError;
+use crate::scramble::UseScrambleProps;
+
+const NEXT_FRAME: &str =
+    "await new Promise((resolve) => requestAnimationFrame(resolve)); return performance.now();";
+
+const PREFERS_REDUCED_MOTION: &str =
+    "return matchMedia('(prefers-reduced-motion: reduce)').matches;";
+
+/// Text signal, mount handler and replay function returned by [`use_scramble`].
+#[derive(Clone, Copy, PartialEq)]
+pub struct UseScramble {
+    /// Text of the current frame, to render inside the element.
+    pub text: ReadSignal<String>,
+
+    /// Plays the animation once the element mounts, unless `play_on_mount` is false.
+    pub onmounted: EventHandler<MountedEvent>,
+
+    /// Plays the animation from the start, cancelling the running one.
+    pub replay: Callback<()>,
+}
+
+/// Runs one animation, drawing every due frame into `text` until the text is revealed.
+async fn animate(mut engine: CopyValue<ScrambleEngine>, mut text: Signal<String>) {
+    let mut props = engine.read().props().clone();
+    let prefers_reduced_motion = document::eval(PREFERS_REDUCED_MOTION)
+        .join::<bool>()
+        .await
+        .unwrap_or(false);
+    if props.reduced_motion.applies(prefers_reduced_motion) {
+        props = props.with_reduced_motion();
+    }
+    // Validated when the engine was created, and reduced motion keeps them valid
+    let _ = engine.write().set_props(props);
+
+    let mut clock = Clock::new();
+    loop {
+        // The renderer has gone away
+        let Ok(time) = document::eval(NEXT_FRAME).join::<f64>().await else {
+            return;
+        };
+        let Some(frame) = engine.write().advance(clock.delta_to(time)) else {
+            continue;
+        };
+        text.set(frame.text);
+        if frame.complete {
+            return;
+        }
+    }
+}
+
+/// Scrambles `props.text` into a signal. Props are read on the first render only; remount the
+/// component, e.g. with a `key`, to change them.
+pub fn use_scramble(props: UseScrambleProps) -> Result<UseScramble, ScrambleError> {
+    let play_on_mount = props.plays_on_mount();
+    let engine = use_hook(|| ScrambleEngine::new(props.clone()).map(CopyValue::new));
+    let text = use_signal(|| props.final_text());
+    let mut task = use_signal(|| None::<Task>);
+
+    let replay = use_callback({
+        let engine = engine.clone();
+        move |()| {
+            let Ok(mut engine) = engine.clone() else {
+                return;
+            };
+            if let Some(task) = task.take() {
+                task.cancel();
+            }
+            engine.write().reset();
+            task.set(Some(spawn(animate(engine, text))));
+        }
+    });
+    let onmounted = use_callback(move |_: MountedEvent| {
+        if play_on_mount {
+            replay.call(());
+        }
+    });
+
+    engine.map(|_| UseScramble {
+        text: text.into(),
+        onmounted,
+        replay,
+    })
+}
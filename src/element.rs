@@ -0,0 +1,203 @@
+//! `<scramble-text>` custom element for framework-free, declarative usage.
+//!
+//! ```html
+//! <scramble-text speed="0.6" scramble="4">Hello</scramble-text>
+//! ```
+//!
+//! The element's text content is the text to reveal. The animation is drawn into a shadow
+//! root, so the light DOM keeps the final text and edits to it re-trigger the animation.
+
+use std::cell::RefCell;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, MutationObserver, MutationObserverInit, ShadowRootInit, ShadowRootMode};
+
+use crate::attributes::{props_from_attributes, PROP_ATTRIBUTES};
+use crate::scramble::{UseScrambleProps, WriteTarget};
+use crate::web::ScrambleText;
+
+#[wasm_bindgen(inline_js = r#"
+export function defineScrambleElement(tagName, observedAttributes, render, release) {
+  if (customElements.get(tagName)) {
+    return false;
+  }
+
+  customElements.define(
+    tagName,
+    class extends HTMLElement {
+      static get observedAttributes() {
+        return observedAttributes;
+      }
+
+      connectedCallback() {
+        this.scheduleRender();
+      }
+
+      disconnectedCallback() {
+        release(this);
+      }
+
+      attributeChangedCallback() {
+        this.scheduleRender();
+      }
+
+      // Coalesce the attribute and connection callbacks fired during upgrade into one render
+      scheduleRender() {
+        if (this.renderPending) {
+          return;
+        }
+        this.renderPending = true;
+        queueMicrotask(() => {
+          this.renderPending = false;
+          if (this.isConnected) {
+            render(this);
+          }
+        });
+      }
+    }
+  );
+  return true;
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = defineScrambleElement, catch)]
+    fn define_scramble_element(
+        tag_name: &str,
+        observed_attributes: js_sys::Array,
+        render: &js_sys::Function,
+        release: &js_sys::Function,
+    ) -> Result<bool, JsValue>;
+}
+
+struct MountedElement {
+    element: Element,
+    scramble: ScrambleText,
+    observer: MutationObserver,
+    _on_mutation: Closure<dyn FnMut()>,
+}
+
+thread_local! {
+    static MOUNTED: RefCell<Vec<MountedElement>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Returns the element inside the shadow root that the animation draws into.
+fn render_target(element: &Element) -> Result<Element, JsValue> {
+    let shadow = match element.shadow_root() {
+        Some(shadow) => shadow,
+        None => element.attach_shadow(&ShadowRootInit::new(ShadowRootMode::Open))?,
+    };
+
+    if let Some(target) = shadow.first_element_child() {
+        return Ok(target);
+    }
+
+    let document = element
+        .owner_document()
+        .ok_or_else(|| JsError::new("Element has no owner document"))?;
+    let target = document.create_element("span")?;
+    target.set_attribute("part", "text")?;
+    shadow.append_child(&target)?;
+    Ok(target)
+}
+
+/// (Re)starts the animation for `element` from its current attributes and text.
+fn render(element: &Element) -> Result<(), JsValue> {
+    let target = render_target(element)?;
+    let text = element
+        .text_content()
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    target.set_text_content(Some(&text));
+
+    // The shadow root only ever shows text content, all of it
+    let props = UseScrambleProps {
+        target: WriteTarget::TextContent,
+        content_selector: None,
+        ..props_from_attributes(element, "", text)?
+    };
+    let play_on_mount = props.plays_on_mount();
+    let mut scramble = ScrambleText::with_props(target.into(), props)?;
+    if play_on_mount {
+        scramble.start()?;
+    }
+
+    MOUNTED.with(|mounted| {
+        let mut mounted = mounted.borrow_mut();
+        if let Some(entry) = mounted.iter_mut().find(|entry| entry.element == *element) {
+            entry.scramble = scramble;
+            return Ok(());
+        }
+
+        // Watch the light DOM so text edits re-trigger the animation
+        let on_mutation = {
+            let element = element.clone();
+            Closure::<dyn FnMut()>::new(move || {
+                if let Err(e) = render(&element) {
+                    web_sys::console::error_1(&e);
+                }
+            })
+        };
+        let observer = MutationObserver::new(on_mutation.as_ref().unchecked_ref())?;
+        let options = MutationObserverInit::new();
+        options.set_child_list(true);
+        options.set_character_data(true);
+        options.set_subtree(true);
+        observer.observe_with_options(element, &options)?;
+
+        mounted.push(MountedElement {
+            element: element.clone(),
+            scramble,
+            observer,
+            _on_mutation: on_mutation,
+        });
+        Ok(())
+    })
+}
+
+fn release(element: &Element) {
+    MOUNTED.with(|mounted| {
+        let mut mounted = mounted.borrow_mut();
+        if let Some(index) = mounted.iter().position(|entry| entry.element == *element) {
+            let entry = mounted.remove(index);
+            entry.observer.disconnect();
+        }
+    });
+}
+
+/// Registers the `<scramble-text>` custom element, or `tag_name` if given. Attributes map onto the
+/// scramble props (`max-length`, `overlength`, `speed`, `max-fps`, `tick`, `step`, `chance`,
+/// `seed`, `scramble`, `scramble-jitter`, `range`, `ignore`, `ignore-words`, `overdrive`,
+/// `overdrive-step`, `overflow`, `pipeline`, `placeholder-char`, `mismatch`, `whitespace`, `dir`,
+/// `transform`, `mask`, `hover-replay`, `triggers`, `spoiler`, `conceal-after`, `scroll-scrub`,
+/// `hover-scrub`, `hover-scrub-radius`, `hover-scrub-settle`, `css-vars`, `shared-target`,
+/// `play-on-mount`, `pause-when-hidden`, `pause-on-blur`, `idle`, `virtualize`, `spans`,
+/// `reduced-motion`, `a11y`, `aria-live`, `announce-on-complete`, `debug`, `overlay`).
+/// Registering twice is a no-op.
+#[wasm_bindgen]
+pub fn define_custom_element(tag_name: Option<String>) -> Result<(), JsError> {
+    let tag_name = tag_name.unwrap_or_else(|| "scramble-text".to_string());
+
+    let render = Closure::<dyn FnMut(Element)>::new(|element: Element| {
+        if let Err(e) = render(&element) {
+            web_sys::console::error_1(&e);
+        }
+    });
+    let release = Closure::<dyn FnMut(Element)>::new(|element: Element| release(&element));
+    let observed_attributes = PROP_ATTRIBUTES.iter().map(|name| JsValue::from_str(name));
+
+    let defined = define_scramble_element(
+        &tag_name,
+        observed_attributes.collect(),
+        render.as_ref().unchecked_ref(),
+        release.as_ref().unchecked_ref(),
+    )
+    .map_err(|e| JsError::new(&format!("Failed to define <{}>: {:?}", tag_name, e)))?;
+
+    // The element class holds on to the callbacks for the lifetime of the page
+    if defined {
+        render.forget();
+        release.forget();
+    }
+    Ok(())
+}
@@ -0,0 +1,96 @@
+//! Emoji sequences, scrambled as one unit.
+//!
+//! An emoji can span several characters: skin tone modifiers, variation selectors, keycaps,
+//! flags made of two regional indicators, and people or families joined with zero width
+//! joiners. Drawn a character at a time, a sequence falls apart into its pieces, with random
+//! characters showing as tofu in between. The engine draws each sequence at its first
+//! character instead, scrambling through other emoji and settling on the whole sequence.
+
+use std::ops::RangeInclusive;
+
+const ZWJ: char = '\u{200D}';
+const VARIATION_SELECTOR: char = '\u{FE0F}';
+const KEYCAP: char = '\u{20E3}';
+const SKIN_TONES: RangeInclusive<char> = '\u{1F3FB}'..='\u{1F3FF}';
+const TAGS: RangeInclusive<char> = '\u{E0020}'..='\u{E007F}';
+const REGIONAL_INDICATORS: RangeInclusive<char> = '\u{1F1E6}'..='\u{1F1FF}';
+
+/// Start of the blocks whose symbols show as emoji on their own.
+const EMOJI_PLANE_START: u32 = 0x1F000;
+
+/// How a character takes part in the emoji sequences of a text.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Unit {
+    /// A character of its own.
+    Char,
+    /// The first character of an emoji, with the number of characters in its sequence.
+    Emoji(usize),
+    /// A character after the first of an emoji sequence.
+    Joined,
+}
+
+fn is_pictographic(c: char) -> bool {
+    matches!(c as u32, 0x2300..=0x23FF | 0x2600..=0x27BF | 0x2B00..=0x2BFF | 0x1F000..=0x1FAFF)
+        && !SKIN_TONES.contains(&c)
+        && !REGIONAL_INDICATORS.contains(&c)
+}
+
+/// Whether `c` belongs to the emoji before it.
+fn is_modifier(c: char) -> bool {
+    c == VARIATION_SELECTOR || c == KEYCAP || SKIN_TONES.contains(&c) || TAGS.contains(&c)
+}
+
+/// Number of characters in the emoji starting at `chars[0]`, or 0 when no emoji does.
+fn emoji_len(chars: &[char]) -> usize {
+    let Some(&first) = chars.first() else {
+        return 0;
+    };
+    if REGIONAL_INDICATORS.contains(&first) {
+        let pair = chars
+            .get(1)
+            .is_some_and(|c| REGIONAL_INDICATORS.contains(c));
+        return if pair { 2 } else { 1 };
+    }
+
+    let keycap = first.is_ascii_digit() || first == '#' || first == '*';
+    if !keycap && !is_pictographic(first) {
+        return 0;
+    }
+    let mut len = 1;
+    loop {
+        match chars.get(len) {
+            Some(&c) if is_modifier(c) => len += 1,
+            Some(&ZWJ) if chars.get(len + 1).is_some_and(|&c| is_pictographic(c)) => len += 2,
+            _ => break,
+        }
+    }
+
+    // Symbols such as `☀` are only emoji with a variation selector, and digits with a keycap
+    let bare_symbol = len == 1 && (first as u32) < EMOJI_PLANE_START;
+    if bare_symbol || (keycap && !chars[..len].contains(&KEYCAP)) {
+        return 0;
+    }
+    len
+}
+
+/// The unit of each of `chars`, or nothing when they hold no emoji.
+pub(crate) fn units(chars: &[char]) -> Vec<Unit> {
+    let may_have_emoji = chars
+        .iter()
+        .any(|&c| c as u32 >= EMOJI_PLANE_START || c == VARIATION_SELECTOR || c == KEYCAP);
+    if !may_have_emoji {
+        return Vec::new();
+    }
+
+    let mut units = Vec::with_capacity(chars.len());
+    while units.len() < chars.len() {
+        match emoji_len(&chars[units.len()..]) {
+            0 => units.push(Unit::Char),
+            len => {
+                units.push(Unit::Emoji(len));
+                units.extend(std::iter::repeat_n(Unit::Joined, len - 1));
+            }
+        }
+    }
+    units
+}
@@ -0,0 +1,1336 @@
+//! Scramble state machine shared by the wasm bindings and Rust-native consumers.
+//!
+//! The engine owns no timers and never touches the DOM: every call to
+//! [`ScrambleEngine::tick`] advances the animation by one frame and returns the text to draw,
+//! while [`ScrambleEngine::advance`] lets game loops or tickers feed it elapsed time instead.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Range;
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
+use crate::bidi;
+use crate::clock::MAX_CATCH_UP_MS;
+use crate::emoji::{self, Unit};
+use crate::error::ScrambleError;
+#[cfg(feature = "web")]
+use crate::options::props_from_js;
+use crate::plugin::FramePlugin;
+use crate::random::RandomBlock;
+use crate::scramble::{
+    Direction, Mismatch, Overlength, RangeOrCharCodes, Stage, UseScrambleProps, DEFAULT_STAGE,
+};
+use crate::scripts;
+
+/// Surrogate code points, which are not characters, and which ranges skip over.
+const SURROGATES: std::ops::Range<u32> = 0xD800..0xE000;
+
+/// Ranges up to this many code points are flattened into a table of characters.
+const MAX_TABLE_LEN: u32 = 4096;
+
+/// Characters a range draws from, resolved once per range so that drawing a character is a
+/// single lookup.
+#[derive(Clone, Debug)]
+enum CharTable {
+    Chars(Vec<char>),
+    /// A range too large to flatten, as `len` code points from `min` with the surrogates
+    /// left out.
+    Span {
+        min: u32,
+        len: u32,
+    },
+}
+
+impl CharTable {
+    /// Builds the table for a validated range, and `text` for `"auto"`. An empty table draws
+    /// `_`.
+    fn new(range: &RangeOrCharCodes, text: &str) -> CharTable {
+        match *range {
+            RangeOrCharCodes::Range(min, max) if max >= min => {
+                // Validation keeps both bounds outside of the surrogates
+                let gap = if min < SURROGATES.start && max >= SURROGATES.end {
+                    SURROGATES.len() as u32
+                } else {
+                    0
+                };
+                let len = max - min + 1 - gap;
+                if len <= MAX_TABLE_LEN {
+                    CharTable::Chars((min..=max).filter_map(char::from_u32).collect())
+                } else {
+                    CharTable::Span { min, len }
+                }
+            }
+            RangeOrCharCodes::Codes(ref codes) => CharTable::Chars(
+                codes
+                    .iter()
+                    .filter_map(|&code| char::from_u32(code))
+                    .collect(),
+            ),
+            RangeOrCharCodes::Auto => CharTable::Chars(scripts::pool(text)),
+            _ => CharTable::Chars(Vec::new()),
+        }
+    }
+
+    /// Whether the table may draw left-to-right letters. Spans are too large to check.
+    fn has_strong_ltr(&self) -> bool {
+        match self {
+            CharTable::Chars(chars) => chars.iter().any(|&c| bidi::is_strong_ltr(c)),
+            CharTable::Span { .. } => true,
+        }
+    }
+
+    fn random_char(&self, random: &mut RandomBlock) -> char {
+        match self {
+            CharTable::Chars(chars) if !chars.is_empty() => {
+                chars[random.int(0, chars.len() as i32 - 1) as usize]
+            }
+            CharTable::Chars(_) => '_',
+            &CharTable::Span { min, len } => {
+                let mut code = min + random.int(0, len as i32 - 1) as u32;
+                if min < SURROGATES.start && code >= SURROGATES.start {
+                    code += SURROGATES.len() as u32;
+                }
+                char::from_u32(code).unwrap_or('_')
+            }
+        }
+    }
+}
+
+/// Emoji drawn for scrambled emoji, from the emoticons block, which all show as emoji without
+/// a variation selector.
+const EMOJI_CHARS: CharTable = CharTable::Span {
+    min: 0x1F600,
+    len: 0x50,
+};
+
+/// State of one character position. Serialized as null, the number of scrambles left, or the
+/// character showing.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ScrambleControl {
+    /// Not drawn yet, or drawn as the `placeholder_char`.
+    Pending,
+    /// Drawn as random characters for this many more frames, then settling on its final
+    /// character.
+    Scrambling(i32),
+    /// Showing this character: its final one, or one such as the overdrive sweep's.
+    Settled(char),
+}
+
+impl fmt::Display for ScrambleControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScrambleControl::Pending => Ok(()),
+            ScrambleControl::Scrambling(n) => write!(f, "{}", n),
+            ScrambleControl::Settled(c) => write!(f, "{}", c),
+        }
+    }
+}
+
+/// A single drawn frame.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Frame {
+    /// Text to display for this frame
+    pub text: String,
+
+    /// Character positions that show their final character
+    #[serde(default)]
+    pub settled: Vec<usize>,
+
+    /// Character positions that landed on their final character in this frame, e.g. to play
+    /// a sound for each
+    #[serde(default)]
+    pub newly_settled: Vec<usize>,
+
+    /// Character positions that drew a new random character in this frame
+    #[serde(default)]
+    pub scrambled: Vec<usize>,
+
+    /// Index in `text`, in characters, where each position of the text is drawn. A scrambled
+    /// emoji sequence or a position not drawn yet takes up fewer characters than the text, so
+    /// this is what splits a frame by position. Empty when a plugin rewrote the text.
+    #[serde(default)]
+    pub offsets: Vec<usize>,
+
+    /// True when `text` matches the target text and the animation is over
+    #[serde(default)]
+    pub complete: bool,
+}
+
+/// A frame captured by [`record_frames`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    /// When the frame is drawn, in milliseconds from the start of the run
+    pub time: f64,
+
+    #[serde(flatten)]
+    pub frame: Frame,
+}
+
+/// Every frame of a run, drawn at the pace set by `speed`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Recording {
+    pub frames: Vec<RecordedFrame>,
+
+    /// Time from the start of the run until its last frame, in milliseconds
+    #[serde(default)]
+    pub duration: f64,
+
+    /// False when the run was cut short by the frame limit, or paused by a speed of 0
+    #[serde(default)]
+    pub complete: bool,
+}
+
+/// Snapshot of a run, taken by [`ScrambleEngine::export_state`] and resumed by
+/// [`ScrambleEngine::import_state`] on an engine revealing the same text.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EngineState {
+    /// Text the run reveals
+    pub text: String,
+
+    /// State of each position of the current chunk
+    pub control: Vec<ScrambleControl>,
+
+    /// Number of frames drawn since the run started
+    pub frame_count: u32,
+
+    /// Reveal front within the current chunk
+    pub scramble_index: usize,
+
+    /// Overdrive sweep position within the current chunk
+    pub overdrive_index: usize,
+
+    /// Index of the current `pipeline` stage
+    #[serde(default)]
+    pub stage: usize,
+
+    /// Frames drawn since the current stage started
+    #[serde(default)]
+    pub stage_frames: u32,
+
+    /// Characters of the text before the current chunk, with `overlength` set to "chunk"
+    #[serde(default)]
+    pub chunk_start: usize,
+
+    /// Time fed to `advance` since the last drawn frame, in milliseconds
+    #[serde(default)]
+    pub elapsed: f64,
+
+    #[serde(default)]
+    pub complete: bool,
+
+    /// Position in the noise of an engine seeded by `seed_random`. Unseeded engines draw
+    /// fresh noise after an import.
+    #[serde(default)]
+    pub random: Option<RandomState>,
+}
+
+/// Position in the stream of a seeded generator.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RandomState {
+    pub seed: u64,
+
+    /// Values drawn since seeding
+    pub drawn: u64,
+}
+
+/// Runs an animation to its end without a clock, capturing at most `max_frames` frames along
+/// with the time each is drawn at. The timing is exact, so recordings of the same props only
+/// differ in their random characters.
+pub fn record_frames(
+    props: UseScrambleProps,
+    max_frames: usize,
+) -> Result<Recording, ScrambleError> {
+    let mut engine = ScrambleEngine::new(props)?;
+    let mut frames = Vec::new();
+    if engine.props.speed > 0.0 {
+        let interval = 1000.0 / (60.0 * engine.props.speed as f64);
+        while frames.len() < max_frames && !engine.is_complete() {
+            frames.push(RecordedFrame {
+                time: frames.len() as f64 * interval,
+                frame: engine.tick(),
+            });
+        }
+    }
+
+    Ok(Recording {
+        duration: frames.last().map_or(0.0, |frame| frame.time),
+        complete: engine.is_complete(),
+        frames,
+    })
+}
+
+/// The text an animation shows before its first frame: fully scrambled with `overflow`, and
+/// empty without it. Server-rendered pages can ship it in place of the final text, so the
+/// final text does not flash before the animation starts.
+pub fn initial_frame(props: UseScrambleProps) -> Result<String, ScrambleError> {
+    let mut frame = Frame::default();
+    ScrambleEngine::new(props)?.draw(&mut frame);
+    Ok(frame.text)
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Marks the characters of whole-word occurrences of `words` in `chars`: those not run into
+/// by a letter, digit or underscore on either side. Empty when there are none.
+fn whole_words(chars: &[char], words: &[String]) -> Vec<bool> {
+    let is_word_char = |c: &char| c.is_alphanumeric() || *c == '_';
+    let mut kept = Vec::new();
+    for word in words {
+        let word: Vec<char> = word.chars().collect();
+        if word.is_empty() || word.len() > chars.len() {
+            continue;
+        }
+        for start in 0..=chars.len() - word.len() {
+            let end = start + word.len();
+            let bounded = (start == 0 || !is_word_char(&chars[start - 1]))
+                && !chars.get(end).is_some_and(is_word_char);
+            if bounded && chars[start..end] == word[..] {
+                kept.resize(chars.len(), false);
+                kept[start..end].fill(true);
+            }
+        }
+    }
+    kept
+}
+
+/// Server-rendered `<tag>` showing the initial frame, with the final text in the
+/// `data-scramble-final` attribute that `ScrambleText.hydrate()` reads. Other props are
+/// not written; add them as `data-scramble-*` attributes where they differ from the defaults.
+pub fn hydration_markup(tag: &str, props: UseScrambleProps) -> Result<String, ScrambleError> {
+    let text = escape_html(&props.text);
+    let frame = escape_html(&initial_frame(props)?);
+    Ok(format!(
+        r#"<{tag} data-scramble-final="{text}">{frame}</{tag}>"#
+    ))
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+pub struct ScrambleEngine {
+    props: UseScrambleProps,
+
+    /// Text revealed: `props.text` after its `transform` and `mask`, if any
+    text: String,
+
+    /// Characters of the text, or of its current chunk, so positions are indexed directly
+    /// rather than by walking the string
+    chars: Vec<char>,
+
+    /// How each of `chars` takes part in emoji sequences, empty when there are none
+    units: Vec<Unit>,
+
+    /// Which of `chars` are part of one of `props.ignore_words`, empty when none are
+    kept: Vec<bool>,
+
+    /// Characters of the text before the current chunk, with `overlength` set to "chunk"
+    chunk_start: usize,
+
+    /// Byte offset of the current chunk in the text
+    chunk_offset: usize,
+
+    /// Length of the text in characters, or of the part of it revealed when truncated
+    text_len: usize,
+
+    /// Characters drawn for scrambled positions, built from `props.range`
+    random_chars: CharTable,
+
+    /// Letters drawn in right-to-left text in place of `random_chars`, when those could start
+    /// a left-to-right run
+    rtl_chars: Option<CharTable>,
+
+    /// Random numbers for the current frame
+    random: RandomBlock,
+
+    /// Scramble controller, one entry per character position
+    control: Vec<ScrambleControl>,
+
+    /// Number of frames drawn since the last reset
+    step: i32,
+
+    /// Reveal front: characters before this index have been handed to the randomizer
+    scramble_index: usize,
+
+    /// Overdrive sweep position
+    overdrive_index: usize,
+
+    /// Index of the current `pipeline` stage
+    stage: usize,
+
+    /// Frames drawn since the current stage started
+    stage_frames: u32,
+
+    /// Time accumulated by `advance` since the last drawn frame, in milliseconds
+    elapsed: f64,
+
+    /// Characters being looked at, set by `set_visible_range`. The others settle at once
+    visible: Option<Range<usize>>,
+
+    /// Post-processors of every drawn frame, in the order they were added
+    plugins: Vec<Box<dyn FramePlugin>>,
+
+    complete: bool,
+}
+
+impl ScrambleEngine {
+    pub fn new(props: UseScrambleProps) -> Result<ScrambleEngine, ScrambleError> {
+        props.validate()?;
+
+        let mut engine = ScrambleEngine {
+            text: props.final_text(),
+            chars: Vec::new(),
+            units: Vec::new(),
+            kept: Vec::new(),
+            chunk_start: 0,
+            chunk_offset: 0,
+            text_len: 0,
+            random_chars: CharTable::new(&props.range, &props.text),
+            rtl_chars: None,
+            random: RandomBlock::default(),
+            props,
+            control: Vec::new(),
+            step: 0,
+            scramble_index: 0,
+            overdrive_index: 0,
+            stage: 0,
+            stage_frames: 0,
+            elapsed: 0.0,
+            visible: None,
+            plugins: Vec::new(),
+            complete: false,
+        };
+        engine.load_chunk(0, 0);
+        engine.load_rtl_chars();
+        engine.reset();
+        Ok(engine)
+    }
+
+    pub fn props(&self) -> &UseScrambleProps {
+        &self.props
+    }
+
+    /// Adds `plugin`, which changes every frame drawn from the next one on.
+    pub fn add_plugin(&mut self, plugin: impl FramePlugin + 'static) {
+        self.plugins.push(Box::new(plugin));
+    }
+
+    /// Replaces this engine with `engine`, e.g. for a new run, keeping the plugins.
+    #[cfg(feature = "web")]
+    pub(crate) fn replace(&mut self, mut engine: ScrambleEngine) {
+        engine.plugins = std::mem::take(&mut self.plugins);
+        *self = engine;
+    }
+
+    /// Draw noise from a generator seeded with `seed` from here on, so that engines seeded
+    /// alike with the same props draw the same frames, e.g. for snapshot tests.
+    pub fn seed_random(&mut self, seed: u64) {
+        self.random.seed(seed);
+    }
+
+    /// Snapshot of the run, which [`import_state`](Self::import_state) resumes from, e.g.
+    /// after a page navigation or to step back while debugging.
+    pub fn export_state(&self) -> EngineState {
+        EngineState {
+            text: self.props.text.clone(),
+            control: self.control.clone(),
+            frame_count: self.frame_count(),
+            scramble_index: self.scramble_index,
+            overdrive_index: self.overdrive_index,
+            stage: self.stage,
+            stage_frames: self.stage_frames,
+            chunk_start: self.chunk_start,
+            elapsed: self.elapsed,
+            complete: self.complete,
+            random: self.random.state(),
+        }
+    }
+
+    /// Resume the run `state` was exported from, returning a frame showing where it was: its
+    /// settled characters, and fresh noise for those still scrambling. Seeded engines go on to
+    /// draw the same frames as the exporting engine would have.
+    pub fn import_state(&mut self, state: &EngineState) -> Result<Frame, ScrambleError> {
+        if state.text != self.props.text || state.chunk_start > self.text_len {
+            return Err(ScrambleError::StateMismatch);
+        }
+
+        // Drawing moves the run on, so the state is applied again afterwards. The noise is
+        // restored last, so that what follows matches the exporting engine.
+        self.apply_state(state);
+        let mut frame = Frame::default();
+        self.draw(&mut frame);
+        frame.complete = state.complete;
+        self.apply_state(state);
+        if let Some(random) = &state.random {
+            self.random.restore(random);
+        }
+        Ok(frame)
+    }
+
+    fn apply_state(&mut self, state: &EngineState) {
+        let offset = self
+            .text
+            .char_indices()
+            .nth(state.chunk_start)
+            .map_or(self.text.len(), |(offset, _)| offset);
+        self.load_chunk(state.chunk_start, offset);
+        self.control = state.control.clone();
+        self.step = state.frame_count as i32;
+        self.scramble_index = state.scramble_index.min(self.control.len());
+        self.overdrive_index = state.overdrive_index;
+        self.stage = state.stage;
+        self.stage_frames = state.stage_frames;
+        self.elapsed = state.elapsed;
+        self.complete = state.complete;
+    }
+
+    /// Index of the first character the reveal hasn't reached yet.
+    pub fn reveal_front(&self) -> usize {
+        self.chunk_start + self.scramble_index
+    }
+
+    /// Scrambles left for each character as of the last drawn frame: `Some(0)` for characters
+    /// showing their final or overdrive character, `None` for those not drawn yet.
+    pub fn scramble_counts(&self) -> Vec<Option<u32>> {
+        let counts = self.control.iter().map(|control| match control {
+            ScrambleControl::Scrambling(n) => Some((*n).max(0) as u32),
+            ScrambleControl::Settled(_) => Some(0),
+            ScrambleControl::Pending => None,
+        });
+        std::iter::repeat_n(Some(0), self.chunk_start)
+            .chain(counts)
+            .collect()
+    }
+
+    /// State of each character position of the current chunk, which is the whole text unless
+    /// `overlength` is "chunk", as of the last drawn frame.
+    pub fn controls(&self) -> &[ScrambleControl] {
+        &self.control
+    }
+
+    /// Sets the state of position `index` of the current chunk, e.g. to settle a character
+    /// early or scramble it again. It is drawn that way from the next frame. Positions past
+    /// the end of the chunk are ignored.
+    pub fn set_control(&mut self, index: usize, control: ScrambleControl) {
+        if let Some(current) = self.control.get_mut(index) {
+            *current = control;
+        }
+    }
+
+    /// Scrambles the characters of `range` of the text that show their final character again
+    /// for `frames` frames, after which they settle back, e.g. those under the pointer. Ignored
+    /// characters and positions outside the current chunk are left alone. A complete animation
+    /// draws frames again until they have settled. Returns whether any character scrambles.
+    pub fn rescramble(&mut self, range: Range<usize>, frames: u32) -> bool {
+        let start = range.start.saturating_sub(self.chunk_start);
+        let end = range
+            .end
+            .saturating_sub(self.chunk_start)
+            .min(self.control.len());
+        let scramble = ScrambleControl::Scrambling(frames.clamp(1, i32::MAX as u32) as i32);
+        let mut rescrambled = false;
+        for i in start..end {
+            let control = self.control[i];
+            if control != ScrambleControl::Settled(self.chars[i]) {
+                continue;
+            }
+            self.control[i] = self.set_if_not_ignored(i, &control, scramble);
+            rescrambled |= self.control[i] == scramble;
+        }
+        if rescrambled {
+            self.complete = false;
+        }
+        rescrambled
+    }
+
+    /// Replace the props without rewinding, e.g. to change `speed` or `range` mid-run. They
+    /// apply from the next frame.
+    pub fn set_props(&mut self, props: UseScrambleProps) -> Result<(), ScrambleError> {
+        props.validate()?;
+        let reload = props.text != self.props.text
+            || props.transform != self.props.transform
+            || props.mask != self.props.mask
+            || props.max_length != self.props.max_length
+            || props.overlength != self.props.overlength;
+        let range_changed = props.range != self.props.range
+            || (props.range == RangeOrCharCodes::Auto && props.text != self.props.text);
+        if range_changed {
+            self.random_chars = CharTable::new(&props.range, &props.text);
+        }
+        let bidi_changed =
+            range_changed || props.text != self.props.text || props.dir != self.props.dir;
+        self.props = props;
+        if reload {
+            self.text = self.props.final_text();
+            self.load_chunk(0, 0);
+        }
+        if bidi_changed {
+            self.load_rtl_chars();
+        }
+        Ok(())
+    }
+
+    /// Picks the letters drawn in right-to-left text, if the text reads that way and `range`
+    /// has left-to-right letters.
+    fn load_rtl_chars(&mut self) {
+        let rtl = match self.props.dir {
+            Direction::Auto => bidi::is_rtl_text(&self.props.text),
+            Direction::Ltr => false,
+            Direction::Rtl => true,
+        };
+        self.rtl_chars = if rtl && self.random_chars.has_strong_ltr() {
+            bidi::rtl_letters(&self.props.text).map(CharTable::Chars)
+        } else {
+            None
+        };
+    }
+
+    /// Loads the characters to animate, starting `start` characters and `offset` bytes into
+    /// the text: all of them, or up to `max_length`.
+    fn load_chunk(&mut self, start: usize, offset: usize) {
+        if start == 0 {
+            let len = self.text.chars().count();
+            self.text_len = match (self.props.max_length, self.props.overlength) {
+                (Some(max), Overlength::Truncate) => len.min(max),
+                _ => len,
+            };
+        }
+
+        self.chunk_start = start;
+        self.chunk_offset = offset;
+        let rest = self.text[offset..].chars();
+        self.chars.clear();
+        match self.props.max_length {
+            Some(max) => self.chars.extend(rest.take(max)),
+            None => self.chars.extend(rest),
+        }
+        self.units = emoji::units(&self.chars);
+        self.kept = whole_words(&self.chars, &self.props.ignore_words);
+    }
+
+    /// Moves on to the chunk after the current one, returning false when there is none.
+    fn next_chunk(&mut self) -> bool {
+        if self.props.overlength != Overlength::Chunk {
+            return false;
+        }
+        let offset = self.chunk_offset + self.chars.iter().map(|c| c.len_utf8()).sum::<usize>();
+        if offset >= self.text.len() {
+            return false;
+        }
+        self.load_chunk(self.chunk_start + self.chars.len(), offset);
+        self.reset_control();
+        true
+    }
+
+    /// Restrict the scramble to the characters in `range`, e.g. those on screen. The others
+    /// show their final character from the next frame on. `None` scrambles every character.
+    pub fn set_visible_range(&mut self, range: Option<Range<usize>>) {
+        self.visible = range;
+    }
+
+    /// Advance the animation by one frame.
+    pub fn tick(&mut self) -> Frame {
+        let mut frame = Frame::default();
+        self.tick_into(&mut frame);
+        frame
+    }
+
+    /// Advance the animation by one frame, drawing it into `frame`. Reusing the same frame
+    /// for a whole run saves allocating its buffers on every frame.
+    pub fn tick_into(&mut self, frame: &mut Frame) {
+        // Enough for a character per position plus the new scramble counts, in the usual case
+        let draws = self.chars.len().max(self.control.len())
+            + 2 * (self.props.step + self.props.seed) as usize;
+        self.random.refill(draws);
+
+        match self.stage() {
+            Stage::Sweep { .. } => self.overdrive(),
+            Stage::Glitch { frames, chance } => self.glitch(frames, chance),
+            stage => {
+                self.overdrive();
+                if self.step % self.props.tick == 0 {
+                    self.step_forward();
+                    self.resize_control();
+                    if !matches!(stage, Stage::Typewriter) {
+                        self.seed_forward();
+                    }
+                }
+            }
+        }
+
+        self.draw(frame);
+    }
+
+    /// Skip to the end of the animation, returning its final frame.
+    pub fn finish(&mut self) -> Frame {
+        while self.next_chunk() {}
+        self.control = self
+            .chars
+            .iter()
+            .map(|&c| ScrambleControl::Settled(c))
+            .collect();
+        self.scramble_index = self.control.len();
+        self.stage = self.props.pipeline.len();
+        let mut frame = Frame::default();
+        self.draw(&mut frame);
+        frame
+    }
+
+    /// Draw the frame `progress` (0-1) of the way through the reveal, regardless of time.
+    /// Characters before that point are final, and the rest are scrambled, or not drawn yet
+    /// without `overflow`.
+    pub fn seek(&mut self, progress: f64) -> Frame {
+        let mut frame = Frame::default();
+        self.seek_into(progress, &mut frame);
+        frame
+    }
+
+    /// Like [`seek`](Self::seek), drawing into `frame` to reuse its buffers.
+    pub fn seek_into(&mut self, progress: f64, frame: &mut Frame) {
+        self.stage = self.reveal_stage();
+        self.stage_frames = 0;
+        let scramble = ScrambleControl::Scrambling(self.scramble().max(1));
+        let overflow = self.overflow();
+        let index = (progress.clamp(0.0, 1.0) * self.chars.len() as f64).round() as usize;
+
+        self.control
+            .resize(self.chars.len(), ScrambleControl::Pending);
+        for i in 0..self.chars.len() {
+            let c = ScrambleControl::Settled(self.chars[i]);
+            self.control[i] = if i < index {
+                c
+            } else if overflow {
+                self.set_if_not_ignored(i, &c, scramble)
+            } else {
+                ScrambleControl::Pending
+            };
+        }
+        self.scramble_index = index;
+        self.complete = false;
+        self.random.refill(self.control.len());
+        self.draw(frame);
+    }
+
+    /// Feed `delta_ms` of elapsed time to the engine. Returns a frame once enough time has
+    /// accumulated for the next redraw at the configured `speed`, and `None` otherwise.
+    pub fn advance(&mut self, delta_ms: f64) -> Option<Frame> {
+        let mut frame = Frame::default();
+        self.advance_into(delta_ms, &mut frame).then_some(frame)
+    }
+
+    /// Like [`advance`](Self::advance), drawing into `frame` to reuse its buffers. Returns
+    /// whether a frame was drawn.
+    pub fn advance_into(&mut self, delta_ms: f64, frame: &mut Frame) -> bool {
+        if self.props.speed == 0.0 {
+            return false;
+        }
+
+        let fps_interval = 1000.0 / (60.0 * self.props.speed as f64);
+        let draw_interval = self.props.max_fps.map_or(fps_interval, |max_fps| {
+            fps_interval.max(1000.0 / max_fps as f64)
+        });
+        self.elapsed += delta_ms;
+        if self.elapsed < draw_interval {
+            return false;
+        }
+
+        // Catch up on the ticks skipped by the fps cap or by throttled timers, but no more
+        // than `MAX_CATCH_UP_MS` holds, so that a long stall doesn't jump ahead
+        let ticks = (self.elapsed / fps_interval)
+            .min((MAX_CATCH_UP_MS.max(draw_interval) / fps_interval).ceil())
+            .max(1.0) as u32;
+        self.elapsed %= fps_interval;
+        for _ in 0..ticks {
+            self.tick_into(frame);
+            if self.complete {
+                break;
+            }
+        }
+        true
+    }
+
+    fn set_if_not_ignored(
+        &self,
+        i: usize,
+        value: &ScrambleControl,
+        replace: ScrambleControl,
+    ) -> ScrambleControl {
+        let ignored = match value {
+            ScrambleControl::Settled(c) => {
+                self.props.ignore.contains(c) || self.kept.get(i) == Some(&true)
+            }
+            _ => false,
+        };
+        if ignored {
+            *value
+        } else {
+            replace
+        }
+    }
+
+    /// Whether to scramble position `i` of the current chunk, with the chance of its character.
+    fn should_scramble(&mut self, i: usize) -> bool {
+        let chance = self.props.chance.at(self.chunk_start + i);
+        self.random.int(0, 9) >= ((1.0 - chance) * 10.0) as i32
+    }
+
+    /// Pick random characters ahead in the string, and add them to the randomizer
+    fn seed_forward(&mut self) {
+        let text_len = self.chars.len();
+        let control_len = self.control.len();
+
+        if self.scramble_index >= text_len || control_len == 0 {
+            return;
+        }
+
+        for _ in 0..self.props.seed {
+            let index = self
+                .random
+                .int(self.scramble_index as i32, (control_len - 1) as i32)
+                as usize;
+
+            let value = self.control[index];
+            if let ScrambleControl::Settled(_) = value {
+                let count = if self.should_scramble(index) {
+                    self.scramble().max(self.props.seed)
+                } else {
+                    0
+                };
+                self.control[index] =
+                    self.set_if_not_ignored(index, &value, ScrambleControl::Scrambling(count));
+            }
+        }
+    }
+
+    /// Add `step` characters to the randomizer, and move the reveal front forward
+    fn step_forward(&mut self) {
+        let scramble = self.scramble();
+
+        for _ in 0..self.props.step {
+            let Some(&c) = self.chars.get(self.scramble_index) else {
+                break;
+            };
+
+            let count = if self.should_scramble(self.scramble_index) {
+                scramble + self.random.int(0, self.props.scramble_jitter.max(scramble))
+            } else {
+                0
+            };
+
+            if self.control.len() <= self.scramble_index {
+                self.control
+                    .resize(self.scramble_index + 1, ScrambleControl::Pending);
+            }
+            self.control[self.scramble_index] = self.set_if_not_ignored(
+                self.scramble_index,
+                &ScrambleControl::Settled(c),
+                ScrambleControl::Scrambling(count),
+            );
+
+            self.scramble_index += 1;
+            // The rest of an emoji sequence is revealed along with its first character
+            while self.units.get(self.scramble_index) == Some(&Unit::Joined) {
+                self.scramble_index += 1;
+            }
+        }
+    }
+
+    fn resize_control(&mut self) {
+        let text_len = self.chars.len();
+        if text_len < self.control.len() {
+            self.control.truncate(text_len);
+        } else if self.control.len() < text_len {
+            self.control.resize(text_len, ScrambleControl::Pending);
+        }
+    }
+
+    /// Sweep the overdrive character across the text ahead of the reveal
+    fn overdrive(&mut self) {
+        let Some(overdrive_char) = self.overdrive_char() else {
+            return;
+        };
+
+        for _ in 0..self.props.overdrive_step.unwrap_or(self.props.step) {
+            if self.overdrive_index >= self.control.len() {
+                break;
+            }
+
+            let current = ScrambleControl::Settled(
+                self.chars
+                    .get(self.overdrive_index)
+                    .copied()
+                    .unwrap_or_default(),
+            );
+            self.control[self.overdrive_index] = self.set_if_not_ignored(
+                self.overdrive_index,
+                &current,
+                ScrambleControl::Settled(overdrive_char),
+            );
+            self.overdrive_index += 1;
+        }
+    }
+
+    /// Scramble random characters of the whole text for the first `frames` frames of the
+    /// stage, each with `chance`. Characters not showing their final one yet settle.
+    fn glitch(&mut self, frames: u32, chance: f32) {
+        self.scramble_index = self.chars.len();
+        self.stage_frames += 1;
+        let glitching = self.stage_frames <= frames;
+        for i in 0..self.control.len() {
+            let Some(&c) = self.chars.get(i) else {
+                break;
+            };
+            if self.units.get(i) == Some(&Unit::Joined) {
+                continue;
+            }
+            let settled = ScrambleControl::Settled(c);
+            let glitch = match self.control[i] {
+                ScrambleControl::Scrambling(_) => false,
+                control if control == settled => {
+                    glitching && self.random.int(0, 99) < (chance * 100.0) as i32
+                }
+                _ => true,
+            };
+            if glitch {
+                let count = ScrambleControl::Scrambling(self.random.int(1, 3));
+                self.control[i] = self.set_if_not_ignored(i, &settled, count);
+            }
+        }
+    }
+
+    /// Characters position `i` settles on: its own, or the whole emoji sequence it starts.
+    fn final_chars(&self, i: usize) -> &[char] {
+        let len = match self.units.get(i) {
+            Some(&Unit::Emoji(len)) => len,
+            _ => 1,
+        };
+        self.chars.get(i..i + len).unwrap_or_default()
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let text_len = self.chars.len();
+        let start = self.chunk_start;
+        let result = &mut frame.text;
+        let settled = &mut frame.settled;
+        let newly_settled = &mut frame.newly_settled;
+        let scrambled = &mut frame.scrambled;
+        let offsets = &mut frame.offsets;
+        result.clear();
+        settled.clear();
+        newly_settled.clear();
+        scrambled.clear();
+        offsets.clear();
+
+        // Chunks before the current one are done
+        result.push_str(&self.text[..self.chunk_offset]);
+        settled.extend(0..start);
+        offsets.extend(0..start);
+        let prefix_len = result.len();
+        // Whether a position has scrambles left, even if it happened to draw its own character
+        let mut scrambling = false;
+        let (mut drawn, mut counted_len) = (start, prefix_len);
+
+        for i in 0..self.control.len() {
+            drawn += result[counted_len..].chars().count();
+            counted_len = result.len();
+            offsets.push(drawn);
+
+            // The rest of an emoji sequence is drawn along with its first character
+            let unit = self.units.get(i).copied().unwrap_or(Unit::Char);
+            if unit == Unit::Joined {
+                continue;
+            }
+
+            // Characters out of view are shown as final right away, without any more work
+            let hidden = self
+                .visible
+                .as_ref()
+                .is_some_and(|visible| !visible.contains(&(start + i)));
+            if hidden && self.control[i] != ScrambleControl::Pending {
+                if let Some(&c) = self.chars.get(i) {
+                    let final_chars = self.final_chars(i);
+                    result.extend(final_chars);
+                    settled.extend(start + i..start + i + final_chars.len());
+                    if self.control[i] != ScrambleControl::Settled(c) {
+                        newly_settled.push(start + i);
+                        self.control[i] = ScrambleControl::Settled(c);
+                    }
+                    continue;
+                }
+            }
+
+            match self.control[i] {
+                ScrambleControl::Scrambling(n) if n > 0 => {
+                    // Left-to-right letters in RTL text keep their own run, the rest draw
+                    // letters that stay in the RTL one
+                    let chars = match &self.rtl_chars {
+                        _ if matches!(unit, Unit::Emoji(_)) => &EMOJI_CHARS,
+                        Some(rtl_chars)
+                            if !self.chars.get(i).is_some_and(|&c| bidi::is_strong_ltr(c)) =>
+                        {
+                            rtl_chars
+                        }
+                        _ => &self.random_chars,
+                    };
+                    result.push(chars.random_char(&mut self.random));
+                    scrambled.push(start + i);
+
+                    if i <= self.scramble_index {
+                        self.control[i] = ScrambleControl::Scrambling(n - 1);
+                    }
+                    scrambling |= self.control[i] != ScrambleControl::Scrambling(0);
+                }
+
+                ScrambleControl::Settled(c) if i >= text_len || i >= self.scramble_index => {
+                    if self.chars.get(i) == Some(&c) {
+                        result.extend(self.final_chars(i));
+                    } else {
+                        result.push(c);
+                    }
+                }
+
+                ScrambleControl::Settled(c) => {
+                    // Behind the reveal front, only the final character settles
+                    if self.chars.get(i) == Some(&c) {
+                        let final_chars = self.final_chars(i);
+                        result.extend(final_chars);
+                        settled.extend(start + i..start + i + final_chars.len());
+                    } else {
+                        match self.props.mismatch {
+                            Mismatch::Space => result.push(' '),
+                            Mismatch::Final => result.extend(self.final_chars(i)),
+                            Mismatch::Scrambled => result.push(c),
+                            Mismatch::Placeholder => {
+                                result.push(self.props.placeholder_char.unwrap_or(' '))
+                            }
+                        }
+                    }
+                }
+
+                ScrambleControl::Scrambling(_) if i < text_len => {
+                    if let Some(&c) = self.chars.get(i) {
+                        let final_chars = self.final_chars(i);
+                        result.extend(final_chars);
+                        if i < self.scramble_index {
+                            settled.extend(start + i..start + i + final_chars.len());
+                        }
+                        newly_settled.push(start + i);
+                        self.control[i] = ScrambleControl::Settled(c);
+                    }
+                }
+
+                // Positions the reveal has not reached yet show the placeholder, if any
+                ScrambleControl::Pending if i < text_len => {
+                    if let Some(placeholder) = self.props.placeholder_char {
+                        result.push(placeholder);
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        let matched = !scrambling && result[prefix_len..].chars().eq(self.chars.iter().copied());
+        let stage_over = match self.stage() {
+            Stage::Sweep { .. } => self.overdrive_index >= self.control.len(),
+            Stage::Glitch { frames, .. } => matched && self.stage_frames >= frames,
+            _ => matched,
+        };
+        if stage_over && !self.next_stage() && matched {
+            self.control.truncate(text_len);
+            // Positions out of scrambles may still hold the random character they last drew,
+            // which happened to be their final one
+            for (i, (control, &c)) in self.control.iter_mut().zip(&self.chars).enumerate() {
+                if *control != ScrambleControl::Settled(c) {
+                    newly_settled.push(start + i);
+                    scrambled.retain(|&position| position != start + i);
+                }
+                *control = ScrambleControl::Settled(c);
+            }
+            // The next chunk starts drawing on the next frame
+            if !self.next_chunk() {
+                self.complete = true;
+                settled.clear();
+                settled.extend(0..self.text_len);
+            }
+        }
+
+        // Spaces are swapped out until the final frame, so that they can't collapse
+        if let Some(space) = self.props.whitespace.space().filter(|_| !self.complete) {
+            if result.contains(' ') {
+                *result = result.replace(' ', space.encode_utf8(&mut [0; 4]));
+            }
+        }
+
+        self.step += 1;
+        frame.complete = self.complete;
+        for plugin in &mut self.plugins {
+            plugin.process(frame, &self.control);
+        }
+    }
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl ScrambleEngine {
+    /// True once the last drawn frame matched the target text.
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Share of the text showing its final characters (0-1), as of the last drawn frame.
+    pub fn progress(&self) -> f64 {
+        let len = self.text_len;
+        if self.complete {
+            return 1.0;
+        }
+        if len == 0 {
+            return 0.0;
+        }
+
+        let settled = self
+            .chars
+            .iter()
+            .zip(&self.control)
+            .take(self.scramble_index)
+            .filter(|(&c, control)| matches!(control, ScrambleControl::Settled(d) if *d == c))
+            .count();
+        (self.chunk_start + settled) as f64 / len as f64
+    }
+
+    /// Number of frames drawn since the last reset.
+    pub fn frame_count(&self) -> u32 {
+        self.step as u32
+    }
+
+    /// One line describing the reveal, for diagnosing runs that never seem to end, e.g.
+    /// `frame 12, front 5/11, 3 scrambling, 5 settled, 3 not drawn`.
+    pub fn debug_summary(&self) -> String {
+        let (mut scrambling, mut settled, mut hidden) = (0, 0, 0);
+        for (i, control) in self.control.iter().enumerate() {
+            match control {
+                ScrambleControl::Scrambling(n) if *n > 0 => scrambling += 1,
+                ScrambleControl::Settled(c) if self.chars.get(i) == Some(c) => settled += 1,
+                ScrambleControl::Pending => hidden += 1,
+                _ => {}
+            }
+        }
+        let mut summary = format!(
+            "frame {}, front {}/{}, {} scrambling, {} settled, {} not drawn",
+            self.step,
+            self.reveal_front(),
+            self.text_len,
+            scrambling,
+            self.chunk_start + settled,
+            hidden
+        );
+        if self.complete {
+            summary.push_str(", complete");
+        }
+        summary
+    }
+
+    /// Rewind the animation to the point where `frame` is showing, e.g. a frame rendered on
+    /// the server, so that it resumes instead of starting over. The leading characters that
+    /// match the text count as revealed.
+    pub fn resume_from(&mut self, frame: &str) {
+        self.reset();
+        let revealed = frame
+            .chars()
+            .zip(&self.chars)
+            .take_while(|(shown, &c)| *shown == c)
+            .count();
+        if revealed == 0 {
+            return;
+        }
+
+        self.resize_control();
+        for (i, &c) in self.chars[..revealed].iter().enumerate() {
+            self.control[i] = ScrambleControl::Settled(c);
+        }
+        self.scramble_index = revealed;
+        self.overdrive_index = revealed;
+        self.stage = self.reveal_stage();
+        self.step = (revealed as i32 / self.props.step) * self.props.tick;
+    }
+
+    /// Rewind the animation to its first frame.
+    pub fn reset(&mut self) {
+        self.step = 0;
+        self.elapsed = 0.0;
+        self.complete = false;
+        if self.chunk_start > 0 {
+            self.load_chunk(0, 0);
+        }
+        self.reset_control();
+    }
+}
+
+impl ScrambleEngine {
+    /// Rewind the reveal of the current chunk, or of the whole text, to its first stage.
+    fn reset_control(&mut self) {
+        self.stage = 0;
+        self.stage_frames = 0;
+        self.scramble_index = 0;
+        self.overdrive_index = 0;
+        self.control = if self.overflow() {
+            let scramble = ScrambleControl::Scrambling(self.scramble());
+            self.chars
+                .iter()
+                .enumerate()
+                .map(|(i, &c)| self.set_if_not_ignored(i, &ScrambleControl::Settled(c), scramble))
+                .collect()
+        } else {
+            vec![ScrambleControl::Pending; self.chars.len()]
+        };
+    }
+
+    /// The current `pipeline` stage, or a scramble reveal without one.
+    fn stage(&self) -> Stage {
+        self.props
+            .pipeline
+            .get(self.stage)
+            .copied()
+            .unwrap_or(DEFAULT_STAGE)
+    }
+
+    /// Index of the first stage revealing the text, where seeking and resuming pick up.
+    fn reveal_stage(&self) -> usize {
+        self.props
+            .pipeline
+            .iter()
+            .position(Stage::reveals)
+            .unwrap_or(0)
+    }
+
+    /// Moves on to the next `pipeline` stage, returning false when there is none. A sweep or
+    /// a reveal starts from the first character, and a reveal hides the characters showing
+    /// their final one again.
+    fn next_stage(&mut self) -> bool {
+        if self.stage + 1 >= self.props.pipeline.len() {
+            return false;
+        }
+        self.stage += 1;
+        self.stage_frames = 0;
+        if let Stage::Sweep { .. } = self.stage() {
+            self.scramble_index = 0;
+            self.overdrive_index = 0;
+        } else if self.stage().reveals() {
+            self.scramble_index = 0;
+            let overflow = self.overflow();
+            let scramble = ScrambleControl::Scrambling(self.scramble());
+            for i in 0..self.control.len() {
+                let settled = ScrambleControl::Settled(self.chars[i]);
+                if self.control[i] == settled {
+                    self.control[i] = if overflow {
+                        self.set_if_not_ignored(i, &settled, scramble)
+                    } else {
+                        ScrambleControl::Pending
+                    };
+                }
+            }
+        }
+        true
+    }
+
+    /// Times each character is scrambled when reached, which a typewriter doesn't.
+    fn scramble(&self) -> i32 {
+        match self.stage() {
+            Stage::Typewriter => 0,
+            _ => self.props.scramble,
+        }
+    }
+
+    /// Whether characters ahead of the reveal show noise.
+    fn overflow(&self) -> bool {
+        match self.stage() {
+            Stage::Scramble { overflow, .. } => overflow.unwrap_or(self.props.overflow),
+            Stage::Typewriter => false,
+            _ => self.props.overflow,
+        }
+    }
+
+    /// Character swept across the text ahead of the reveal, if any.
+    fn overdrive_char(&self) -> Option<char> {
+        match self.stage() {
+            Stage::Sweep { char } => Some(char.unwrap_or('_')),
+            Stage::Scramble { overdrive, .. } => overdrive.unwrap_or(self.props.overdrive).char(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "web")]
+#[wasm_bindgen]
+impl ScrambleEngine {
+    #[wasm_bindgen(constructor)]
+    pub fn from_js(
+        #[wasm_bindgen(unchecked_param_type = "ScrambleProps | ScrambleOptions")] props: JsValue,
+    ) -> Result<ScrambleEngine, JsError> {
+        let props = props_from_js(props)?;
+        Ok(ScrambleEngine::new(props)?)
+    }
+
+    /// Advance one frame and return it as `{ text, settled, complete }`.
+    #[wasm_bindgen(js_name = tick, unchecked_return_type = "ScrambleFrame")]
+    pub fn js_tick(&mut self) -> Result<JsValue, JsError> {
+        Ok(serde_wasm_bindgen::to_value(&self.tick())?)
+    }
+
+    /// Snapshot of the run, as a plain object that `import_state()` resumes from.
+    #[wasm_bindgen(js_name = export_state, unchecked_return_type = "ScrambleState")]
+    pub fn js_export_state(&self) -> Result<JsValue, JsError> {
+        Ok(serde_wasm_bindgen::to_value(&self.export_state())?)
+    }
+
+    /// Resumes the run a snapshot from `export_state()` was taken of, returning its frame.
+    #[wasm_bindgen(js_name = import_state, unchecked_return_type = "ScrambleFrame")]
+    pub fn js_import_state(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "ScrambleState")] state: JsValue,
+    ) -> Result<JsValue, JsError> {
+        let state: EngineState = serde_wasm_bindgen::from_value(state)?;
+        Ok(serde_wasm_bindgen::to_value(&self.import_state(&state)?)?)
+    }
+
+    /// Feed elapsed milliseconds; returns the next frame, or `undefined` if none is due yet.
+    #[wasm_bindgen(js_name = advance, unchecked_return_type = "ScrambleFrame | undefined")]
+    pub fn js_advance(&mut self, delta_ms: f64) -> Result<JsValue, JsError> {
+        match self.advance(delta_ms) {
+            Some(frame) => Ok(serde_wasm_bindgen::to_value(&frame)?),
+            None => Ok(JsValue::UNDEFINED),
+        }
+    }
+}
+
+/// Runs an animation to its end without a clock, returning `{ frames, duration, complete }`
+/// where each frame also has the `time` it is drawn at.
+#[cfg(feature = "web")]
+#[wasm_bindgen(js_name = record_frames, unchecked_return_type = "ScrambleRecording")]
+pub fn js_record_frames(
+    #[wasm_bindgen(unchecked_param_type = "ScrambleProps | ScrambleOptions")] props: JsValue,
+    max_frames: usize,
+) -> Result<JsValue, JsError> {
+    let recording = record_frames(props_from_js(props)?, max_frames)?;
+    Ok(serde_wasm_bindgen::to_value(&recording)?)
+}
+
+/// The text an animation shows before its first frame.
+#[cfg(feature = "web")]
+#[wasm_bindgen(js_name = initial_frame)]
+pub fn js_initial_frame(
+    #[wasm_bindgen(unchecked_param_type = "ScrambleProps | ScrambleOptions")] props: JsValue,
+) -> Result<String, JsError> {
+    Ok(initial_frame(props_from_js(props)?)?)
+}
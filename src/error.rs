@@ -0,0 +1,205 @@
+//! Errors reported when validating props and setting up animations.
+
+use std::fmt;
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
+use crate::scramble::Jitter;
+
+/// Why props were rejected, or an animation could not run.
+///
+/// In JS these surface as `Error`s whose `code` property holds [`ScrambleError::code`], so
+/// callers can tell problems apart without parsing messages. `InvalidProps` errors also have
+/// an `errors` array holding one such `Error` per problem.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScrambleError {
+    InvalidMaxLength,
+    /// Text longer than `max_length`, with `overlength` set to "error"
+    TextTooLong {
+        len: usize,
+        max: usize,
+    },
+    InvalidSpeed(f32),
+    InvalidMaxFps(f32),
+    InvalidTick(i32),
+    InvalidStep(i32),
+    InvalidOverdriveStep(i32),
+    InvalidChance(f32),
+    InvalidSeed(i32),
+    InvalidScramble(i32),
+    InvalidScrambleJitter(Jitter),
+    /// A range that is descending, negative or outside the Unicode scalar values
+    InvalidRange {
+        min: u32,
+        max: u32,
+    },
+    EmptyCodes,
+    /// A char code that is not a Unicode scalar value
+    InvalidCode(u32),
+    EmptyTargetAttribute,
+    InvalidOverdrive(u32),
+    /// A pipeline whose last stage leaves the text hidden behind a sweep
+    UnrevealedPipeline,
+    NoWindow,
+    NoDocument,
+    /// The instance was used after `dispose()`
+    Disposed,
+    /// Another instance already draws into the node, with `shared_target` set to "error"
+    TargetInUse,
+    /// State passed to `import_state` that was exported by a run revealing other text
+    StateMismatch,
+    /// A key in a props object that is not a prop, with the prop it most likely meant
+    UnknownProp {
+        name: String,
+        suggestion: Option<&'static str>,
+    },
+    /// Several problems with the props at once, in field order
+    InvalidProps(Vec<ScrambleError>),
+}
+
+impl ScrambleError {
+    /// Stable, machine-readable name of the error, e.g. `"invalid_speed"`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ScrambleError::InvalidMaxLength => "invalid_max_length",
+            ScrambleError::TextTooLong { .. } => "text_too_long",
+            ScrambleError::InvalidSpeed(_) => "invalid_speed",
+            ScrambleError::InvalidMaxFps(_) => "invalid_max_fps",
+            ScrambleError::InvalidTick(_) => "invalid_tick",
+            ScrambleError::InvalidStep(_) => "invalid_step",
+            ScrambleError::InvalidOverdriveStep(_) => "invalid_overdrive_step",
+            ScrambleError::InvalidChance(_) => "invalid_chance",
+            ScrambleError::InvalidSeed(_) => "invalid_seed",
+            ScrambleError::InvalidScramble(_) => "invalid_scramble",
+            ScrambleError::InvalidScrambleJitter(_) => "invalid_scramble_jitter",
+            ScrambleError::InvalidRange { .. } => "invalid_range",
+            ScrambleError::EmptyCodes => "empty_codes",
+            ScrambleError::InvalidCode(_) => "invalid_code",
+            ScrambleError::EmptyTargetAttribute => "empty_target_attribute",
+            ScrambleError::InvalidOverdrive(_) => "invalid_overdrive",
+            ScrambleError::UnrevealedPipeline => "unrevealed_pipeline",
+            ScrambleError::NoWindow => "no_window",
+            ScrambleError::NoDocument => "no_document",
+            ScrambleError::Disposed => "disposed",
+            ScrambleError::TargetInUse => "target_in_use",
+            ScrambleError::StateMismatch => "state_mismatch",
+            ScrambleError::UnknownProp { .. } => "unknown_prop",
+            ScrambleError::InvalidProps(_) => "invalid_props",
+        }
+    }
+}
+
+impl fmt::Display for ScrambleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScrambleError::InvalidMaxLength => write!(f, "Max length must be greater than 0"),
+            ScrambleError::TextTooLong { len, max } => write!(
+                f,
+                "Text is {} characters long, over the max length of {}",
+                len, max
+            ),
+            ScrambleError::InvalidSpeed(speed) => {
+                write!(f, "Speed must be between 0 and 1, got {}", speed)
+            }
+            ScrambleError::InvalidMaxFps(max_fps) => {
+                write!(f, "Max fps must be greater than 0, got {}", max_fps)
+            }
+            ScrambleError::InvalidTick(tick) => {
+                write!(f, "Tick must be greater than 0, got {}", tick)
+            }
+            ScrambleError::InvalidStep(step) => {
+                write!(f, "Step must be greater than 0, got {}", step)
+            }
+            ScrambleError::InvalidOverdriveStep(step) => {
+                write!(f, "Overdrive step must be greater than 0, got {}", step)
+            }
+            ScrambleError::InvalidChance(chance) => {
+                write!(f, "Chance must be between 0 and 1, got {}", chance)
+            }
+            ScrambleError::InvalidSeed(seed) => {
+                write!(f, "Seed must be greater than or equal to 0, got {}", seed)
+            }
+            ScrambleError::InvalidScramble(scramble) => {
+                write!(
+                    f,
+                    "Scramble must be greater than or equal to 0, got {}",
+                    scramble
+                )
+            }
+            ScrambleError::InvalidScrambleJitter(jitter) => {
+                write!(
+                    f,
+                    "Scramble jitter must be greater than or equal to 0, got {}",
+                    jitter
+                )
+            }
+            ScrambleError::InvalidRange { min, max } => write!(
+                f,
+                "Invalid range {}-{}: values must be ascending Unicode scalar values",
+                min, max
+            ),
+            ScrambleError::EmptyCodes => write!(f, "Codes vector cannot be empty"),
+            ScrambleError::InvalidCode(code) => {
+                write!(f, "Code {} is not a valid Unicode scalar value", code)
+            }
+            ScrambleError::EmptyTargetAttribute => {
+                write!(f, "Target attribute name cannot be empty")
+            }
+            ScrambleError::InvalidOverdrive(code) => {
+                write!(
+                    f,
+                    "Overdrive code {} is not a valid Unicode scalar value",
+                    code
+                )
+            }
+            ScrambleError::UnrevealedPipeline => {
+                write!(f, "Pipeline cannot end with a sweep stage")
+            }
+            ScrambleError::NoWindow => write!(f, "No window found"),
+            ScrambleError::NoDocument => write!(f, "No document found"),
+            ScrambleError::Disposed => write!(f, "ScrambleText has been disposed"),
+            ScrambleError::TargetInUse => {
+                write!(f, "Another ScrambleText already draws into this node")
+            }
+            ScrambleError::StateMismatch => {
+                write!(f, "State was exported by a run revealing other text")
+            }
+            ScrambleError::UnknownProp { name, suggestion } => {
+                write!(f, "Unknown prop \"{}\"", name)?;
+                if let Some(suggestion) = suggestion {
+                    write!(f, ", did you mean \"{}\"?", suggestion)?;
+                }
+                Ok(())
+            }
+            ScrambleError::InvalidProps(errors) => {
+                write!(f, "Invalid props: ")?;
+                for (i, error) in errors.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "; ")?;
+                    }
+                    write!(f, "{}", error)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "web")]
+impl From<ScrambleError> for JsError {
+    fn from(error: ScrambleError) -> Self {
+        let js_error = JsError::new(&error.to_string());
+        // The clone refers to the same JS object
+        let value = JsValue::from(js_error.clone());
+        let _ = js_sys::Reflect::set(&value, &"code".into(), &error.code().into());
+
+        if let ScrambleError::InvalidProps(errors) = error {
+            let errors: js_sys::Array = errors
+                .into_iter()
+                .map(|error| JsValue::from(JsError::from(error)))
+                .collect();
+            let _ = js_sys::Reflect::set(&value, &"errors".into(), &errors);
+        }
+        js_error
+    }
+}
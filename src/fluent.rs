@@ -0,0 +1,92 @@
+//! Chainable `ScrambleText` API for plain JS.
+//!
+//! ```js
+//! ScrambleText.create('#title', { speed: 0.6 })
+//!   .onFrame((text) => console.log(text))
+//!   .onEnd(() => console.log('done'))
+//!   .play();
+//! ```
+//!
+//! Each chained call consumes the instance it is called on and returns it, so keep the
+//! value returned by the last call.
+
+use js_sys::{Object, Reflect};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::Node;
+
+use crate::options::plain_props;
+use crate::web::{query_element, ScrambleText};
+
+#[wasm_bindgen]
+impl ScrambleText {
+    /// Creates an instance for `target`, given as a node or a CSS selector. `props` may leave
+    /// out `text`, which then defaults to the target's current text.
+    pub fn create(
+        #[wasm_bindgen(unchecked_param_type = "Node | string")] target: JsValue,
+        #[wasm_bindgen(unchecked_optional_param_type = "Partial<ScrambleProps> | ScrambleOptions")]
+        props: JsValue,
+    ) -> Result<ScrambleText, JsError> {
+        let target: Node = match target.as_string() {
+            Some(selector) => query_element(&selector)?.into(),
+            None => target
+                .dyn_into()
+                .map_err(|_| JsError::new("Target must be a node or a CSS selector"))?,
+        };
+
+        let props = if props.is_undefined() || props.is_null() {
+            Object::new().into()
+        } else {
+            plain_props(props)?
+        };
+        let has_text = Reflect::has(&props, &"text".into()).unwrap_or(false);
+        let props = if has_text {
+            props
+        } else {
+            let props = Object::<JsValue>::assign(&Object::new(), props.unchecked_ref());
+            let text = target.text_content().unwrap_or_default();
+            Reflect::set(&props, &"text".into(), &text.into())
+                .map_err(|e| JsError::new(&format!("Failed to set text: {:?}", e)))?;
+            props.into()
+        };
+        ScrambleText::new(target, props)
+    }
+
+    /// Sets the callback run when an animation starts. Returns the instance.
+    #[wasm_bindgen(js_name = onStart)]
+    pub fn on_start(mut self, callback: js_sys::Function) -> ScrambleText {
+        self.set_on_animation_start(callback);
+        self
+    }
+
+    /// Sets the callback run when an animation ends. Returns the instance.
+    #[wasm_bindgen(js_name = onEnd)]
+    pub fn on_end(mut self, callback: js_sys::Function) -> ScrambleText {
+        self.set_on_animation_end(callback);
+        self
+    }
+
+    /// Sets the callback run with the text of every frame. Returns the instance.
+    #[wasm_bindgen(js_name = onFrame)]
+    pub fn on_frame(
+        mut self,
+        #[wasm_bindgen(unchecked_param_type = "(text: string, frame: ScrambleFrame) => void")]
+        callback: js_sys::Function,
+    ) -> ScrambleText {
+        self.set_on_animation_frame(callback);
+        self
+    }
+
+    /// Sets the callback run once the instance is disposed. Returns the instance.
+    #[wasm_bindgen(js_name = onDispose)]
+    pub fn on_dispose(mut self, callback: js_sys::Function) -> ScrambleText {
+        self.set_on_dispose(callback);
+        self
+    }
+
+    /// Starts the animation and returns the instance. Use `start()` instead to await the run.
+    pub fn play(mut self) -> Result<ScrambleText, JsError> {
+        self.start()?;
+        Ok(self)
+    }
+}
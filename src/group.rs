@@ -0,0 +1,266 @@
+//! Groups of `ScrambleText` instances, and auto-initialisation from `data-scramble` markup.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::str::FromStr;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::Element;
+
+use crate::attributes::props_from_attributes;
+use crate::error::ScrambleError;
+use crate::random_int;
+use crate::split::Split;
+use crate::web::{ScrambleText, Timeout};
+
+/// How start delays spread across a group. Every pattern spans the same total time, from 0
+/// to `each_ms` times the number of animations after the first.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Stagger {
+    /// In order, `each_ms` apart
+    Linear,
+    /// Outwards from the center of the group's elements, in a wave
+    Center,
+    /// At random
+    Random,
+}
+
+impl FromStr for Stagger {
+    type Err = String;
+
+    /// Parses `linear`, `center` or `random`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "linear" => Ok(Stagger::Linear),
+            "center" => Ok(Stagger::Center),
+            "random" => Ok(Stagger::Random),
+            _ => Err(format!("Unknown stagger {}", s)),
+        }
+    }
+}
+
+enum GroupStagger {
+    Pattern(Stagger, f64),
+    /// Called as `(element, index) => delayMs`
+    Custom(js_sys::Function),
+}
+
+/// The animations of a group, shared with the observer re-splitting the lines of a
+/// `split_target` group.
+pub(crate) struct GroupState {
+    pub(crate) instances: Vec<ScrambleText>,
+    stagger: Option<GroupStagger>,
+    /// Staggered starts of the current `start()` that are still due.
+    pending: Vec<Timeout>,
+}
+
+/// Handle controlling several scramble animations at once.
+#[wasm_bindgen]
+pub struct ScrambleGroup {
+    state: Rc<RefCell<GroupState>>,
+    /// Markup replaced by `split_target`, put back on `destroy()`.
+    split: Option<Split>,
+}
+
+fn center(element: &Element) -> (f64, f64) {
+    let rect = element.get_bounding_client_rect();
+    (
+        rect.left() + rect.width() / 2.0,
+        rect.top() + rect.height() / 2.0,
+    )
+}
+
+/// Start delays radiating from the middle of the elements' bounding box, scaled so the
+/// farthest element starts at `span` ms. Instances without an element start first.
+fn center_delays(elements: &[Option<Element>], span: f64) -> Vec<f64> {
+    let centers: Vec<_> = elements
+        .iter()
+        .map(|element| element.as_ref().map(center))
+        .collect();
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (f64::MAX, f64::MAX, f64::MIN, f64::MIN);
+    for &(x, y) in centers.iter().flatten() {
+        (min_x, min_y) = (min_x.min(x), min_y.min(y));
+        (max_x, max_y) = (max_x.max(x), max_y.max(y));
+    }
+    let middle = ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0);
+
+    let distances: Vec<f64> = centers
+        .iter()
+        .map(|center| center.map_or(0.0, |(x, y)| (x - middle.0).hypot(y - middle.1)))
+        .collect();
+    let farthest = distances.iter().copied().fold(0.0, f64::max);
+    distances
+        .iter()
+        .map(|distance| {
+            if farthest > 0.0 {
+                distance / farthest * span
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+#[wasm_bindgen]
+impl ScrambleGroup {
+    /// Number of animations in the group.
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.state.borrow().instances.len()
+    }
+
+    /// Spreads the starts of later `start()` calls: `"linear"` starts them in order `each_ms`
+    /// apart, `"center"` in a wave from the middle of the group outwards, and `"random"` at
+    /// random, all within the same total time.
+    pub fn stagger(&mut self, strategy: &str, each_ms: f64) -> Result<(), JsError> {
+        let strategy = strategy.parse().map_err(|e: String| JsError::new(&e))?;
+        self.state.borrow_mut().stagger = Some(GroupStagger::Pattern(strategy, each_ms.max(0.0)));
+        Ok(())
+    }
+
+    /// Staggers later `start()` calls with a custom `(element, index) => delayMs` function.
+    /// `element` is null for instances that do not draw into an element.
+    pub fn stagger_with(&mut self, delay: js_sys::Function) {
+        self.state.borrow_mut().stagger = Some(GroupStagger::Custom(delay));
+    }
+
+    /// Starts every animation, staggered when `stagger()` or `stagger_with()` set a pattern.
+    pub fn start(&mut self) -> Result<(), JsError> {
+        self.state.borrow_mut().start()
+    }
+
+    /// Stops every animation, including the ones still waiting for their staggered start.
+    pub fn stop(&mut self) -> Result<(), JsError> {
+        let state = &mut *self.state.borrow_mut();
+        state.pending.clear();
+        state.instances.iter_mut().try_for_each(ScrambleText::stop)
+    }
+
+    /// Disposes every animation and releases the instances. The group is empty afterwards.
+    /// Groups from `split_target` put the element's original markup back.
+    pub fn destroy(&mut self) {
+        self.state.borrow_mut().dispose();
+        if let Some(split) = self.split.take() {
+            split.restore();
+        }
+    }
+}
+
+impl ScrambleGroup {
+    /// Group animating the spans `split_target` made, each with its own instance.
+    pub(crate) fn split(state: Rc<RefCell<GroupState>>, split: Split) -> ScrambleGroup {
+        ScrambleGroup {
+            state,
+            split: Some(split),
+        }
+    }
+}
+
+impl GroupState {
+    pub(crate) fn new(instances: Vec<ScrambleText>) -> GroupState {
+        GroupState {
+            instances,
+            stagger: None,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Starts every animation, staggered when a pattern is set.
+    pub(crate) fn start(&mut self) -> Result<(), JsError> {
+        self.pending.clear();
+        let delays = self.delays()?;
+        for (instance, delay) in self.instances.iter_mut().zip(delays) {
+            if delay > 0.0 {
+                self.pending.push(instance.start_after(delay)?);
+            } else {
+                instance.start()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Disposes every animation, including the ones still waiting for their staggered start,
+    /// and releases the instances.
+    pub(crate) fn dispose(&mut self) {
+        self.pending.clear();
+        self.instances.iter_mut().for_each(ScrambleText::dispose);
+        self.instances.clear();
+    }
+
+    /// Start delay of each instance, in milliseconds.
+    fn delays(&self) -> Result<Vec<f64>, JsError> {
+        let count = self.instances.len();
+        let span = |each_ms: f64| each_ms * count.saturating_sub(1) as f64;
+
+        let delays = match &self.stagger {
+            None => vec![0.0; count],
+            Some(GroupStagger::Pattern(Stagger::Linear, each_ms)) => {
+                (0..count).map(|i| i as f64 * each_ms).collect()
+            }
+            Some(GroupStagger::Pattern(Stagger::Center, each_ms)) => {
+                let elements: Vec<_> = self.instances.iter().map(ScrambleText::element).collect();
+                center_delays(&elements, span(*each_ms))
+            }
+            Some(GroupStagger::Pattern(Stagger::Random, each_ms)) => (0..count)
+                .map(|_| random_int(0, span(*each_ms) as i32) as f64)
+                .collect(),
+            Some(GroupStagger::Custom(delay)) => self
+                .instances
+                .iter()
+                .enumerate()
+                .map(|(i, instance)| {
+                    let element = instance.element().map_or(JsValue::null(), JsValue::from);
+                    let delay = delay
+                        .call2(&JsValue::null(), &element, &(i as u32).into())
+                        .map_err(|e| JsError::new(&format!("Stagger function failed: {:?}", e)))?;
+                    Ok(delay.as_f64().unwrap_or(0.0))
+                })
+                .collect::<Result<_, JsError>>()?,
+        };
+        Ok(delays)
+    }
+}
+
+/// Creates and starts a `ScrambleText` for every element matching `selector`
+/// (`[data-scramble]` by default).
+///
+/// The text to reveal is the `data-scramble` attribute when it is not empty, and the element's
+/// text content otherwise. Props are read from `data-scramble-*` attributes, e.g.
+/// `data-scramble-speed="0.6"` or `data-scramble-range="33-126"`. Elements with
+/// `data-scramble-play-on-mount="false"` are created without being started.
+#[wasm_bindgen]
+pub fn init_all(selector: Option<String>) -> Result<ScrambleGroup, JsError> {
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or(ScrambleError::NoDocument)?;
+    let selector = selector.unwrap_or_else(|| "[data-scramble]".to_string());
+    let nodes = document
+        .query_selector_all(&selector)
+        .map_err(|e| JsError::new(&format!("Invalid selector {}: {:?}", selector, e)))?;
+
+    let mut instances = Vec::with_capacity(nodes.length() as usize);
+    for node in (0..nodes.length()).filter_map(|i| nodes.get(i)) {
+        let Ok(element) = node.dyn_into::<Element>() else {
+            continue;
+        };
+
+        let text = element
+            .get_attribute("data-scramble")
+            .filter(|text| !text.is_empty())
+            .or_else(|| element.text_content())
+            .unwrap_or_default();
+        let props = props_from_attributes(&element, "data-scramble-", text)?;
+        let play_on_mount = props.plays_on_mount();
+
+        let mut instance = ScrambleText::with_props(element.into(), props)?;
+        if play_on_mount {
+            instance.start()?;
+        }
+        instances.push(instance);
+    }
+
+    Ok(ScrambleGroup {
+        state: Rc::new(RefCell::new(GroupState::new(instances))),
+        split: None,
+    })
+}
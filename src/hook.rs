@@ -0,0 +1,112 @@
+//! Rust-native port of the react `useScramble` hook, driven by `requestAnimationFrame`.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::Element;
+
+use crate::clock::Clock;
+use crate::engine::ScrambleEngine;
+use crate::error::ScrambleError;
+use crate::motion::prefers_reduced_motion;
+use crate::scramble::UseScrambleProps;
+
+/// Element the animation draws into. Set it once the node is mounted.
+pub type NodeRef = Rc<RefCell<Option<Element>>>;
+
+/// Node ref, play function and cleanup function returned by [`use_scramble`].
+pub type ScrambleHandles = (NodeRef, Box<dyn Fn()>, Box<dyn Fn()>);
+
+type AnimationLoop = Rc<RefCell<Option<Closure<dyn FnMut(f64)>>>>;
+
+pub fn use_scramble(mut props: UseScrambleProps) -> Result<ScrambleHandles, ScrambleError> {
+    if props.reduced_motion.applies(prefers_reduced_motion()) {
+        props = props.with_reduced_motion();
+    }
+
+    let speed = props.speed;
+    let engine = Rc::new(RefCell::new(ScrambleEngine::new(props)?));
+
+    // Text node ref
+    let node_ref: NodeRef = Rc::new(RefCell::new(None));
+
+    // Animation frame request
+    let raf_ref = Rc::new(RefCell::new(0));
+
+    // Turns frame timestamps into the time the engine advances by
+    let clock_ref = Rc::new(RefCell::new(Clock::new()));
+
+    let animate: AnimationLoop = Rc::new(RefCell::new(None));
+    *animate.borrow_mut() = Some({
+        let engine = engine.clone();
+        let node_ref = node_ref.clone();
+        let raf_ref = raf_ref.clone();
+        let clock = clock_ref.clone();
+        let animate = animate.clone();
+
+        Closure::wrap(Box::new(move |time: f64| {
+            if speed == 0.0 {
+                *raf_ref.borrow_mut() = 0;
+                return;
+            }
+
+            let delta = clock.borrow_mut().delta_to(time);
+            let frame = engine.borrow_mut().advance(delta);
+            if let Some(frame) = frame {
+                if let Some(node) = node_ref.borrow().as_ref() {
+                    node.set_text_content(Some(&frame.text));
+                }
+
+                if frame.complete {
+                    *raf_ref.borrow_mut() = 0;
+                    return;
+                }
+            }
+
+            if let (Some(window), Some(animate)) = (web_sys::window(), animate.borrow().as_ref()) {
+                if let Ok(id) = window.request_animation_frame(animate.as_ref().unchecked_ref()) {
+                    *raf_ref.borrow_mut() = id;
+                }
+            }
+        }) as Box<dyn FnMut(f64)>)
+    });
+
+    let play = {
+        let raf_ref = raf_ref.clone();
+        let animate = animate.clone();
+
+        Box::new(move || {
+            engine.borrow_mut().reset();
+            clock_ref.borrow_mut().reset();
+
+            let window = web_sys::window().expect("no global window exists");
+
+            // Cancel any existing animation frame
+            if *raf_ref.borrow() != 0 {
+                let _ = window.cancel_animation_frame(*raf_ref.borrow());
+                *raf_ref.borrow_mut() = 0;
+            }
+
+            // Start new animation
+            if let Some(animate) = animate.borrow().as_ref() {
+                if let Ok(id) = window.request_animation_frame(animate.as_ref().unchecked_ref()) {
+                    *raf_ref.borrow_mut() = id;
+                }
+            }
+        }) as Box<dyn Fn()>
+    };
+
+    // Cancels the pending frame and releases the animation loop, which otherwise keeps itself alive
+    let cleanup = Box::new(move || {
+        if let Some(window) = web_sys::window() {
+            if *raf_ref.borrow() != 0 {
+                let _ = window.cancel_animation_frame(*raf_ref.borrow());
+                *raf_ref.borrow_mut() = 0;
+            }
+        }
+        animate.borrow_mut().take();
+    }) as Box<dyn Fn()>;
+
+    Ok((node_ref, play, cleanup))
+}
@@ -0,0 +1,91 @@
+//! Leptos port of the react `useScramble` hook.
+//!
+//! ```ignore
+//! let (text, set_text) = signal("Hello".to_string());
+//! let (node_ref, play, stop) = use_scramble::<html::Span>(UseScrambleProps::default(), text);
+//! view! { <span node_ref=node_ref on:mouseenter=move |_| play()></span> }
+//! ```
+
+use ::leptos::html::ElementType;
+use ::leptos::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::Node;
+
+use crate::scramble::UseScrambleProps;
+use crate::web::ScrambleText;
+
+fn log_error(result: Result<(), wasm_bindgen::JsError>) {
+    if let Err(e) = result {
+        web_sys::console::error_1(&e.into());
+    }
+}
+
+/// Scrambles `text` into the element bound to the returned node ref, replaying whenever the
+/// signal changes. `props.text` is replaced by the signal's value, and `play_on_mount: false`
+/// only holds the first text until `play()`.
+///
+/// Returns the node ref, a function replaying the animation and one stopping it. The
+/// animation stops when the owning component is cleaned up.
+pub fn use_scramble<E>(
+    props: UseScrambleProps,
+    text: impl Into<Signal<String>>,
+) -> (NodeRef<E>, impl Fn() + Clone, impl Fn() + Clone)
+where
+    E: ElementType,
+    E::Output: JsCast + Clone + 'static,
+{
+    let text = text.into();
+    let node_ref = NodeRef::<E>::new();
+    // Owned by the calling component, so the instance is dropped, which stops it and removes
+    // its listeners, once the component is cleaned up
+    let instance = StoredValue::new_local(None::<ScrambleText>);
+
+    Effect::new(move |_| {
+        let text = text.get();
+        let Some(node) = node_ref.get() else {
+            return;
+        };
+        let props = UseScrambleProps {
+            text,
+            ..props.clone()
+        };
+
+        instance.update_value(|instance| match instance {
+            Some(scramble) => {
+                log_error(scramble.set_props(props));
+                log_error(scramble.start());
+            }
+            None => {
+                let node = node.unchecked_into::<Node>();
+                node.set_text_content(Some(&props.final_text()));
+                let play_on_mount = props.plays_on_mount();
+                match ScrambleText::with_props(node, props) {
+                    Ok(mut scramble) => {
+                        if play_on_mount {
+                            log_error(scramble.start());
+                        }
+                        *instance = Some(scramble);
+                    }
+                    Err(e) => web_sys::console::error_1(&e.into()),
+                }
+            }
+        });
+    });
+
+    let play = move || {
+        instance.update_value(|instance| {
+            if let Some(scramble) = instance {
+                log_error(scramble.start());
+            }
+        })
+    };
+    let stop = move || {
+        instance.update_value(|instance| {
+            if let Some(scramble) = instance {
+                log_error(scramble.stop());
+            }
+        })
+    };
+
+    (node_ref, play, stop)
+}
@@ -0,0 +1,17 @@
+//! The `prefers-reduced-motion` user preference.
+
+use web_sys::MediaQueryList;
+
+/// Media query matching while the user prefers reduced motion.
+pub(crate) fn reduced_motion_query() -> Option<MediaQueryList> {
+    web_sys::window()
+        .and_then(|window| window.match_media("(prefers-reduced-motion: reduce)").ok())
+        .flatten()
+}
+
+/// Whether the user asked the system to minimize non-essential motion.
+pub(crate) fn prefers_reduced_motion() -> bool {
+    reduced_motion_query()
+        .map(|media| media.matches())
+        .unwrap_or(false)
+}
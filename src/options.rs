@@ -0,0 +1,400 @@
+//! Typed options class, an alternative to plain props objects.
+//!
+//! ```js
+//! const options = new ScrambleOptions('Hello');
+//! options.speed = 0.6;
+//! options.range = '33-126';
+//! options.triggers = ['hover', 'focus'];
+//! new ScrambleText(element, options);
+//! ```
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::scramble::{
+    check_prop_names, deserialize_ignore, sorted_ignore, AriaLive, Transform, UseScrambleProps,
+};
+
+/// Props set through typed properties, so TypeScript checks names and types as they are
+/// written. Accepted anywhere a props object is; see the props docs for what each one does.
+#[wasm_bindgen]
+#[derive(Clone, Default)]
+pub struct ScrambleOptions {
+    props: UseScrambleProps,
+}
+
+/// Getter and setter pairs for props that map straight onto JS values.
+macro_rules! accessors {
+    ($($field:ident / $setter:ident: $ty:ty;)*) => {
+        #[wasm_bindgen]
+        impl ScrambleOptions {
+            $(
+                #[wasm_bindgen(getter)]
+                pub fn $field(&self) -> $ty {
+                    self.props.$field.clone()
+                }
+
+                #[wasm_bindgen(setter)]
+                pub fn $setter(&mut self, $field: $ty) {
+                    self.props.$field = $field;
+                }
+            )*
+        }
+    };
+}
+
+accessors! {
+    play_on_mount / set_play_on_mount: Option<bool>;
+    text / set_text: String;
+    max_length / set_max_length: Option<usize>;
+    speed / set_speed: f32;
+    max_fps / set_max_fps: Option<f32>;
+    tick / set_tick: i32;
+    step / set_step: i32;
+    seed / set_seed: i32;
+    scramble / set_scramble: i32;
+    ignore_words / set_ignore_words: Vec<String>;
+    overdrive_step / set_overdrive_step: Option<i32>;
+    overflow / set_overflow: bool;
+    placeholder_char / set_placeholder_char: Option<char>;
+    mask / set_mask: Option<char>;
+    hover_replay / set_hover_replay: bool;
+    content_selector / set_content_selector: Option<String>;
+    spoiler / set_spoiler: bool;
+    conceal_after / set_conceal_after: Option<f64>;
+    scroll_scrub / set_scroll_scrub: bool;
+    hover_scrub / set_hover_scrub: bool;
+    hover_scrub_radius / set_hover_scrub_radius: u32;
+    hover_scrub_settle / set_hover_scrub_settle: f64;
+    css_vars / set_css_vars: bool;
+    pause_when_hidden / set_pause_when_hidden: bool;
+    pause_on_blur / set_pause_on_blur: bool;
+    idle / set_idle: bool;
+    virtualize / set_virtualize: bool;
+    spans / set_spans: bool;
+    a11y / set_a11y: bool;
+    announce_on_complete / set_announce_on_complete: bool;
+    strict / set_strict: bool;
+    debug / set_debug: bool;
+    overlay / set_overlay: bool;
+}
+
+#[wasm_bindgen]
+impl ScrambleOptions {
+    /// Default options revealing `text`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(text: Option<String>) -> ScrambleOptions {
+        ScrambleOptions {
+            props: UseScrambleProps {
+                text: text.unwrap_or_default(),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// `MIN-MAX` code point range, comma separated codes, or `auto`.
+    #[wasm_bindgen(getter)]
+    pub fn range(&self) -> String {
+        self.props.range.to_string()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_range(&mut self, range: &str) -> Result<(), JsError> {
+        self.props.range = range.parse().map_err(|e: String| JsError::new(&e))?;
+        Ok(())
+    }
+
+    /// Characters to avoid scrambling, as a sorted list.
+    #[wasm_bindgen(getter)]
+    pub fn ignore(&self) -> Vec<String> {
+        sorted_ignore(&self.props.ignore)
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_ignore(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "string | string[]")] ignore: JsValue,
+    ) -> Result<(), JsError> {
+        self.props.ignore = deserialize_ignore(serde_wasm_bindgen::Deserializer::from(ignore))?;
+        Ok(())
+    }
+
+    /// The chance of scrambling every character, or a list with the chance of each.
+    #[wasm_bindgen(getter, unchecked_return_type = "number | number[]")]
+    pub fn chance(&self) -> Result<JsValue, JsError> {
+        Ok(serde_wasm_bindgen::to_value(&self.props.chance)?)
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_chance(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "number | number[]")] chance: JsValue,
+    ) -> Result<(), JsError> {
+        self.props.chance = serde_wasm_bindgen::from_value(chance)?;
+        Ok(())
+    }
+
+    /// Most extra times each character is scrambled: a number, or a percentage of `scramble`.
+    #[wasm_bindgen(getter, unchecked_return_type = "number | `${number}%`")]
+    pub fn scramble_jitter(&self) -> Result<JsValue, JsError> {
+        Ok(serde_wasm_bindgen::to_value(&self.props.scramble_jitter)?)
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_scramble_jitter(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "number | `${number}%`")] scramble_jitter: JsValue,
+    ) -> Result<(), JsError> {
+        self.props.scramble_jitter = serde_wasm_bindgen::from_value(scramble_jitter)?;
+        Ok(())
+    }
+
+    /// `true`, `false` or the code point to sweep with.
+    #[wasm_bindgen(getter, unchecked_return_type = "boolean | number")]
+    pub fn overdrive(&self) -> Result<JsValue, JsError> {
+        Ok(serde_wasm_bindgen::to_value(&self.props.overdrive)?)
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_overdrive(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "boolean | number")] overdrive: JsValue,
+    ) -> Result<(), JsError> {
+        self.props.overdrive = serde_wasm_bindgen::from_value(overdrive)?;
+        Ok(())
+    }
+
+    #[wasm_bindgen(getter, unchecked_return_type = "ScrambleStage[]")]
+    pub fn pipeline(&self) -> Result<JsValue, JsError> {
+        Ok(serde_wasm_bindgen::to_value(&self.props.pipeline)?)
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_pipeline(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "ScrambleStage[]")] pipeline: JsValue,
+    ) -> Result<(), JsError> {
+        self.props.pipeline = serde_wasm_bindgen::from_value(pipeline)?;
+        Ok(())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn triggers(&self) -> Vec<String> {
+        self.props
+            .triggers
+            .iter()
+            .map(|trigger| trigger.as_str().to_string())
+            .collect()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_triggers(&mut self, triggers: Vec<String>) -> Result<(), JsError> {
+        self.props.triggers = triggers
+            .iter()
+            .map(|trigger| trigger.parse())
+            .collect::<Result<_, String>>()
+            .map_err(|e| JsError::new(&e))?;
+        Ok(())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn target(&self) -> String {
+        self.props.target.clone().into()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_target(&mut self, target: String) {
+        self.props.target = target.into();
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn overlength(&self) -> String {
+        self.props.overlength.as_str().to_string()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_overlength(&mut self, overlength: &str) -> Result<(), JsError> {
+        self.props.overlength = overlength.parse().map_err(|e: String| JsError::new(&e))?;
+        Ok(())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn mismatch(&self) -> String {
+        self.props.mismatch.as_str().to_string()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_mismatch(&mut self, mismatch: &str) -> Result<(), JsError> {
+        self.props.mismatch = mismatch.parse().map_err(|e: String| JsError::new(&e))?;
+        Ok(())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn whitespace(&self) -> String {
+        self.props.whitespace.as_str().to_string()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_whitespace(&mut self, whitespace: &str) -> Result<(), JsError> {
+        self.props.whitespace = whitespace.parse().map_err(|e: String| JsError::new(&e))?;
+        Ok(())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn dir(&self) -> String {
+        self.props.dir.as_str().to_string()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_dir(&mut self, dir: &str) -> Result<(), JsError> {
+        self.props.dir = dir.parse().map_err(|e: String| JsError::new(&e))?;
+        Ok(())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn shared_target(&self) -> String {
+        self.props.shared_target.as_str().to_string()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_shared_target(&mut self, shared_target: &str) -> Result<(), JsError> {
+        self.props.shared_target = shared_target
+            .parse()
+            .map_err(|e: String| JsError::new(&e))?;
+        Ok(())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn reduced_motion(&self) -> String {
+        self.props.reduced_motion.as_str().to_string()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_reduced_motion(&mut self, reduced_motion: &str) -> Result<(), JsError> {
+        self.props.reduced_motion = reduced_motion
+            .parse()
+            .map_err(|e: String| JsError::new(&e))?;
+        Ok(())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn transform(&self) -> Option<String> {
+        self.props
+            .transform
+            .map(|transform| transform.as_str().to_string())
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_transform(&mut self, transform: Option<String>) -> Result<(), JsError> {
+        self.props.transform = transform
+            .map(|transform| transform.parse::<Transform>())
+            .transpose()
+            .map_err(|e| JsError::new(&e))?;
+        Ok(())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn aria_live(&self) -> Option<String> {
+        self.props
+            .aria_live
+            .map(|aria_live| aria_live.as_str().to_string())
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_aria_live(&mut self, aria_live: Option<String>) -> Result<(), JsError> {
+        self.props.aria_live = aria_live
+            .map(|aria_live| aria_live.parse::<AriaLive>())
+            .transpose()
+            .map_err(|e| JsError::new(&e))?;
+        Ok(())
+    }
+
+    /// Checks the options, throwing an error that lists every problem.
+    pub fn validate(&self) -> Result<(), JsError> {
+        Ok(self.props.validate()?)
+    }
+
+    /// The equivalent plain props object, also used by `JSON.stringify`.
+    #[wasm_bindgen(js_name = toJSON, unchecked_return_type = "ScrambleProps")]
+    pub fn to_json(&self) -> Result<JsValue, JsError> {
+        Ok(serde_wasm_bindgen::to_value(&self.props)?)
+    }
+}
+
+impl ScrambleOptions {
+    pub fn props(&self) -> &UseScrambleProps {
+        &self.props
+    }
+}
+
+impl From<UseScrambleProps> for ScrambleOptions {
+    fn from(props: UseScrambleProps) -> Self {
+        ScrambleOptions { props }
+    }
+}
+
+/// Reads props given either as a plain object or as a `ScrambleOptions` instance. Instances
+/// are read through their `toJSON()`, which leaves them usable afterwards.
+pub(crate) fn props_from_js(props: JsValue) -> Result<UseScrambleProps, JsError> {
+    let props = plain_props(props)?;
+    check_prop_keys(&props)?;
+    Ok(serde_wasm_bindgen::from_value(resolve_chance(props)?)?)
+}
+
+/// `props`, with a `chance` function swapped for the list of chances it gives the characters
+/// of the text, called as `chance(index, length)`.
+pub(crate) fn resolve_chance(props: JsValue) -> Result<JsValue, JsError> {
+    let chance = js_sys::Reflect::get(&props, &"chance".into()).unwrap_or_default();
+    let Some(chance) = chance.dyn_ref::<js_sys::Function>() else {
+        return Ok(props);
+    };
+    let len = js_sys::Reflect::get(&props, &"text".into())
+        .ok()
+        .and_then(|text| text.as_string())
+        .map_or(0, |text| text.chars().count());
+    let chances = js_sys::Array::new();
+    for i in 0..len {
+        let value = chance
+            .call2(&JsValue::null(), &(i as u32).into(), &(len as u32).into())
+            .map_err(|e| JsError::new(&format!("Failed to compute chance: {:?}", e)))?;
+        chances.push(&value);
+    }
+
+    let props = js_sys::Object::<JsValue>::assign(&js_sys::Object::new(), props.unchecked_ref());
+    js_sys::Reflect::set(&props, &"chance".into(), &chances)
+        .map_err(|e| JsError::new(&format!("Failed to set chance: {:?}", e)))?;
+    Ok(props.into())
+}
+
+/// The plain props object for `props`, calling `toJSON()` on objects that have it.
+pub(crate) fn plain_props(props: JsValue) -> Result<JsValue, JsError> {
+    let to_json = js_sys::Reflect::get(&props, &"toJSON".into())
+        .ok()
+        .and_then(|to_json| to_json.dyn_into::<js_sys::Function>().ok());
+    match to_json {
+        Some(to_json) => to_json
+            .call0(&props)
+            .map_err(|e| JsError::new(&format!("Failed to read options: {:?}", e))),
+        None => Ok(props),
+    }
+}
+
+/// Rejects unknown keys in a props object, unless it sets `strict: false`.
+pub(crate) fn check_prop_keys(props: &JsValue) -> Result<(), JsError> {
+    if !props.is_object() {
+        return Ok(());
+    }
+    let strict = js_sys::Reflect::get(props, &"strict".into())
+        .ok()
+        .and_then(|strict| strict.as_bool())
+        .unwrap_or(true);
+    if !strict {
+        return Ok(());
+    }
+
+    let keys: Vec<String> = js_sys::Object::keys(props.unchecked_ref::<js_sys::Object<JsValue>>())
+        .iter()
+        .filter_map(|key| key.as_string())
+        .collect();
+    Ok(check_prop_names(keys.iter().map(String::as_str))?)
+}
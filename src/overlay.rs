@@ -0,0 +1,96 @@
+//! Dev overlay, drawn with the `overlay` prop in builds with the `overlay` feature.
+//!
+//! Each instance gets a panel in a column pinned to the bottom right corner of the page. Under
+//! the frame being drawn, it shows how many scrambles each character has left and marks the
+//! reveal front:
+//!
+//! ```text
+//! "Hello World": frame 12, front 5/11, 3 scrambling, 5 settled, 3 not drawn
+//! He#l*$ W
+//! ··4·32·1
+//!      ^
+//! ```
+
+use wasm_bindgen::prelude::*;
+use web_sys::{Document, Element};
+
+use crate::engine::ScrambleEngine;
+
+const CONTAINER_ATTRIBUTE: &str = "data-scramble-overlay";
+
+const CONTAINER_STYLE: &str = "position: fixed; right: 8px; bottom: 8px; z-index: 2147483647; \
+    display: flex; flex-direction: column; gap: 4px; max-width: 50vw; pointer-events: none;";
+
+const PANEL_STYLE: &str = "margin: 0; padding: 4px 6px; overflow: hidden; border-radius: 4px; \
+    background: rgba(0, 0, 0, 0.8); color: #8f8; font: 11px/1.3 monospace; white-space: pre;";
+
+pub(crate) struct Overlay {
+    panel: Element,
+}
+
+/// Glyph for a character's scrambles left: blank when not drawn yet, a dot once settled, and
+/// the count, or `+` past 9, while scrambling.
+fn count_glyph(count: Option<u32>) -> char {
+    match count {
+        None => ' ',
+        Some(0) => '·',
+        Some(n) => char::from_digit(n, 10).unwrap_or('+'),
+    }
+}
+
+/// The column every panel is added to, created on first use.
+fn container(document: &Document) -> Result<Element, JsValue> {
+    if let Some(container) = document.query_selector(&format!("[{}]", CONTAINER_ATTRIBUTE))? {
+        return Ok(container);
+    }
+    let body = document.body().ok_or("No body found")?;
+    let container = document.create_element("div")?;
+    container.set_attribute(CONTAINER_ATTRIBUTE, "")?;
+    container.set_attribute("style", CONTAINER_STYLE)?;
+    container.set_attribute("aria-hidden", "true")?;
+    body.append_child(&container)?;
+    Ok(container)
+}
+
+impl Overlay {
+    pub(crate) fn new() -> Result<Overlay, JsValue> {
+        let document = web_sys::window()
+            .and_then(|window| window.document())
+            .ok_or("No document found")?;
+        let panel = document.create_element("pre")?;
+        panel.set_attribute("style", PANEL_STYLE)?;
+        container(&document)?.append_child(&panel)?;
+        Ok(Overlay { panel })
+    }
+
+    /// Redraws the panel for the frame `engine` last drew, `text`, under `label`.
+    pub(crate) fn update(&self, label: &str, engine: &ScrambleEngine, text: &str) {
+        let counts = engine.scramble_counts();
+        let frame: String = text
+            .chars()
+            .map(|c| if c == '\n' { '⏎' } else { c })
+            .collect();
+        let counts: String = counts.into_iter().map(count_glyph).collect();
+        let front = " ".repeat(engine.reveal_front()) + "^";
+        let content = format!(
+            "{:?}: {}\n{}\n{}\n{}",
+            label,
+            engine.debug_summary(),
+            frame,
+            counts.trim_end(),
+            front
+        );
+        self.panel.set_text_content(Some(&content));
+    }
+}
+
+impl Drop for Overlay {
+    fn drop(&mut self) {
+        let container = self.panel.parent_element();
+        self.panel.remove();
+        if let Some(container) = container.filter(|container| container.child_element_count() == 0)
+        {
+            container.remove();
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Playback of frames captured by `record_frames`.
+//!
+//! ```js
+//! const recording = record_frames({ text: 'Hello' }, 600);
+//! localStorage.setItem('take', JSON.stringify(recording));
+//!
+//! const player = new FramePlayer(element, JSON.parse(localStorage.getItem('take')));
+//! player.play();
+//! ```
+
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::Node;
+
+use crate::engine::{RecordedFrame, Recording};
+use crate::error::ScrambleError;
+use crate::render::RenderTarget;
+use crate::scramble::WriteTarget;
+
+struct PlayerState {
+    target: RenderTarget,
+    frames: Vec<RecordedFrame>,
+    /// Number of frames written by the current playback.
+    written: usize,
+    start_time: Option<f64>,
+    request_id: Option<i32>,
+    on_end: Option<js_sys::Function>,
+    animate: Option<Closure<dyn FnMut(f64)>>,
+}
+
+impl PlayerState {
+    fn request_frame(&mut self) -> Result<(), JsError> {
+        let window = web_sys::window().ok_or(ScrambleError::NoWindow)?;
+        if let Some(animate) = &self.animate {
+            let id = window
+                .request_animation_frame(animate.as_ref().unchecked_ref())
+                .map_err(|_| JsError::new("Failed to request animation frame"))?;
+            self.request_id = Some(id);
+        }
+        Ok(())
+    }
+
+    fn cancel_frame(&mut self) {
+        if let (Some(id), Some(window)) = (self.request_id.take(), web_sys::window()) {
+            let _ = window.cancel_animation_frame(id);
+        }
+    }
+
+    /// Writes the last frame due `elapsed` milliseconds into playback, skipping any frames
+    /// missed in between. Returns whether playback is over.
+    fn draw(&mut self, elapsed: f64) -> bool {
+        let due = self.frames[self.written..]
+            .iter()
+            .take_while(|frame| frame.time <= elapsed)
+            .count();
+        if due > 0 {
+            self.written += due;
+            self.target.write(&self.frames[self.written - 1].frame.text);
+        }
+        self.written == self.frames.len()
+    }
+}
+
+fn on_frame(state: &Weak<RefCell<PlayerState>>, timestamp: f64) {
+    let Some(state) = state.upgrade() else {
+        return;
+    };
+
+    let on_end = {
+        let mut state = state.borrow_mut();
+        state.request_id = None;
+        let start_time = *state.start_time.get_or_insert(timestamp);
+        if !state.draw(timestamp - start_time) {
+            if let Err(e) = state.request_frame() {
+                web_sys::console::error_1(&e.into());
+            }
+            return;
+        }
+        state.start_time = None;
+        state.on_end.clone()
+    };
+
+    if let Some(on_end) = on_end {
+        let _ = on_end.call0(&JsValue::null());
+    }
+}
+
+/// Plays recorded frames back into a target at their recorded times, drawing the exact same
+/// characters on every playback.
+#[wasm_bindgen]
+pub struct FramePlayer {
+    state: Rc<RefCell<PlayerState>>,
+}
+
+#[wasm_bindgen]
+impl FramePlayer {
+    /// Creates a player writing the text content of `target`. `recording` is the object
+    /// returned by `record_frames`, possibly edited or round-tripped through JSON.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        target: Node,
+        #[wasm_bindgen(unchecked_param_type = "ScrambleRecording")] recording: JsValue,
+    ) -> Result<FramePlayer, JsError> {
+        let recording: Recording = serde_wasm_bindgen::from_value(recording)?;
+        FramePlayer::with_recording(target, recording)
+    }
+
+    /// Called once the last frame has been drawn.
+    pub fn set_on_end(&mut self, callback: js_sys::Function) {
+        self.state.borrow_mut().on_end = Some(callback);
+    }
+
+    /// Plays the frames from the start, restarting playback if it is running.
+    pub fn play(&mut self) -> Result<(), JsError> {
+        let mut state = self.state.borrow_mut();
+        state.cancel_frame();
+        state.written = 0;
+        state.start_time = None;
+        if state.frames.is_empty() {
+            return Ok(());
+        }
+        // Frames at time 0 are drawn right away
+        state.draw(0.0);
+        state.request_frame()
+    }
+
+    /// Stops playback, leaving the current frame in place.
+    pub fn stop(&mut self) {
+        let mut state = self.state.borrow_mut();
+        state.cancel_frame();
+        state.start_time = None;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn is_playing(&self) -> bool {
+        self.state.borrow().request_id.is_some()
+    }
+
+    /// Time of the last frame, in milliseconds.
+    #[wasm_bindgen(getter)]
+    pub fn duration(&self) -> f64 {
+        self.state
+            .borrow()
+            .frames
+            .last()
+            .map_or(0.0, |frame| frame.time)
+    }
+
+    /// Number of recorded frames.
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.state.borrow().frames.len()
+    }
+}
+
+impl FramePlayer {
+    /// Creates a player from a recording made in Rust.
+    pub fn with_recording(target: Node, recording: Recording) -> Result<FramePlayer, JsError> {
+        let mut frames = recording.frames;
+        frames.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+        let state = Rc::new(RefCell::new(PlayerState {
+            target: RenderTarget::new(target, &WriteTarget::TextContent)?,
+            frames,
+            written: 0,
+            start_time: None,
+            request_id: None,
+            on_end: None,
+            animate: None,
+        }));
+        let weak_state = Rc::downgrade(&state);
+        state.borrow_mut().animate = Some(Closure::new(move |timestamp: f64| {
+            on_frame(&weak_state, timestamp)
+        }));
+        Ok(FramePlayer { state })
+    }
+}
+
+impl Drop for FramePlayer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
@@ -0,0 +1,39 @@
+//! Post-processing of drawn frames, for effects the engine doesn't draw itself, such as
+//! color tags, zalgo or sparklines, without forking the draw loop.
+
+use crate::engine::{Frame, ScrambleControl};
+
+/// Changes each frame an engine draws before it is shown. Plugins run in the order they were
+/// added, once the engine has drawn the frame and settled whether it completes the run.
+pub trait FramePlugin {
+    /// Changes `frame`, given the state of each position of the current chunk.
+    fn process(&mut self, frame: &mut Frame, controls: &[ScrambleControl]);
+}
+
+impl<F: FnMut(&mut Frame, &[ScrambleControl])> FramePlugin for F {
+    fn process(&mut self, frame: &mut Frame, controls: &[ScrambleControl]) {
+        self(frame, controls)
+    }
+}
+
+/// JS function added with `ScrambleText.add_plugin()`, called with the text of the frame
+/// and the state of each position. A string it returns replaces the text.
+#[cfg(feature = "web")]
+pub(crate) struct JsPlugin(pub(crate) js_sys::Function);
+
+#[cfg(feature = "web")]
+impl FramePlugin for JsPlugin {
+    fn process(&mut self, frame: &mut Frame, controls: &[ScrambleControl]) {
+        use wasm_bindgen::JsValue;
+
+        let Ok(controls) = serde_wasm_bindgen::to_value(controls) else {
+            return;
+        };
+        let text = JsValue::from_str(&frame.text);
+        let processed = self.0.call2(&JsValue::null(), &text, &controls);
+        if let Some(text) = processed.ok().and_then(|text| text.as_string()) {
+            frame.text = text;
+            frame.offsets.clear();
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! Random numbers generated a block at a time.
+//!
+//! A frame may need a random number for every character of the text. Rather than calling
+//! into the generator for each, the engine fills a block once per frame and hands out its
+//! values in turn.
+//!
+//! With the `small-rng` feature the values come from a `SmallRng` seeded once per engine,
+//! from `crypto.getRandomValues` in the browser, rather than from `thread_rng`.
+//!
+//! An engine seeded with [`ScrambleEngine::seed_random`](crate::ScrambleEngine::seed_random)
+//! draws from a `StdRng` instead, giving the same values with or without `small-rng`.
+
+#[cfg(feature = "small-rng")]
+use rand::rngs::SmallRng;
+use rand::rngs::StdRng;
+#[cfg(not(feature = "small-rng"))]
+use rand::rngs::ThreadRng;
+use rand::Rng;
+use rand::SeedableRng;
+
+use crate::engine::RandomState;
+#[cfg(feature = "small-rng")]
+use std::cell::RefCell;
+
+#[cfg(feature = "small-rng")]
+type BlockRng = SmallRng;
+#[cfg(not(feature = "small-rng"))]
+type BlockRng = ThreadRng;
+
+#[cfg(feature = "small-rng")]
+fn new_rng() -> BlockRng {
+    SmallRng::from_entropy()
+}
+
+#[cfg(not(feature = "small-rng"))]
+fn new_rng() -> BlockRng {
+    rand::thread_rng()
+}
+
+#[cfg(feature = "small-rng")]
+thread_local! {
+    /// Generator for the numbers drawn outside of an engine.
+    static RNG: RefCell<SmallRng> = RefCell::new(SmallRng::from_entropy());
+}
+
+/// A random integer between `min` and `max` inclusive.
+#[cfg(feature = "small-rng")]
+pub(crate) fn gen_int(min: i32, max: i32) -> i32 {
+    RNG.with(|rng| rng.borrow_mut().gen_range(min..=max))
+}
+
+/// A random integer between `min` and `max` inclusive.
+#[cfg(not(feature = "small-rng"))]
+pub(crate) fn gen_int(min: i32, max: i32) -> i32 {
+    rand::thread_rng().gen_range(min..=max)
+}
+
+/// Size of a block refilled before the first one was sized.
+const MIN_BLOCK_LEN: usize = 64;
+
+/// Values skipped at a time when restoring a seeded generator.
+const SKIP_BLOCK_LEN: usize = 4096;
+
+enum Generator {
+    Default(BlockRng),
+    Seeded(Box<StdRng>),
+}
+
+pub(crate) struct RandomBlock {
+    rng: Generator,
+    /// Seed of a seeded generator, and the number of values drawn from it since
+    seeded: Option<RandomState>,
+    values: Vec<u32>,
+    next: usize,
+}
+
+impl Default for RandomBlock {
+    fn default() -> Self {
+        RandomBlock {
+            rng: Generator::Default(new_rng()),
+            seeded: None,
+            values: Vec::new(),
+            next: 0,
+        }
+    }
+}
+
+impl RandomBlock {
+    /// Draws from a generator seeded with `seed` from here on, dropping the values left.
+    pub(crate) fn seed(&mut self, seed: u64) {
+        self.rng = Generator::Seeded(Box::new(StdRng::seed_from_u64(seed)));
+        self.seeded = Some(RandomState { seed, drawn: 0 });
+        self.next = self.values.len();
+    }
+
+    /// Where a seeded generator is in its stream, or `None` for an unseeded one.
+    pub(crate) fn state(&self) -> Option<RandomState> {
+        self.seeded.clone()
+    }
+
+    /// Seeds the generator like [`seed`](Self::seed), then skips the values drawn before
+    /// `state` was taken, so the same values follow.
+    pub(crate) fn restore(&mut self, state: &RandomState) {
+        self.seed(state.seed);
+        let mut left = state.drawn;
+        while left > 0 {
+            let len = left.min(SKIP_BLOCK_LEN as u64) as usize;
+            self.refill(len);
+            left -= len as u64;
+        }
+        self.next = self.values.len();
+    }
+
+    /// Replaces the block with `len` fresh values.
+    pub(crate) fn refill(&mut self, len: usize) {
+        self.values.resize(len.max(1), 0);
+        match &mut self.rng {
+            Generator::Default(rng) => rng.fill(&mut self.values[..]),
+            Generator::Seeded(rng) => rng.fill(&mut self.values[..]),
+        }
+        if let Some(seeded) = &mut self.seeded {
+            seeded.drawn += self.values.len() as u64;
+        }
+        self.next = 0;
+    }
+
+    /// A random integer between `min` and `max` inclusive, like [`random_int`](crate::random_int).
+    /// A used up block is refilled at its current size.
+    pub(crate) fn int(&mut self, min: i32, max: i32) -> i32 {
+        if self.next >= self.values.len() {
+            self.refill(self.values.len().max(MIN_BLOCK_LEN));
+        }
+        let value = self.values[self.next];
+        self.next += 1;
+
+        // Scales the value onto the range, which is close enough to uniform for visual noise
+        let span = (max as i64 - min as i64 + 1) as u64;
+        (min as i64 + ((value as u64 * span) >> 32) as i64) as i32
+    }
+}
@@ -0,0 +1,106 @@
+//! Drop-in replacement for the `useScramble` hook of the `use-scramble` react package.
+//!
+//! ```jsx
+//! import { useScramble } from 'scramble-text';
+//!
+//! const { ref, replay } = useScramble({ text: 'Hello', speed: 0.6 });
+//! return <p ref={ref} onMouseOver={replay} />;
+//! ```
+//!
+//! The snippet imports `react`, so it is only built with the `react` feature.
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::Node;
+
+use crate::web::ScrambleText;
+
+#[wasm_bindgen(inline_js = r#"
+import { useCallback, useEffect, useRef } from 'react';
+
+// Converts use-scramble's camelCase props, where a range is an array of two bounds or of
+// several codes
+function toProps(props) {
+  const {
+    playOnMount,
+    range,
+    onAnimationStart,
+    onAnimationFrame,
+    onAnimationEnd,
+    ...rest
+  } = props;
+  const converted = { ...rest, text: props.text ?? '' };
+  if (playOnMount !== undefined) {
+    converted.play_on_mount = playOnMount;
+  }
+  if (Array.isArray(range)) {
+    converted.range = range.length === 2 ? { Range: range } : { Codes: range };
+  }
+  return converted;
+}
+
+export function useScramble(props, create) {
+  const ref = useRef(null);
+  const scrambleRef = useRef(null);
+
+  // Callbacks are read when they fire, so new ones don't recreate the animation
+  const propsRef = useRef(props);
+  propsRef.current = props;
+
+  const { text, speed, max_fps, tick, step, chance, seed, scramble, overdrive, overflow } = props;
+  const range = String(props.range);
+  const ignore = String(props.ignore);
+
+  useEffect(() => {
+    if (!ref.current) {
+      return undefined;
+    }
+
+    const instance = create(ref.current, toProps(propsRef.current));
+    if (instance instanceof Error) {
+      throw instance;
+    }
+    instance.set_on_animation_start(() => propsRef.current.onAnimationStart?.());
+    instance.set_on_animation_frame((text, frame) => propsRef.current.onAnimationFrame?.(text, frame));
+    instance.set_on_animation_end(() => propsRef.current.onAnimationEnd?.());
+    if (propsRef.current.playOnMount !== false) {
+      instance.start();
+    }
+
+    scrambleRef.current = instance;
+    return () => {
+      scrambleRef.current = null;
+      instance.dispose();
+      instance.free();
+    };
+  }, [text, speed, max_fps, tick, step, chance, seed, scramble, overdrive, overflow, range, ignore]);
+
+  const replay = useCallback(() => {
+    scrambleRef.current?.start();
+  }, []);
+
+  return { ref, replay };
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = useScramble)]
+    fn use_scramble_hook(props: JsValue, create: &js_sys::Function) -> JsValue;
+}
+
+thread_local! {
+    /// Creates the instances of every hook. Errors are returned rather than thrown, for the
+    /// hook to rethrow.
+    static CREATE: Closure<dyn FnMut(Node, JsValue) -> JsValue> =
+        Closure::new(|target: Node, props: JsValue| match ScrambleText::new(target, props) {
+            Ok(scramble) => scramble.into(),
+            Err(e) => e.into(),
+        });
+}
+
+/// React hook with the API of `use-scramble`: takes its camelCase props, including the
+/// `onAnimationStart`, `onAnimationFrame` and `onAnimationEnd` callbacks, and returns
+/// `{ ref, replay }`. The animation is recreated when a prop changes, and disposed on unmount.
+#[wasm_bindgen(js_name = useScramble, unchecked_return_type = "{ ref: { current: HTMLElement | null }, replay: () => void }")]
+pub fn use_scramble_react(props: JsValue) -> JsValue {
+    CREATE.with(|create| use_scramble_hook(props, create.as_ref().unchecked_ref()))
+}
@@ -0,0 +1,376 @@
+//! Write strategies for the node an animation draws into.
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, Node, Text};
+
+use crate::engine::Frame;
+use crate::scramble::WriteTarget;
+use crate::span_text::SpanText;
+use crate::virtual_text::VirtualText;
+
+const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+const PROGRESS_VAR: &str = "--scramble-progress";
+const ACTIVE_INDEX_VAR: &str = "--scramble-active-index";
+
+/// Collects the non-blank text nodes below `node` in document order.
+fn text_nodes(node: &Node, found: &mut Vec<Text>) {
+    let children = node.child_nodes();
+    for child in (0..children.length()).filter_map(|i| children.get(i)) {
+        match child.dyn_into::<Text>() {
+            Ok(text) if !text.data().trim().is_empty() => found.push(text),
+            Ok(_) => {}
+            Err(child) => text_nodes(&child, found),
+        }
+    }
+}
+
+/// Writes `text` across `segments`: each node receives as many positions of the text as it
+/// originally held, and the last one takes whatever remains. `offsets` are the frame's
+/// [`Frame::offsets`]; without them, every position counts as one character.
+fn write_segments<T>(
+    segments: &[(T, usize)],
+    text: &str,
+    offsets: &[usize],
+    write: impl Fn(&T, &str),
+) {
+    let (mut rest, mut written) = (text, 0);
+    let mut position = 0;
+    for (i, (node, len)) in segments.iter().enumerate() {
+        position += len;
+        let split = if i + 1 == segments.len() {
+            rest.len()
+        } else {
+            let end = if offsets.is_empty() {
+                position
+            } else {
+                offsets.get(position).copied().unwrap_or(usize::MAX)
+            };
+            rest.char_indices()
+                .nth(end.saturating_sub(written))
+                .map_or(rest.len(), |(index, _)| index)
+        };
+        let (head, tail) = rest.split_at(split);
+        write(node, head);
+        written += head.chars().count();
+        rest = tail;
+    }
+}
+
+/// Closest element containing all of `elements`.
+fn common_ancestor(elements: &[(Element, usize)]) -> Option<Element> {
+    let mut ancestor = elements.first()?.0.parent_element()?;
+    while !elements
+        .iter()
+        .all(|(element, _)| ancestor.contains(Some(element)))
+    {
+        ancestor = ancestor.parent_element()?;
+    }
+    Some(ancestor)
+}
+
+pub(crate) enum RenderTarget {
+    /// Text nodes are updated in place, leaving their siblings untouched.
+    Text(Text),
+    /// Elements have their children replaced by the frame text.
+    Element(Element),
+    /// Text spread over several text nodes inside an element, such as the `<tspan>`s of an
+    /// SVG `<text>`. The nodes, and the positioning attributes around them, are kept.
+    Segments(Element, Vec<(Text, usize)>),
+    /// Text spread over separate elements, such as one per line or word from a text splitter.
+    /// Each keeps as many characters as it held to begin with, the last taking the rest.
+    Distributed(Vec<(Element, usize)>),
+    /// Any other node, such as a document fragment or shadow root.
+    Node(Node),
+    /// The `value` property of a form control.
+    Value(Element),
+    /// An attribute such as `placeholder` or `title`.
+    Attribute(Element, String),
+    /// Long text split into chunks, only those near the viewport being rewritten.
+    Virtual(VirtualText),
+    /// A `<span>` per character, marked with its state in each frame.
+    Spans(SpanText),
+}
+
+/// SVG `<text>` elements hold their glyphs in `<tspan>`s that must survive each frame.
+fn is_svg_text(element: &Element) -> bool {
+    element.namespace_uri().as_deref() == Some(SVG_NAMESPACE)
+        && element.local_name() == "text"
+        && element.child_element_count() > 0
+}
+
+impl RenderTarget {
+    pub(crate) fn new(node: Node, target: &WriteTarget) -> Result<RenderTarget, JsError> {
+        let node = match node.dyn_into::<Text>() {
+            Ok(text) if *target == WriteTarget::TextContent => return Ok(RenderTarget::Text(text)),
+            Ok(_) => {
+                return Err(JsError::new(
+                    "Text nodes can only be written as textContent",
+                ))
+            }
+            Err(node) => node,
+        };
+
+        match (node.dyn_into::<Element>(), target) {
+            (Ok(element), WriteTarget::TextContent) if is_svg_text(&element) => {
+                let mut nodes = Vec::new();
+                text_nodes(&element, &mut nodes);
+                let segments = nodes
+                    .into_iter()
+                    .map(|node| {
+                        let len = node.data().chars().count();
+                        (node, len)
+                    })
+                    .collect::<Vec<_>>();
+
+                if segments.is_empty() {
+                    Ok(RenderTarget::Element(element))
+                } else {
+                    Ok(RenderTarget::Segments(element, segments))
+                }
+            }
+            (Ok(element), WriteTarget::TextContent) => Ok(RenderTarget::Element(element)),
+            (Ok(element), WriteTarget::Value) => Ok(RenderTarget::Value(element)),
+            (Ok(element), WriteTarget::Attribute(name)) => {
+                Ok(RenderTarget::Attribute(element, name.clone()))
+            }
+            (Err(node), WriteTarget::TextContent) => Ok(RenderTarget::Node(node)),
+            (Err(_), _) => Err(JsError::new(
+                "Only elements can be written as a value or attribute",
+            )),
+        }
+    }
+
+    /// Draws one text across `elements`, in order, for `ScrambleText.across()`.
+    pub(crate) fn distributed(
+        elements: Vec<Element>,
+        target: &WriteTarget,
+    ) -> Result<RenderTarget, JsError> {
+        if *target != WriteTarget::TextContent {
+            return Err(JsError::new(
+                "Text across several elements can only be written as textContent",
+            ));
+        }
+        if elements.is_empty() {
+            return Err(JsError::new("No elements to draw the text across"));
+        }
+        let elements = elements
+            .into_iter()
+            .map(|element| {
+                let len = element.text_content().unwrap_or_default().chars().count();
+                (element, len)
+            })
+            .collect();
+        Ok(RenderTarget::Distributed(elements))
+    }
+
+    /// Switches an element target to virtualized rendering.
+    pub(crate) fn virtualize(self) -> Result<RenderTarget, JsError> {
+        match self {
+            RenderTarget::Element(element) => VirtualText::new(element)
+                .map(RenderTarget::Virtual)
+                .map_err(|e| JsError::new(&format!("Failed to virtualize: {:?}", e))),
+            _ => Err(JsError::new(
+                "Only elements written as textContent can be virtualized",
+            )),
+        }
+    }
+
+    /// Switches an element target to drawing each character in its own span.
+    pub(crate) fn spans(self) -> Result<RenderTarget, JsError> {
+        match self {
+            RenderTarget::Element(element) => Ok(RenderTarget::Spans(SpanText::new(element))),
+            _ => Err(JsError::new(
+                "Only elements written as textContent can be drawn in spans",
+            )),
+        }
+    }
+
+    /// Readies the target for a run revealing `text`.
+    pub(crate) fn prepare(&self, text: &str) -> Result<(), JsError> {
+        match self {
+            RenderTarget::Virtual(virtual_text) => virtual_text
+                .prepare(text)
+                .map_err(|e| JsError::new(&format!("Failed to split the text: {:?}", e))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Characters near the viewport, for virtualized targets that are partly off screen.
+    pub(crate) fn visible_range(&self) -> Option<std::ops::Range<usize>> {
+        match self {
+            RenderTarget::Virtual(virtual_text) => virtual_text.visible_range(),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn write(&self, text: &str) {
+        self.write_split(text, &[]);
+    }
+
+    /// Writes `text`, split across segments at the character indices in `offsets`.
+    pub(crate) fn write_split(&self, text: &str, offsets: &[usize]) {
+        match self {
+            RenderTarget::Text(node) => node.set_data(text),
+            RenderTarget::Element(element) => element.set_text_content(Some(text)),
+            RenderTarget::Segments(_, segments) => {
+                write_segments(segments, text, offsets, |node, slice| node.set_data(slice))
+            }
+            RenderTarget::Distributed(elements) => {
+                write_segments(elements, text, offsets, |element, slice| {
+                    element.set_text_content(Some(slice))
+                })
+            }
+            RenderTarget::Node(node) => node.set_text_content(Some(text)),
+            RenderTarget::Value(element) => {
+                let _ = js_sys::Reflect::set(element, &"value".into(), &text.into());
+            }
+            RenderTarget::Attribute(element, name) => {
+                let _ = element.set_attribute(name, text);
+            }
+            RenderTarget::Virtual(virtual_text) => virtual_text.write(text),
+            RenderTarget::Spans(spans) => spans.write(text, None),
+        }
+    }
+
+    /// Writes a drawn frame. Span targets also mark the state of each character, and targets
+    /// spread over several nodes split it by position.
+    pub(crate) fn write_frame(&self, frame: &Frame) {
+        match self {
+            RenderTarget::Spans(spans) => spans.write(&frame.text, Some(frame)),
+            _ => self.write_split(&frame.text, &frame.offsets),
+        }
+    }
+
+    /// Position in the text of the character drawn in `node`, for span targets.
+    pub(crate) fn char_index(&self, node: &Node) -> Option<usize> {
+        match self {
+            RenderTarget::Spans(spans) => spans.char_index(node),
+            _ => None,
+        }
+    }
+
+    /// Whether the target changes with every frame, even one showing the same text as the
+    /// last. Span targets count the age of settled characters.
+    pub(crate) fn ages(&self) -> bool {
+        matches!(self, RenderTarget::Spans(_))
+    }
+
+    /// Whether the target is currently in a document.
+    pub(crate) fn is_connected(&self) -> bool {
+        match self {
+            RenderTarget::Text(node) => node.is_connected(),
+            RenderTarget::Element(element)
+            | RenderTarget::Segments(element, _)
+            | RenderTarget::Value(element)
+            | RenderTarget::Attribute(element, _) => element.is_connected(),
+            RenderTarget::Node(node) => node.is_connected(),
+            RenderTarget::Distributed(elements) => {
+                elements.iter().any(|(element, _)| element.is_connected())
+            }
+            RenderTarget::Virtual(virtual_text) => virtual_text.element().is_connected(),
+            RenderTarget::Spans(spans) => spans.element().is_connected(),
+        }
+    }
+
+    /// Element whose text content the frames replace, if any.
+    pub(crate) fn text_element(&self) -> Option<&Element> {
+        match self {
+            RenderTarget::Element(element) | RenderTarget::Segments(element, _) => Some(element),
+            RenderTarget::Virtual(virtual_text) => Some(virtual_text.element()),
+            RenderTarget::Spans(spans) => Some(spans.element()),
+            _ => None,
+        }
+    }
+
+    /// Whether the element the target draws into lays text out right to left.
+    pub(crate) fn is_rtl(&self) -> bool {
+        self.event_element()
+            .and_then(|element| web_sys::window()?.get_computed_style(&element).ok()?)
+            .and_then(|style| style.get_property_value("direction").ok())
+            .is_some_and(|direction| direction == "rtl")
+    }
+
+    /// Element that receives pointer events for the target. Text nodes use their parent, and
+    /// text across several elements the closest element containing them all.
+    pub(crate) fn event_element(&self) -> Option<Element> {
+        match self {
+            RenderTarget::Text(node) => node.parent_element(),
+            RenderTarget::Element(element)
+            | RenderTarget::Segments(element, _)
+            | RenderTarget::Value(element)
+            | RenderTarget::Attribute(element, _) => Some(element.clone()),
+            RenderTarget::Node(node) => node.dyn_ref::<Element>().cloned(),
+            RenderTarget::Distributed(elements) => common_ancestor(elements),
+            RenderTarget::Virtual(virtual_text) => Some(virtual_text.element().clone()),
+            RenderTarget::Spans(spans) => Some(spans.element().clone()),
+        }
+    }
+}
+
+/// Inline `white-space: pre-wrap` on the element a run draws into, set with the `whitespace:
+/// "pre"` prop so runs of spaces in the frames keep their width. The previous inline value is
+/// put back on drop.
+pub(crate) struct PreservedWhitespace {
+    element: HtmlElement,
+    previous: String,
+}
+
+impl PreservedWhitespace {
+    pub(crate) fn new(target: &RenderTarget) -> Option<PreservedWhitespace> {
+        let element = target.event_element()?.dyn_into::<HtmlElement>().ok()?;
+        let style = element.style();
+        let previous = style.get_property_value("white-space").ok()?;
+        style.set_property("white-space", "pre-wrap").ok()?;
+        Some(PreservedWhitespace { element, previous })
+    }
+}
+
+impl Drop for PreservedWhitespace {
+    fn drop(&mut self) {
+        let style = self.element.style();
+        if self.previous.is_empty() {
+            let _ = style.remove_property("white-space");
+        } else {
+            let _ = style.set_property("white-space", &self.previous);
+        }
+    }
+}
+
+/// Inline `--scramble-progress` and `--scramble-active-index` custom properties on the element
+/// a run draws into, set with the `css_vars` prop so that styles can follow the reveal. Both
+/// are removed on drop.
+pub(crate) struct ProgressVars {
+    element: HtmlElement,
+    /// Values last set, so that frames which don't move the reveal leave the style alone
+    last: Option<(f64, usize)>,
+}
+
+impl ProgressVars {
+    pub(crate) fn new(target: &RenderTarget) -> Option<ProgressVars> {
+        let element = target.event_element()?.dyn_into::<HtmlElement>().ok()?;
+        Some(ProgressVars {
+            element,
+            last: None,
+        })
+    }
+
+    /// Sets the share of the text revealed (0-1), and the index of the next character to be.
+    pub(crate) fn update(&mut self, progress: f64, active_index: usize) {
+        if self.last == Some((progress, active_index)) {
+            return;
+        }
+        self.last = Some((progress, active_index));
+        let style = self.element.style();
+        let _ = style.set_property(PROGRESS_VAR, &progress.to_string());
+        let _ = style.set_property(ACTIVE_INDEX_VAR, &active_index.to_string());
+    }
+}
+
+impl Drop for ProgressVars {
+    fn drop(&mut self) {
+        let style = self.element.style();
+        let _ = style.remove_property(PROGRESS_VAR);
+        let _ = style.remove_property(ACTIVE_INDEX_VAR);
+    }
+}
@@ -0,0 +1,395 @@
+//! Shared `requestAnimationFrame` loop driving every running `ScrambleText`.
+//!
+//! Runs with the `idle` prop are driven by a second loop of `requestIdleCallback`s instead,
+//! where the browser supports it, so they only draw once more urgent work is done.
+
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::clock::Clock;
+use crate::error::ScrambleError;
+use crate::motion::reduced_motion_query;
+use crate::web::{
+    cancel_delayed_start, complete_animation, compute_frame, dispose_animation, finish_frame,
+    stop_animation, write_frame, AnimationState,
+};
+
+/// The frame the scheduler is waiting for.
+enum PendingFrame {
+    Animation(i32),
+    /// Animation frames never fire in hidden documents, so animations that keep running while
+    /// hidden are driven by a timer instead.
+    Timeout(i32),
+    Idle(u32),
+}
+
+/// Which of the two loops drives an animation.
+#[derive(Clone, Copy, PartialEq)]
+enum Lane {
+    Frame,
+    Idle,
+}
+
+/// Longest an idle run waits for the browser to go idle before drawing anyway, in milliseconds.
+const IDLE_TIMEOUT: u32 = 250;
+
+/// The state of one loop.
+#[derive(Default)]
+struct LoopState {
+    pending: Option<PendingFrame>,
+    clock: Clock,
+}
+
+/// Page conditions that may pause animations.
+#[derive(Clone, Copy, Default)]
+struct PageState {
+    hidden: bool,
+    blurred: bool,
+    /// Set by `pause_all()`, holding every animation regardless of its props.
+    frozen: bool,
+}
+
+/// Lazily created singleton holding the running animations. Instances register when started
+/// and are dropped from the loop once they stop, so no frames are requested while idle.
+#[derive(Default)]
+struct ScrambleScheduler {
+    animations: Vec<Weak<RefCell<AnimationState>>>,
+    /// Every instance not yet disposed, running or not, for `stop_all()`.
+    live: Vec<Weak<RefCell<AnimationState>>>,
+    frame_loop: LoopState,
+    idle_loop: LoopState,
+    page: PageState,
+    page_listeners: Vec<Closure<dyn FnMut()>>,
+}
+
+impl ScrambleScheduler {
+    fn lane_loop(&mut self, lane: Lane) -> &mut LoopState {
+        match lane {
+            Lane::Frame => &mut self.frame_loop,
+            Lane::Idle => &mut self.idle_loop,
+        }
+    }
+}
+
+/// Lists used while drawing a frame, kept between frames to reuse their allocations.
+#[derive(Default)]
+struct FrameScratch {
+    animations: Vec<Weak<RefCell<AnimationState>>>,
+    drawn: Vec<Rc<RefCell<AnimationState>>>,
+    detached: Vec<Rc<RefCell<AnimationState>>>,
+}
+
+thread_local! {
+    static SCHEDULER: RefCell<ScrambleScheduler> = RefCell::default();
+    static SCRATCH: RefCell<FrameScratch> = RefCell::default();
+    static FRAME_CALLBACK: Closure<dyn FnMut(f64)> = Closure::new(on_frame);
+    static TIMEOUT_CALLBACK: Closure<dyn FnMut()> = Closure::new(|| on_frame(Clock::now()));
+    static IDLE_CALLBACK: Closure<dyn FnMut()> = Closure::new(|| run_lane(Lane::Idle, Clock::now()));
+    static SUPPORTS_IDLE: bool = web_sys::window()
+        .is_some_and(|window| js_sys::Reflect::has(&window, &"requestIdleCallback".into()).unwrap_or(false));
+}
+
+fn is_paused(state: &RefCell<AnimationState>, page: PageState) -> bool {
+    page.frozen
+        || state
+            .try_borrow()
+            .map_or(true, |state| state.is_paused(page.hidden, page.blurred))
+}
+
+/// Whether the page holds the animation, as with `pause_all()` or its pause props.
+pub(crate) fn is_held(state: &RefCell<AnimationState>) -> bool {
+    let page = SCHEDULER.with(|scheduler| scheduler.borrow().page);
+    is_paused(state, page)
+}
+
+/// The loop driving an animation. Idle runs fall back to animation frames where
+/// `requestIdleCallback` is missing, as in Safari.
+fn lane_of(state: &RefCell<AnimationState>) -> Lane {
+    let idle = state.try_borrow().is_ok_and(|state| state.is_idle());
+    if idle && SUPPORTS_IDLE.with(|supported| *supported) {
+        Lane::Idle
+    } else {
+        Lane::Frame
+    }
+}
+
+fn wants_frame(state: &RefCell<AnimationState>, page: PageState, lane: Lane) -> bool {
+    lane_of(state) == lane
+        && !is_paused(state, page)
+        && state.try_borrow().is_ok_and(|state| state.wants_frame())
+}
+
+/// Requests the next frame of both loops, for the animations that have one to draw.
+fn request_frame(scheduler: &mut ScrambleScheduler) -> Result<(), JsError> {
+    request_lane_frame(scheduler, Lane::Frame)?;
+    request_lane_frame(scheduler, Lane::Idle)
+}
+
+fn request_lane_frame(scheduler: &mut ScrambleScheduler, lane: Lane) -> Result<(), JsError> {
+    if scheduler.lane_loop(lane).pending.is_some() {
+        return Ok(());
+    }
+
+    // Nothing to draw until the page changes again
+    let page = scheduler.page;
+    if !scheduler
+        .animations
+        .iter()
+        .filter_map(Weak::upgrade)
+        .any(|state| wants_frame(&state, page, lane))
+    {
+        scheduler.lane_loop(lane).clock.reset();
+        return Ok(());
+    }
+
+    let window = web_sys::window().ok_or(ScrambleError::NoWindow)?;
+    if lane == Lane::Idle {
+        let options = web_sys::IdleRequestOptions::new();
+        options.set_timeout(IDLE_TIMEOUT);
+        let id = IDLE_CALLBACK
+            .with(|callback| {
+                window
+                    .request_idle_callback_with_options(callback.as_ref().unchecked_ref(), &options)
+            })
+            .map_err(|_| JsError::new("Failed to request idle callback"))?;
+        scheduler.idle_loop.pending = Some(PendingFrame::Idle(id));
+    } else if !page.hidden {
+        let id = FRAME_CALLBACK
+            .with(|callback| window.request_animation_frame(callback.as_ref().unchecked_ref()))
+            .map_err(|_| JsError::new("Failed to request animation frame"))?;
+        scheduler.frame_loop.pending = Some(PendingFrame::Animation(id));
+    } else {
+        let id = TIMEOUT_CALLBACK
+            .with(|callback| {
+                window.set_timeout_with_callback_and_timeout_and_arguments_0(
+                    callback.as_ref().unchecked_ref(),
+                    1000 / 60,
+                )
+            })
+            .map_err(|_| JsError::new("Failed to start animation timer"))?;
+        scheduler.frame_loop.pending = Some(PendingFrame::Timeout(id));
+    }
+    Ok(())
+}
+
+/// Cancels the pending frames of both loops, and forgets their last frame times.
+fn cancel_frame(scheduler: &mut ScrambleScheduler) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    for lane_loop in [&mut scheduler.frame_loop, &mut scheduler.idle_loop] {
+        lane_loop.clock.reset();
+        match lane_loop.pending.take() {
+            Some(PendingFrame::Animation(id)) => {
+                let _ = window.cancel_animation_frame(id);
+            }
+            Some(PendingFrame::Timeout(id)) => window.clear_timeout_with_handle(id),
+            Some(PendingFrame::Idle(id)) => window.cancel_idle_callback(id),
+            None => {}
+        }
+    }
+}
+
+fn on_frame(timestamp: f64) {
+    run_lane(Lane::Frame, timestamp);
+}
+
+/// Draws the due frames of the animations driven by `lane`.
+fn run_lane(lane: Lane, timestamp: f64) {
+    let mut scratch = SCRATCH.with(RefCell::take);
+    let (delta, page) = SCHEDULER.with(|scheduler| {
+        let mut scheduler = scheduler.borrow_mut();
+        let lane_loop = scheduler.lane_loop(lane);
+        lane_loop.pending = None;
+        let delta = lane_loop.clock.delta_to(timestamp);
+        scratch
+            .animations
+            .extend(scheduler.animations.iter().cloned());
+        (delta, scheduler.page)
+    });
+
+    // Compute every due frame before touching the DOM, then write them all in one pass so
+    // that writes are never interleaved with other instances' work
+    for state in scratch
+        .animations
+        .drain(..)
+        .filter_map(|state| state.upgrade())
+    {
+        if lane_of(&state) != lane || is_paused(&state, page) {
+            continue;
+        }
+        if state.borrow_mut().left_document() {
+            scratch.detached.push(state);
+        } else if compute_frame(&state, delta) {
+            scratch.drawn.push(state);
+        }
+    }
+    for state in &scratch.drawn {
+        write_frame(state);
+    }
+
+    // The scheduler is not borrowed here, so callbacks may start or stop other instances
+    for state in scratch.drawn.drain(..) {
+        finish_frame(&state);
+    }
+    for state in scratch.detached.drain(..) {
+        dispose_animation(&state);
+    }
+    SCRATCH.with(|cell| *cell.borrow_mut() = scratch);
+
+    SCHEDULER.with(|scheduler| {
+        let mut scheduler = scheduler.borrow_mut();
+        scheduler.animations.retain(|state| {
+            state
+                .upgrade()
+                .is_some_and(|state| state.try_borrow().map_or(true, |state| state.is_running()))
+        });
+
+        if scheduler.animations.is_empty() {
+            scheduler.frame_loop.clock.reset();
+            scheduler.idle_loop.clock.reset();
+        } else if let Err(e) = request_frame(&mut scheduler) {
+            web_sys::console::error_1(&e.into());
+        }
+    });
+}
+
+/// Applies a page change and reschedules, without counting the time spent paused towards the
+/// paused animations.
+fn on_page_change(update: impl FnOnce(&mut PageState)) {
+    SCHEDULER.with(|scheduler| {
+        let mut scheduler = scheduler.borrow_mut();
+        update(&mut scheduler.page);
+        cancel_frame(&mut scheduler);
+        if let Err(e) = request_frame(&mut scheduler) {
+            web_sys::console::error_1(&e.into());
+        }
+    });
+}
+
+/// Snaps the animations that respect the preference to their final frame once the user turns
+/// on reduced motion. Later runs pick up the preference when they start.
+fn on_reduced_motion_change() {
+    if !reduced_motion_query().is_some_and(|media| media.matches()) {
+        return;
+    }
+
+    let animations = SCHEDULER.with(|scheduler| scheduler.borrow().animations.clone());
+    for state in animations.iter().filter_map(Weak::upgrade) {
+        if state.borrow().respects_reduced_motion() {
+            complete_animation(&state);
+        }
+    }
+}
+
+/// Listens for visibility, focus and reduced motion changes for the lifetime of the page.
+fn watch_page(scheduler: &mut ScrambleScheduler) -> Result<(), JsError> {
+    if !scheduler.page_listeners.is_empty() {
+        return Ok(());
+    }
+
+    let window = web_sys::window().ok_or(ScrambleError::NoWindow)?;
+    let document = window.document().ok_or(ScrambleError::NoDocument)?;
+
+    let on_visibility_change = {
+        let document = document.clone();
+        Closure::<dyn FnMut()>::new(move || {
+            let hidden = document.hidden();
+            on_page_change(|page| page.hidden = hidden);
+        })
+    };
+    let on_blur = Closure::<dyn FnMut()>::new(|| on_page_change(|page| page.blurred = true));
+    let on_focus = Closure::<dyn FnMut()>::new(|| on_page_change(|page| page.blurred = false));
+
+    let listeners: [(&web_sys::EventTarget, _, _); 3] = [
+        (&document, "visibilitychange", on_visibility_change),
+        (&window, "blur", on_blur),
+        (&window, "focus", on_focus),
+    ];
+    for (target, event, closure) in listeners {
+        target
+            .add_event_listener_with_callback(event, closure.as_ref().unchecked_ref())
+            .map_err(|e| JsError::new(&format!("Failed to add event listener: {:?}", e)))?;
+        scheduler.page_listeners.push(closure);
+    }
+
+    if let Some(media) = reduced_motion_query() {
+        let closure = Closure::<dyn FnMut()>::new(on_reduced_motion_change);
+        media
+            .add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())
+            .map_err(|e| JsError::new(&format!("Failed to add event listener: {:?}", e)))?;
+        scheduler.page_listeners.push(closure);
+    }
+
+    // A page may start out hidden or unfocused, e.g. in a background iframe
+    scheduler.page.hidden = document.hidden();
+    scheduler.page.blurred = !document
+        .has_focus()
+        .map_err(|e| JsError::new(&format!("Failed to read document focus: {:?}", e)))?;
+    Ok(())
+}
+
+/// Adds a started animation to the shared loop, starting the loop if it is idle.
+pub(crate) fn register(state: &Rc<RefCell<AnimationState>>) -> Result<(), JsError> {
+    SCHEDULER.with(|scheduler| {
+        let mut scheduler = scheduler.borrow_mut();
+        watch_page(&mut scheduler)?;
+
+        let weak_state = Rc::downgrade(state);
+        if !scheduler
+            .animations
+            .iter()
+            .any(|registered| registered.ptr_eq(&weak_state))
+        {
+            scheduler.animations.push(weak_state);
+        }
+        request_frame(&mut scheduler)
+    })
+}
+
+/// Keeps track of a new instance until it is disposed, so that `stop_all()` reaches it before
+/// it starts.
+pub(crate) fn track(state: &Rc<RefCell<AnimationState>>) {
+    SCHEDULER.with(|scheduler| {
+        let mut scheduler = scheduler.borrow_mut();
+        scheduler.live.retain(|live| live.strong_count() > 0);
+        scheduler.live.push(Rc::downgrade(state));
+    });
+}
+
+/// Forgets a disposed instance.
+pub(crate) fn untrack(state: &Rc<RefCell<AnimationState>>) {
+    let weak_state = Rc::downgrade(state);
+    SCHEDULER.with(|scheduler| {
+        scheduler
+            .borrow_mut()
+            .live
+            .retain(|live| live.strong_count() > 0 && !live.ptr_eq(&weak_state));
+    });
+}
+
+/// Pauses every running animation, e.g. during a route transition. Animations started while
+/// paused wait as well, until `resume_all()`.
+#[wasm_bindgen]
+pub fn pause_all() {
+    on_page_change(|page| page.frozen = true);
+}
+
+/// Resumes the animations held by `pause_all()` where they left off.
+#[wasm_bindgen]
+pub fn resume_all() {
+    on_page_change(|page| page.frozen = false);
+}
+
+/// Stops every running animation, firing their end callbacks, and cancels the starts still
+/// due, such as those of a staggered group or a timeline. Triggers still start runs later.
+#[wasm_bindgen]
+pub fn stop_all() -> Result<(), JsError> {
+    let live = SCHEDULER.with(|scheduler| scheduler.borrow().live.clone());
+    live.iter().filter_map(Weak::upgrade).try_for_each(|state| {
+        cancel_delayed_start(&state);
+        stop_animation(&state)
+    })
+}
@@ -1,25 +1,868 @@
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashSet;
 use std::fmt;
+use std::str::FromStr;
 
-#[derive(Clone, Serialize, Deserialize)]
+use crate::error::ScrambleError;
+
+/// Code points scrambled characters are drawn from. Each code point may be given as a number
+/// or as a string read like `from_str` reads one, such as the character itself.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub enum RangeOrCharCodes {
-    Range(i32, i32),
-    Codes(Vec<i32>),
+    /// Inclusive range. The surrogates, which aren't characters, are skipped.
+    #[serde(alias = "range")]
+    Range(
+        #[serde(deserialize_with = "deserialize_code")] u32,
+        #[serde(deserialize_with = "deserialize_code")] u32,
+    ),
+    #[serde(alias = "codes")]
+    Codes(#[serde(deserialize_with = "deserialize_codes")] Vec<u32>),
+    /// Letters of the scripts the text is written in, such as Cyrillic or Hangul. Given as
+    /// `"auto"`.
+    #[serde(rename = "auto")]
+    Auto,
+}
+
+/// The character `s` consists of, if it is a single one.
+fn single_char(s: &str) -> Option<char> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+/// Parses a code point given as a decimal number, in hex as `0x2591` or `U+2591`, or as the
+/// character itself. Digits are read as numbers, so `5` is code point 5 rather than `'5'`.
+fn parse_code(s: &str) -> Result<u32, String> {
+    let code = s.trim();
+    let hex = code
+        .strip_prefix("0x")
+        .or_else(|| code.strip_prefix("U+"))
+        .or_else(|| code.strip_prefix("u+"));
+    let parsed = match hex {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => code.parse().ok(),
+    };
+    // A character is read before trimming too, so that a space is one
+    parsed
+        .or_else(|| single_char(s).or_else(|| single_char(code)).map(u32::from))
+        .ok_or_else(|| format!("Invalid character code: {}", s))
 }
 
-#[derive(Clone)]
-pub enum ScrambleControl {
-    Char(char),
-    Number(i32),
-    Null,
+/// Code point as given in a props object.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CodeValue {
+    Number(u32),
+    Text(String),
 }
 
-impl fmt::Display for ScrambleControl {
+impl CodeValue {
+    /// The code point. A string of a single character is that character, digits included, as
+    /// in `{ range: ["0", "9"] }`.
+    fn code<E: de::Error>(self) -> Result<u32, E> {
+        match self {
+            CodeValue::Number(code) => Ok(code),
+            CodeValue::Text(code) => match single_char(&code) {
+                Some(c) => Ok(c as u32),
+                None => parse_code(&code).map_err(E::custom),
+            },
+        }
+    }
+}
+
+fn deserialize_code<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    CodeValue::deserialize(deserializer)?.code()
+}
+
+fn deserialize_codes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u32>, D::Error> {
+    Vec::<CodeValue>::deserialize(deserializer)?
+        .into_iter()
+        .map(CodeValue::code)
+        .collect()
+}
+
+/// Parses `MIN-MAX` as a range, a comma separated list such as `9617,9618,9619` as codes, or
+/// `auto`. Code points may also be given in hex, as in `0x2591` or `U+2591`, or as characters
+/// other than digits, as in `a-z`.
+impl FromStr for RangeOrCharCodes {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim() == "auto" {
+            return Ok(RangeOrCharCodes::Auto);
+        }
+        match s.split_once('-') {
+            Some((min, max)) => Ok(RangeOrCharCodes::Range(parse_code(min)?, parse_code(max)?)),
+            None => Ok(RangeOrCharCodes::Codes(
+                s.split(',').map(parse_code).collect::<Result<_, _>>()?,
+            )),
+        }
+    }
+}
+
+/// Formats the range the way `from_str` parses it.
+impl fmt::Display for RangeOrCharCodes {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            ScrambleControl::Char(c) => write!(f, "{}", c),
-            ScrambleControl::Number(n) => write!(f, "{}", n),
-            ScrambleControl::Null => write!(f, ""),
+            RangeOrCharCodes::Range(min, max) => write!(f, "{}-{}", min, max),
+            RangeOrCharCodes::Codes(codes) => {
+                for (i, code) in codes.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", code)?;
+                }
+                Ok(())
+            }
+            RangeOrCharCodes::Auto => write!(f, "auto"),
+        }
+    }
+}
+
+/// Overdrive setting, given either as a boolean or as the code point to sweep with.
+///
+/// `true` sweeps with an underscore, mirroring the react hook; a number such as `0x2022`
+/// sweeps with that character instead.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Overdrive {
+    Enabled(bool),
+    Code(u32),
+}
+
+impl Overdrive {
+    /// The character used for the overdrive sweep, or `None` when overdrive is off.
+    pub fn char(&self) -> Option<char> {
+        match *self {
+            Overdrive::Enabled(true) => Some('_'),
+            Overdrive::Enabled(false) => None,
+            Overdrive::Code(code) => char::from_u32(code),
+        }
+    }
+}
+
+impl Default for Overdrive {
+    fn default() -> Self {
+        Overdrive::Enabled(false)
+    }
+}
+
+/// Parses `true`/`false` or a code point.
+impl FromStr for Overdrive {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "true" => Ok(Overdrive::Enabled(true)),
+            "false" => Ok(Overdrive::Enabled(false)),
+            code => code
+                .parse()
+                .map(Overdrive::Code)
+                .map_err(|_| format!("Overdrive must be true, false or a code point: {}", s)),
+        }
+    }
+}
+
+impl From<bool> for Overdrive {
+    fn from(enabled: bool) -> Self {
+        Overdrive::Enabled(enabled)
+    }
+}
+
+impl From<u32> for Overdrive {
+    fn from(code: u32) -> Self {
+        Overdrive::Code(code)
+    }
+}
+
+/// Chance of scrambling a character (0-1), given either as one number for every character or
+/// as a list with the chance of each, e.g. high at the start of the text and low at its end.
+/// Characters past the end of the list use its last chance.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Chance {
+    Uniform(f32),
+    Positions(Vec<f32>),
+}
+
+impl Chance {
+    /// Chance of scrambling the character at `index`.
+    pub fn at(&self, index: usize) -> f32 {
+        match self {
+            Chance::Uniform(chance) => *chance,
+            Chance::Positions(chances) => chances
+                .get(index)
+                .or(chances.last())
+                .copied()
+                .unwrap_or_else(default_chance),
+        }
+    }
+}
+
+impl Default for Chance {
+    fn default() -> Self {
+        Chance::Uniform(default_chance())
+    }
+}
+
+impl fmt::Display for Chance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Chance::Uniform(chance) => write!(f, "{}", chance),
+            Chance::Positions(chances) => {
+                let chances: Vec<_> = chances.iter().map(f32::to_string).collect();
+                write!(f, "{}", chances.join(","))
+            }
+        }
+    }
+}
+
+/// Parses a chance, or comma separated chances for each position.
+impl FromStr for Chance {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chances = s
+            .split(',')
+            .map(|chance| chance.trim().parse::<f32>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| format!("Chance must be a number or a comma separated list: {}", s))?;
+        match chances[..] {
+            [chance] => Ok(Chance::Uniform(chance)),
+            _ => Ok(Chance::Positions(chances)),
+        }
+    }
+}
+
+impl From<f32> for Chance {
+    fn from(chance: f32) -> Self {
+        Chance::Uniform(chance)
+    }
+}
+
+impl From<Vec<f32>> for Chance {
+    fn from(chances: Vec<f32>) -> Self {
+        Chance::Positions(chances)
+    }
+}
+
+/// Most extra times a character is scrambled on top of `scramble`, picked at random for each
+/// one so that they don't all settle together. Given as a number of times, or as a share of
+/// `scramble` such as `"50%"`. Zero settles every character after exactly `scramble` times.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "JitterValue", into = "JitterValue")]
+pub enum Jitter {
+    Times(i32),
+    Share(f32),
+}
+
+/// How a [`Jitter`] is written: a number, or a percentage string.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum JitterValue {
+    Times(i32),
+    Share(String),
+}
+
+impl Jitter {
+    /// Most extra times a character scrambled `scramble` times is scrambled.
+    pub fn max(&self, scramble: i32) -> i32 {
+        match *self {
+            _ if scramble == 0 => 0,
+            Jitter::Times(times) => times,
+            Jitter::Share(share) => (scramble as f32 * share).ceil() as i32,
+        }
+    }
+}
+
+impl Default for Jitter {
+    fn default() -> Self {
+        Jitter::Share(0.5)
+    }
+}
+
+impl fmt::Display for Jitter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Jitter::Times(times) => write!(f, "{}", times),
+            Jitter::Share(share) => write!(f, "{}%", share * 100.0),
+        }
+    }
+}
+
+/// Parses a number of times, or a percentage such as `50%`.
+impl FromStr for Jitter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let jitter = match s.strip_suffix('%') {
+            Some(percent) => percent
+                .trim()
+                .parse()
+                .map(|p: f32| Jitter::Share(p / 100.0))
+                .ok(),
+            None => s.parse().map(Jitter::Times).ok(),
+        };
+        jitter.ok_or_else(|| format!("Scramble jitter must be a number or a percentage: {}", s))
+    }
+}
+
+impl TryFrom<JitterValue> for Jitter {
+    type Error = String;
+
+    fn try_from(value: JitterValue) -> Result<Self, Self::Error> {
+        match value {
+            JitterValue::Times(times) => Ok(Jitter::Times(times)),
+            JitterValue::Share(share) if share.trim().ends_with('%') => share.parse(),
+            JitterValue::Share(share) => Err(format!(
+                "Scramble jitter must be a number or a percentage: {}",
+                share
+            )),
+        }
+    }
+}
+
+impl From<Jitter> for JitterValue {
+    fn from(jitter: Jitter) -> Self {
+        match jitter {
+            Jitter::Times(times) => JitterValue::Times(times),
+            share => JitterValue::Share(share.to_string()),
+        }
+    }
+}
+
+/// Where each frame is written on the target element.
+///
+/// Given as a string: `"textContent"`, `"value"`, or the name of an attribute such as
+/// `"placeholder"`, `"title"` or `"aria-label"`.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum WriteTarget {
+    #[default]
+    TextContent,
+    Value,
+    Attribute(String),
+}
+
+impl From<String> for WriteTarget {
+    fn from(target: String) -> Self {
+        match target.as_str() {
+            "textContent" => WriteTarget::TextContent,
+            "value" => WriteTarget::Value,
+            _ => WriteTarget::Attribute(target),
+        }
+    }
+}
+
+impl From<WriteTarget> for String {
+    fn from(target: WriteTarget) -> Self {
+        match target {
+            WriteTarget::TextContent => "textContent".to_string(),
+            WriteTarget::Value => "value".to_string(),
+            WriteTarget::Attribute(name) => name,
+        }
+    }
+}
+
+/// What a new instance does when another live instance already draws into its node.
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SharedTarget {
+    /// Wait for the other instance's run to end before starting a run.
+    #[default]
+    Queue,
+    /// Refuse to create the instance.
+    Error,
+    /// Control the other instance's animation instead, giving it the new props.
+    Adopt,
+}
+
+impl SharedTarget {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SharedTarget::Queue => "queue",
+            SharedTarget::Error => "error",
+            SharedTarget::Adopt => "adopt",
+        }
+    }
+}
+
+/// Parses `queue`, `error` or `adopt`.
+impl FromStr for SharedTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "queue" => Ok(SharedTarget::Queue),
+            "error" => Ok(SharedTarget::Error),
+            "adopt" => Ok(SharedTarget::Adopt),
+            _ => Err(format!(
+                "Shared target must be queue, error or adopt: {}",
+                s
+            )),
+        }
+    }
+}
+
+/// How the animation reacts to the `prefers-reduced-motion` user preference.
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReducedMotion {
+    /// Damp the effect when the user prefers reduced motion.
+    #[default]
+    Respect,
+    /// Always play the full effect.
+    Ignore,
+    /// Always damp the effect.
+    Force,
+}
+
+impl ReducedMotion {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReducedMotion::Respect => "respect",
+            ReducedMotion::Ignore => "ignore",
+            ReducedMotion::Force => "force",
+        }
+    }
+
+    /// Whether the effect is damped, given the user's preference.
+    pub fn applies(&self, prefers_reduced_motion: bool) -> bool {
+        match self {
+            ReducedMotion::Respect => prefers_reduced_motion,
+            ReducedMotion::Ignore => false,
+            ReducedMotion::Force => true,
+        }
+    }
+}
+
+/// Parses `respect`, `ignore` or `force`.
+impl FromStr for ReducedMotion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "respect" => Ok(ReducedMotion::Respect),
+            "ignore" => Ok(ReducedMotion::Ignore),
+            "force" => Ok(ReducedMotion::Force),
+            _ => Err(format!(
+                "Reduced motion must be respect, ignore or force: {}",
+                s
+            )),
+        }
+    }
+}
+
+/// What happens to text longer than `max_length`.
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Overlength {
+    /// Reject the props.
+    #[default]
+    Error,
+    /// Only reveal the first `max_length` characters.
+    Truncate,
+    /// Reveal `max_length` characters at a time, each chunk once the one before it is done.
+    /// The text after the current chunk is not drawn yet.
+    Chunk,
+}
+
+impl Overlength {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Overlength::Error => "error",
+            Overlength::Truncate => "truncate",
+            Overlength::Chunk => "chunk",
+        }
+    }
+}
+
+/// Parses `error`, `truncate` or `chunk`.
+impl FromStr for Overlength {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "error" => Ok(Overlength::Error),
+            "truncate" => Ok(Overlength::Truncate),
+            "chunk" => Ok(Overlength::Chunk),
+            _ => Err(format!(
+                "Overlength must be error, truncate or chunk: {}",
+                s
+            )),
+        }
+    }
+}
+
+/// What a position behind the reveal front shows while it holds a character other than its
+/// final one, such as one left by the overdrive sweep or an imported state.
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mismatch {
+    /// A space.
+    #[default]
+    Space,
+    /// Its final character, ahead of time.
+    Final,
+    /// The character it holds.
+    Scrambled,
+    /// The `placeholder_char`, or a space without one.
+    Placeholder,
+}
+
+impl Mismatch {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Mismatch::Space => "space",
+            Mismatch::Final => "final",
+            Mismatch::Scrambled => "scrambled",
+            Mismatch::Placeholder => "placeholder",
+        }
+    }
+}
+
+/// Parses `space`, `final`, `scrambled` or `placeholder`.
+impl FromStr for Mismatch {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "space" => Ok(Mismatch::Space),
+            "final" => Ok(Mismatch::Final),
+            "scrambled" => Ok(Mismatch::Scrambled),
+            "placeholder" => Ok(Mismatch::Placeholder),
+            _ => Err(format!(
+                "Mismatch must be space, final, scrambled or placeholder: {}",
+                s
+            )),
+        }
+    }
+}
+
+/// How spaces are kept from collapsing while frames are drawn, which would shift the layout
+/// mid-animation.
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Whitespace {
+    /// Write spaces as they are.
+    #[default]
+    Normal,
+    /// Write spaces as non-breaking spaces in every frame but the final one.
+    Nbsp,
+    /// Write spaces as figure spaces, as wide as a digit, in every frame but the final one.
+    Figure,
+    /// Give the element `white-space: pre-wrap`, so no frame collapses its spaces, the final
+    /// one included. Restored once the instance is disposed.
+    Pre,
+}
+
+impl Whitespace {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Whitespace::Normal => "normal",
+            Whitespace::Nbsp => "nbsp",
+            Whitespace::Figure => "figure",
+            Whitespace::Pre => "pre",
+        }
+    }
+
+    /// Character written in place of spaces in intermediate frames, if any.
+    pub fn space(&self) -> Option<char> {
+        match self {
+            Whitespace::Nbsp => Some('\u{a0}'),
+            Whitespace::Figure => Some('\u{2007}'),
+            Whitespace::Normal | Whitespace::Pre => None,
+        }
+    }
+}
+
+/// Parses `normal`, `nbsp`, `figure` or `pre`.
+impl FromStr for Whitespace {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "normal" => Ok(Whitespace::Normal),
+            "nbsp" => Ok(Whitespace::Nbsp),
+            "figure" => Ok(Whitespace::Figure),
+            "pre" => Ok(Whitespace::Pre),
+            _ => Err(format!(
+                "Whitespace must be normal, nbsp, figure or pre: {}",
+                s
+            )),
+        }
+    }
+}
+
+/// Direction the text reads in, which decides the characters scrambled positions draw.
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    /// Right to left when the first letter of the text is, or when the element is.
+    #[default]
+    Auto,
+    Ltr,
+    Rtl,
+}
+
+impl Direction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Auto => "auto",
+            Direction::Ltr => "ltr",
+            Direction::Rtl => "rtl",
+        }
+    }
+}
+
+/// Parses `auto`, `ltr` or `rtl`.
+impl FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "auto" => Ok(Direction::Auto),
+            "ltr" => Ok(Direction::Ltr),
+            "rtl" => Ok(Direction::Rtl),
+            _ => Err(format!("Direction must be auto, ltr or rtl: {}", s)),
+        }
+    }
+}
+
+/// Stylized variant of the text that the animation lands on.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Transform {
+    /// Letters swapped for look-alike digits, as in `H3ll0 W0rld`.
+    Leetspeak,
+    Uppercase,
+    /// Lowercase letters swapped for small capitals, as in `Hᴇʟʟᴏ Wᴏʀʟᴅ`.
+    SmallCaps,
+}
+
+impl Transform {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Transform::Leetspeak => "leetspeak",
+            Transform::Uppercase => "uppercase",
+            Transform::SmallCaps => "small-caps",
+        }
+    }
+
+    /// `text` in this style.
+    pub fn apply(&self, text: &str) -> String {
+        match self {
+            Transform::Leetspeak => text.chars().map(leetspeak).collect(),
+            Transform::Uppercase => text.to_uppercase(),
+            Transform::SmallCaps => text.chars().map(small_caps).collect(),
+        }
+    }
+}
+
+fn leetspeak(c: char) -> char {
+    match c {
+        'a' | 'A' => '4',
+        'e' | 'E' => '3',
+        'i' | 'I' => '1',
+        'o' | 'O' => '0',
+        's' | 'S' => '5',
+        't' | 'T' => '7',
+        _ => c,
+    }
+}
+
+fn small_caps(c: char) -> char {
+    const SMALL_CAPS: &str = "ᴀʙᴄᴅᴇꜰɢʜɪᴊᴋʟᴍɴᴏᴘǫʀꜱᴛᴜᴠᴡxʏᴢ";
+    if c.is_ascii_lowercase() {
+        SMALL_CAPS
+            .chars()
+            .nth((c as u8 - b'a') as usize)
+            .unwrap_or(c)
+    } else {
+        c
+    }
+}
+
+/// Parses `leetspeak`, `uppercase` or `small-caps`.
+impl FromStr for Transform {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "leetspeak" => Ok(Transform::Leetspeak),
+            "uppercase" => Ok(Transform::Uppercase),
+            "small-caps" => Ok(Transform::SmallCaps),
+            _ => Err(format!(
+                "Transform must be leetspeak, uppercase or small-caps: {}",
+                s
+            )),
+        }
+    }
+}
+
+/// Politeness of the `aria-live` region announcing the text.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AriaLive {
+    Off,
+    Polite,
+    Assertive,
+}
+
+impl AriaLive {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AriaLive::Off => "off",
+            AriaLive::Polite => "polite",
+            AriaLive::Assertive => "assertive",
+        }
+    }
+}
+
+/// Parses `off`, `polite` or `assertive`.
+impl FromStr for AriaLive {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "off" => Ok(AriaLive::Off),
+            "polite" => Ok(AriaLive::Polite),
+            "assertive" => Ok(AriaLive::Assertive),
+            _ => Err(format!("aria-live must be off, polite or assertive: {}", s)),
+        }
+    }
+}
+
+/// Interaction that replays the animation.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Trigger {
+    /// The pointer entering the element, as with `hover_replay`.
+    Hover,
+    /// The element receiving focus.
+    Focus,
+    /// Enter or Space pressed while the element has focus.
+    Keydown,
+    Click,
+    Touchstart,
+}
+
+impl Trigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Trigger::Hover => "hover",
+            Trigger::Focus => "focus",
+            Trigger::Keydown => "keydown",
+            Trigger::Click => "click",
+            Trigger::Touchstart => "touchstart",
+        }
+    }
+
+    /// The DOM event the trigger listens for.
+    pub fn event(&self) -> &'static str {
+        match self {
+            Trigger::Hover => "mouseenter",
+            Trigger::Focus => "focus",
+            Trigger::Keydown => "keydown",
+            Trigger::Click => "click",
+            Trigger::Touchstart => "touchstart",
+        }
+    }
+}
+
+/// Parses `hover`, `focus`, `keydown`, `click` or `touchstart`.
+impl FromStr for Trigger {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "hover" => Ok(Trigger::Hover),
+            "focus" => Ok(Trigger::Focus),
+            "keydown" => Ok(Trigger::Keydown),
+            "click" => Ok(Trigger::Click),
+            "touchstart" => Ok(Trigger::Touchstart),
+            _ => Err(format!(
+                "Trigger must be hover, focus, keydown, click or touchstart: {}",
+                s
+            )),
+        }
+    }
+}
+
+/// A stage of the `pipeline`, run once the one before it is done, from the characters it left
+/// showing.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "stage", rename_all = "lowercase")]
+pub enum Stage {
+    /// Sweeps `char` (an underscore by default) across the text, `step` characters every
+    /// `tick` frames. Done once it reaches the end.
+    Sweep {
+        #[serde(default)]
+        char: Option<char>,
+    },
+    /// Reveals the text, scrambling each character as it is reached. `overflow` and
+    /// `overdrive` default to the props of the same names. Done once the text is revealed.
+    Scramble {
+        #[serde(default)]
+        overflow: Option<bool>,
+        #[serde(default)]
+        overdrive: Option<Overdrive>,
+    },
+    /// Types the text out, showing each character as it is reached without scrambling it.
+    /// Done once the text is revealed.
+    Typewriter,
+    /// Scrambles random characters of the revealed text for `frames` frames, each with
+    /// `chance` on every frame, then lets them settle.
+    Glitch {
+        #[serde(default = "default_glitch_frames")]
+        frames: u32,
+        #[serde(default = "default_glitch_chance")]
+        chance: f32,
+    },
+}
+
+/// The stage of an empty `pipeline`: a reveal configured by the props.
+pub const DEFAULT_STAGE: Stage = Stage::Scramble {
+    overflow: None,
+    overdrive: None,
+};
+
+impl Stage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Stage::Sweep { .. } => "sweep",
+            Stage::Scramble { .. } => "scramble",
+            Stage::Typewriter => "typewriter",
+            Stage::Glitch { .. } => "glitch",
+        }
+    }
+
+    /// Whether the stage reveals the text from its start.
+    pub fn reveals(&self) -> bool {
+        matches!(self, Stage::Scramble { .. } | Stage::Typewriter)
+    }
+}
+
+/// Parses `sweep`, `scramble`, `typewriter` or `glitch`, configured with their defaults.
+impl FromStr for Stage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "sweep" => Ok(Stage::Sweep { char: None }),
+            "scramble" => Ok(DEFAULT_STAGE),
+            "typewriter" => Ok(Stage::Typewriter),
+            "glitch" => Ok(Stage::Glitch {
+                frames: default_glitch_frames(),
+                chance: default_glitch_chance(),
+            }),
+            _ => Err(format!(
+                "Stage must be sweep, scramble, typewriter or glitch: {}",
+                s
+            )),
         }
     }
 }
@@ -27,16 +870,31 @@ impl fmt::Display for ScrambleControl {
 #[derive(Clone, Serialize, Deserialize)]
 pub struct UseScrambleProps {
     /// When true, the animation will play automatically when a text input is first provided.
+    /// Unset, it plays unless the text is a `spoiler`.
     pub play_on_mount: Option<bool>,
 
     /// Text input to be scrambled
     pub text: String,
 
+    /// Longest text that is animated at once, in characters. Longer text is handled as set
+    /// by `overlength`. No limit by default.
+    #[serde(default)]
+    pub max_length: Option<usize>,
+
+    /// What happens to text longer than `max_length`: "error" (default), "truncate" or "chunk".
+    #[serde(default)]
+    pub overlength: Overlength,
+
     /// 0-1 range that determines the scramble speed. A speed of 1 will redraw 60 times a second.
     /// A speed of 0 will pause the animation.
     #[serde(default = "default_speed")]
     pub speed: f32,
 
+    /// Upper bound on redraws per second, e.g. 30 to save battery. Frames are skipped without
+    /// slowing the reveal: the ticks due in between are caught up on the next drawn frame.
+    #[serde(default)]
+    pub max_fps: Option<f32>,
+
     /// The controller will move forward along the text input and scramble more characters,
     /// at a pace of `tick` frames.
     #[serde(default = "default_tick")]
@@ -46,9 +904,10 @@ pub struct UseScrambleProps {
     #[serde(default = "default_step")]
     pub step: i32,
 
-    /// Chance of scrambling a character (0-1)
-    #[serde(default = "default_chance")]
-    pub chance: f32,
+    /// Chance of scrambling a character (0-1), or of each character in turn. In JS, also a
+    /// function `(index, length) => chance`, called for each character when the props are read.
+    #[serde(default)]
+    pub chance: Chance,
 
     /// Number of characters to randomly scramble
     #[serde(default = "default_seed")]
@@ -58,25 +917,332 @@ pub struct UseScrambleProps {
     #[serde(default = "default_scramble")]
     pub scramble: i32,
 
-    /// Characters to avoid scrambling
-    #[serde(default = "default_ignore")]
-    pub ignore: Vec<String>,
+    /// Most extra times each character is scrambled, at random: a number of times, or a share
+    /// of `scramble` such as `"50%"` (default). Zero settles every character after exactly
+    /// `scramble` times, e.g. to keep several elements in step.
+    #[serde(default)]
+    pub scramble_jitter: Jitter,
+
+    /// Characters to avoid scrambling, given as a string such as `".,!?"` or as a list of
+    /// strings, each standing for its characters. Serialized as a sorted list of characters.
+    #[serde(
+        default = "default_ignore",
+        deserialize_with = "deserialize_ignore",
+        serialize_with = "serialize_ignore"
+    )]
+    pub ignore: HashSet<char>,
+
+    /// Words never scrambled, such as brand names or code identifiers, matched whole and
+    /// case-sensitively: an occurrence running into a letter, digit or underscore is left
+    /// alone. Their characters show like those in `ignore`.
+    #[serde(default)]
+    pub ignore_words: Vec<String>,
 
     /// Unicode character range for scrambler
     #[serde(default = "default_range")]
     pub range: RangeOrCharCodes,
 
-    /// When true, enables overdrive mode
+    /// Enables overdrive mode. Either a boolean (underscore sweep) or a code point to sweep with.
     #[serde(default)]
-    pub overdrive: bool,
+    pub overdrive: Overdrive,
 
-    /// When true, animation starts from empty string
+    /// Characters the overdrive sweep moves forward on every frame, so that it can fly ahead
+    /// of a slower reveal. Defaults to `step`.
     #[serde(default)]
+    pub overdrive_step: Option<i32>,
+
+    /// When true, the full-length text is scrambled from the first frame.
+    /// When false, animation starts from an empty string and grows as characters are revealed.
+    #[serde(default = "default_overflow")]
     pub overflow: bool,
 
-    /// When true, enables hover-to-replay functionality
+    /// Stages the run goes through in order, e.g. a sweep, then a scramble reveal, then a
+    /// glitch: `[{ stage: "sweep" }, { stage: "scramble" }, { stage: "glitch", frames: 30 }]`.
+    /// Empty (default), the run is a single scramble reveal configured by the props.
+    #[serde(default)]
+    pub pipeline: Vec<Stage>,
+
+    /// Character shown in place of the characters the reveal hasn't reached yet, e.g. '·',
+    /// '█' or a non-breaking space, so the text keeps its length and weight without
+    /// `overflow`. By default they are not drawn.
+    #[serde(default)]
+    pub placeholder_char: Option<char>,
+
+    /// What positions behind the reveal front show while they hold a character other than
+    /// their final one, e.g. one left by the overdrive sweep: "space" (default), "final" for
+    /// their final character, "scrambled" to keep the one they hold, or "placeholder" for
+    /// `placeholder_char`.
+    #[serde(default)]
+    pub mismatch: Mismatch,
+
+    /// How spaces are kept from collapsing mid-animation: "normal" (default) writes them as
+    /// they are, "nbsp" and "figure" swap them for non-breaking or figure spaces until the
+    /// final frame, and "pre" gives the element `white-space: pre-wrap`.
+    #[serde(default)]
+    pub whitespace: Whitespace,
+
+    /// Direction the text reads in: "auto" (default), "ltr" or "rtl". In right-to-left text,
+    /// positions whose final character isn't a left-to-right letter draw letters of the text's
+    /// own script, such as Hebrew or Arabic, in place of `range`, so they don't break up the
+    /// bidi runs. Ranges without left-to-right letters, like block glyphs, are kept. The
+    /// reveal follows the logical order of the text either way, starting from the right.
+    #[serde(default)]
+    pub dir: Direction,
+
+    /// Style the animation lands on instead of the text as given: "leetspeak", "uppercase" or
+    /// "small-caps". The props, announcements and accessible label keep the original text.
+    #[serde(default)]
+    pub transform: Option<Transform>,
+
+    /// Character every revealed character but whitespace shows as, e.g. '•' for a password
+    /// being "decrypted". Only the positions still scrambling show random characters, and the
+    /// run lands on the masked text.
+    #[serde(default)]
+    pub mask: Option<char>,
+
+    /// When true, enables hover-to-replay functionality. Same as a `"hover"` trigger.
     #[serde(default)]
     pub hover_replay: bool,
+
+    /// Interactions that replay the animation: "hover", "focus", "keydown" (Enter or Space),
+    /// "click" or "touchstart". Focus and keyboard triggers need a focusable element, e.g.
+    /// one with a `tabindex`.
+    #[serde(default)]
+    pub triggers: Vec<Trigger>,
+
+    /// When true, the text is a spoiler: it starts scrambled, is revealed by a click, Enter or
+    /// Space, and scrambles back when activated again or when the pointer or focus leaves it.
+    /// The element gets `role="button"`, a `tabindex` and `aria-pressed`.
+    #[serde(default)]
+    pub spoiler: bool,
+
+    /// Milliseconds after a reveal completes before the text scrambles back, e.g. to hide a
+    /// spoiler again. By default it stays revealed.
+    #[serde(default)]
+    pub conceal_after: Option<f64>,
+
+    /// When true, the reveal follows the page scroll instead of time: the text starts
+    /// scrambled as the element enters at the bottom of the viewport, and is fully revealed
+    /// once its center reaches the middle. The run lasts until `stop()`.
+    #[serde(default)]
+    pub scroll_scrub: bool,
+
+    /// When true, the characters under the pointer scramble again as it moves across the
+    /// revealed text, along with `hover_scrub_radius` neighbors on each side, and settle back
+    /// after `hover_scrub_settle` milliseconds. The text is drawn in spans, as with `spans`.
+    /// Read when the instance is created.
+    #[serde(default)]
+    pub hover_scrub: bool,
+
+    /// Neighbors on each side of the character under the pointer that `hover_scrub` scrambles
+    /// as well.
+    #[serde(default = "default_hover_scrub_radius")]
+    pub hover_scrub_radius: u32,
+
+    /// Milliseconds characters scrambled by `hover_scrub` take to settle back, at the
+    /// configured `speed`.
+    #[serde(default = "default_hover_scrub_settle")]
+    pub hover_scrub_settle: f64,
+
+    /// When true, the element's `--scramble-progress` (0-1) and `--scramble-active-index`
+    /// custom properties follow the reveal on every frame, for gradients, underlines or glows
+    /// styled in CSS.
+    #[serde(default)]
+    pub css_vars: bool,
+
+    /// CSS selector, such as `".headline span.em"`, narrowing the target to its matching
+    /// descendants, so the text to animate needn't be isolated in its own element. Several
+    /// matches share the text in document order, like `ScrambleText::across`. Read when the
+    /// instance is created.
+    #[serde(default)]
+    pub content_selector: Option<String>,
+
+    /// Where frames are written: text content (default), an input's value, or an attribute
+    #[serde(default)]
+    pub target: WriteTarget,
+
+    /// What happens when another instance already draws into the same node, which would
+    /// otherwise overwrite each other's frames: "queue" (default) starts runs once the other
+    /// instance's run ends, "error" refuses to create the instance, and "adopt" returns a
+    /// handle to the other instance's animation, with these props. Read when the instance is
+    /// created.
+    #[serde(default)]
+    pub shared_target: SharedTarget,
+
+    /// When true, the animation pauses while the document is hidden and picks up where it
+    /// left off once the tab is visible again.
+    #[serde(default = "default_pause_when_hidden")]
+    pub pause_when_hidden: bool,
+
+    /// When true, the animation pauses while the window is blurred and resumes on focus.
+    /// Useful in iframes and Electron apps, where the document stays visible.
+    #[serde(default)]
+    pub pause_on_blur: bool,
+
+    /// When true, frames are computed in `requestIdleCallback`s instead of animation frames,
+    /// so a decorative scramble never competes with input handling or rendering. Browsers
+    /// without `requestIdleCallback` draw on animation frames as usual.
+    #[serde(default)]
+    pub idle: bool,
+
+    /// When true, long text is split into chunks and only those near the viewport are
+    /// scrambled and redrawn; the rest show their final text. Only for elements written as
+    /// text content, and read when the instance is created.
+    #[serde(default)]
+    pub virtualize: bool,
+
+    /// When true, each character is drawn in its own `<span>` with a `data-state` of
+    /// `settled`, `scrambling` or `waiting`, and settled ones with a `data-age` counting the
+    /// frames since they settled, up to 60. Only for elements written as text content that
+    /// aren't virtualized, and read when the instance is created.
+    #[serde(default)]
+    pub spans: bool,
+
+    /// Whether to honor `prefers-reduced-motion`: "respect" (default), "ignore" or "force".
+    /// Reduced motion reveals the text without scrambling.
+    #[serde(default)]
+    pub reduced_motion: ReducedMotion,
+
+    /// When true, screen readers get the final text instead of the scrambled frames: the
+    /// target is hidden from them while it animates, and a visually hidden copy of the final
+    /// text is placed next to it.
+    #[serde(default)]
+    pub a11y: bool,
+
+    /// `aria-live` politeness for the target element: "off", "polite" or "assertive".
+    /// With `announce_on_complete`, it applies to the announcement region instead.
+    #[serde(default)]
+    pub aria_live: Option<AriaLive>,
+
+    /// When true, the final text is announced once, through a visually hidden live region,
+    /// when the animation completes, instead of the target announcing every frame.
+    #[serde(default)]
+    pub announce_on_complete: bool,
+
+    /// When true, props objects with unknown keys, such as a misspelled `scrample`, are
+    /// rejected. Set to false to ignore them instead.
+    #[serde(default = "default_strict")]
+    pub strict: bool,
+
+    /// When true, the instance logs its runs, every frame's state and rejected props to the
+    /// console, prefixed with `[scramble-text]`.
+    #[serde(default)]
+    pub debug: bool,
+
+    /// When true, a panel pinned to the corner of the page shows the frame being drawn, how
+    /// many scrambles each character has left and the reveal front, live. Only drawn in builds
+    /// with the `overlay` feature, so it can be left on without reaching production.
+    #[serde(default)]
+    pub overlay: bool,
+}
+
+/// Keys a props object may have.
+pub const PROP_NAMES: &[&str] = &[
+    "play_on_mount",
+    "text",
+    "max_length",
+    "overlength",
+    "speed",
+    "max_fps",
+    "tick",
+    "step",
+    "chance",
+    "seed",
+    "scramble",
+    "scramble_jitter",
+    "ignore",
+    "ignore_words",
+    "range",
+    "overdrive",
+    "overdrive_step",
+    "overflow",
+    "pipeline",
+    "placeholder_char",
+    "mismatch",
+    "whitespace",
+    "dir",
+    "transform",
+    "mask",
+    "hover_replay",
+    "triggers",
+    "spoiler",
+    "conceal_after",
+    "scroll_scrub",
+    "hover_scrub",
+    "hover_scrub_radius",
+    "hover_scrub_settle",
+    "css_vars",
+    "content_selector",
+    "target",
+    "shared_target",
+    "pause_when_hidden",
+    "pause_on_blur",
+    "idle",
+    "virtualize",
+    "spans",
+    "reduced_motion",
+    "a11y",
+    "aria_live",
+    "announce_on_complete",
+    "strict",
+    "debug",
+    "overlay",
+];
+
+/// Number of single character edits between `a` and `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &b) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if a == b {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// The prop `name` was most likely meant to be: the same name in another case style, such
+/// as `playOnMount`, or one at most two typos away.
+pub fn suggest_prop(name: &str) -> Option<&'static str> {
+    let normalized = name.replace(['_', '-'], "").to_lowercase();
+    if let Some(&prop) = PROP_NAMES
+        .iter()
+        .find(|prop| prop.replace('_', "") == normalized)
+    {
+        return Some(prop);
+    }
+
+    PROP_NAMES
+        .iter()
+        .map(|&prop| (edit_distance(name, prop), prop))
+        .filter(|&(distance, _)| distance <= 2)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, prop)| prop)
+}
+
+/// Checks the keys of a props object, reporting every unknown one.
+pub fn check_prop_names<'a>(names: impl IntoIterator<Item = &'a str>) -> Result<(), ScrambleError> {
+    let mut errors: Vec<_> = names
+        .into_iter()
+        .filter(|name| !PROP_NAMES.contains(name))
+        .map(|name| ScrambleError::UnknownProp {
+            name: name.to_string(),
+            suggestion: suggest_prop(name),
+        })
+        .collect();
+    match errors.len() {
+        0 => Ok(()),
+        1 => Err(errors.remove(0)),
+        _ => Err(ScrambleError::InvalidProps(errors)),
+    }
 }
 
 fn default_speed() -> f32 {
@@ -97,58 +1263,257 @@ fn default_seed() -> i32 {
 fn default_scramble() -> i32 {
     1
 }
-fn default_ignore() -> Vec<String> {
-    vec![" ".to_string()]
+fn default_overflow() -> bool {
+    true
+}
+fn default_glitch_frames() -> u32 {
+    30
+}
+fn default_glitch_chance() -> f32 {
+    0.05
+}
+fn default_hover_scrub_radius() -> u32 {
+    1
+}
+fn default_hover_scrub_settle() -> f64 {
+    300.0
+}
+fn default_pause_when_hidden() -> bool {
+    true
+}
+fn default_strict() -> bool {
+    true
+}
+fn default_ignore() -> HashSet<char> {
+    HashSet::from([' '])
+}
+
+/// `ignore` as given in a props object.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum IgnoreValue {
+    Text(String),
+    List(Vec<String>),
+}
+
+pub(crate) fn deserialize_ignore<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<HashSet<char>, D::Error> {
+    Ok(match IgnoreValue::deserialize(deserializer)? {
+        IgnoreValue::Text(text) => text.chars().collect(),
+        IgnoreValue::List(list) => list.iter().flat_map(|text| text.chars()).collect(),
+    })
+}
+
+fn serialize_ignore<S: Serializer>(
+    ignore: &HashSet<char>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(sorted_ignore(ignore))
+}
+
+/// `ignore` as a sorted list of one character strings.
+pub(crate) fn sorted_ignore(ignore: &HashSet<char>) -> Vec<String> {
+    let mut chars: Vec<char> = ignore.iter().copied().collect();
+    chars.sort_unstable();
+    chars.iter().map(char::to_string).collect()
 }
 fn default_range() -> RangeOrCharCodes {
     RangeOrCharCodes::Range(65, 125)
 }
 
 impl UseScrambleProps {
-    pub fn validate(&self) -> Result<(), String> {
+    /// Whether the animation plays once mounted: as set by `play_on_mount`, or unless the
+    /// text is a `spoiler`.
+    pub fn plays_on_mount(&self) -> bool {
+        self.play_on_mount.unwrap_or(!self.spoiler)
+    }
+
+    /// The text after the `transform`, before any `mask`.
+    pub fn transformed_text(&self) -> String {
+        match self.transform {
+            Some(transform) => transform.apply(&self.text),
+            None => self.text.clone(),
+        }
+    }
+
+    /// The text the animation lands on, after the `transform` and `mask`.
+    pub fn final_text(&self) -> String {
+        let text = self.transformed_text();
+        match self.mask {
+            Some(mask) => text
+                .chars()
+                .map(|c| if c.is_whitespace() { c } else { mask })
+                .collect(),
+            None => text,
+        }
+    }
+
+    /// These props damped for reduced motion: the whole text is revealed on the first step,
+    /// without scrambling, an overdrive sweep or other pipeline stages.
+    pub fn with_reduced_motion(mut self) -> Self {
+        self.step = self.text.chars().count().max(1) as i32;
+        self.chance = Chance::Uniform(0.0);
+        self.overdrive = Overdrive::Enabled(false);
+        self.pipeline.clear();
+        self
+    }
+
+    /// Upper bound on the number of frames a run draws: the reveal front reaches the last
+    /// character after `ceil(len / step)` ticks, which then scrambles at most `scramble` times
+    /// plus the jitter. Each stage of a `pipeline` adds its own frames.
+    pub fn max_frames(&self) -> u32 {
+        let len = self.text.chars().count() as i32;
+        if len == 0 {
+            return 1;
+        }
+        let ticks = (len + self.step - 1) / self.step;
+        let reveal = |scramble: i32| {
+            let scramble = scramble + self.scramble_jitter.max(scramble);
+            (self.tick * (ticks - 1) + scramble + 1) as u32
+        };
+        if self.pipeline.is_empty() {
+            return reveal(self.scramble);
+        }
+        self.pipeline
+            .iter()
+            .map(|stage| match stage {
+                Stage::Sweep { .. } => {
+                    let step = self.overdrive_step.unwrap_or(self.step);
+                    ((len + step - 1) / step) as u32 + 1
+                }
+                Stage::Scramble { .. } => reveal(self.scramble),
+                Stage::Typewriter => reveal(0),
+                Stage::Glitch { frames, .. } => frames + 4,
+            })
+            .sum()
+    }
+
+    /// Upper bound on how long a run takes at `speed`, in milliseconds. Infinite at a speed
+    /// of 0.
+    pub fn max_duration(&self) -> f64 {
+        if self.speed == 0.0 {
+            return f64::INFINITY;
+        }
+        self.max_frames() as f64 * 1000.0 / (60.0 * self.speed as f64)
+    }
+
+    /// Checks the props, reporting every problem at once: a single problem as is, and several
+    /// as [`ScrambleError::InvalidProps`].
+    pub fn validate(&self) -> Result<(), ScrambleError> {
+        let mut errors = self.validate_all();
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            _ => Err(ScrambleError::InvalidProps(errors)),
+        }
+    }
+
+    /// Every problem with the props, in field order.
+    pub fn validate_all(&self) -> Vec<ScrambleError> {
+        let mut errors = Vec::new();
+
+        match self.max_length {
+            Some(0) => errors.push(ScrambleError::InvalidMaxLength),
+            Some(max) if self.overlength == Overlength::Error => {
+                let len = self.text.chars().count();
+                if len > max {
+                    errors.push(ScrambleError::TextTooLong { len, max });
+                }
+            }
+            _ => {}
+        }
         if !(0.0..=1.0).contains(&self.speed) {
-            return Err("Speed must be between 0 and 1".to_string());
+            errors.push(ScrambleError::InvalidSpeed(self.speed));
+        }
+        if let Some(max_fps) = self.max_fps.filter(|fps| !(fps.is_finite() && *fps > 0.0)) {
+            errors.push(ScrambleError::InvalidMaxFps(max_fps));
         }
         if self.tick <= 0 {
-            return Err("Tick must be greater than 0".to_string());
+            errors.push(ScrambleError::InvalidTick(self.tick));
         }
         if self.step <= 0 {
-            return Err("Step must be greater than 0".to_string());
+            errors.push(ScrambleError::InvalidStep(self.step));
         }
-        if !(0.0..=1.0).contains(&self.chance) {
-            return Err("Chance must be between 0 and 1".to_string());
+        if let Some(overdrive_step) = self.overdrive_step.filter(|&step| step <= 0) {
+            errors.push(ScrambleError::InvalidOverdriveStep(overdrive_step));
         }
+        let chances = match &self.chance {
+            Chance::Uniform(chance) => std::slice::from_ref(chance),
+            Chance::Positions(chances) => chances,
+        };
+        errors.extend(
+            chances
+                .iter()
+                .filter(|chance| !(0.0..=1.0).contains(*chance))
+                .map(|&chance| ScrambleError::InvalidChance(chance)),
+        );
         if self.seed < 0 {
-            return Err("Seed must be greater than or equal to 0".to_string());
+            errors.push(ScrambleError::InvalidSeed(self.seed));
         }
         if self.scramble < 0 {
-            return Err("Scramble must be greater than or equal to 0".to_string());
+            errors.push(ScrambleError::InvalidScramble(self.scramble));
+        }
+        let negative = match self.scramble_jitter {
+            Jitter::Times(times) => times < 0,
+            Jitter::Share(share) => share < 0.0 || share.is_nan(),
+        };
+        if negative {
+            errors.push(ScrambleError::InvalidScrambleJitter(self.scramble_jitter));
         }
 
         match &self.range {
-            RangeOrCharCodes::Range(min, max) => {
-                if *min < 0 || *max < *min {
-                    return Err("Invalid range values".to_string());
-                }
-                if !char::from_u32(*min as u32).is_some() || !char::from_u32(*max as u32).is_some()
-                {
-                    return Err("Range values must be valid Unicode scalar values".to_string());
+            &RangeOrCharCodes::Range(min, max) => {
+                // Ranges spanning the surrogates skip them, but can't start or end in them
+                if max < min || char::from_u32(min).is_none() || char::from_u32(max).is_none() {
+                    errors.push(ScrambleError::InvalidRange { min, max });
                 }
             }
             RangeOrCharCodes::Codes(codes) => {
                 if codes.is_empty() {
-                    return Err("Codes vector cannot be empty".to_string());
+                    errors.push(ScrambleError::EmptyCodes);
+                }
+                errors.extend(
+                    codes
+                        .iter()
+                        .filter(|&&code| char::from_u32(code).is_none())
+                        .map(|&code| ScrambleError::InvalidCode(code)),
+                );
+            }
+            RangeOrCharCodes::Auto => {}
+        }
+
+        if let WriteTarget::Attribute(name) = &self.target {
+            if name.is_empty() {
+                errors.push(ScrambleError::EmptyTargetAttribute);
+            }
+        }
+
+        if let Overdrive::Code(code) = self.overdrive {
+            if char::from_u32(code).is_none() {
+                errors.push(ScrambleError::InvalidOverdrive(code));
+            }
+        }
+
+        for stage in &self.pipeline {
+            match *stage {
+                Stage::Scramble {
+                    overdrive: Some(Overdrive::Code(code)),
+                    ..
+                } if char::from_u32(code).is_none() => {
+                    errors.push(ScrambleError::InvalidOverdrive(code));
                 }
-                if codes
-                    .iter()
-                    .any(|&code| !char::from_u32(code as u32).is_some())
-                {
-                    return Err("All codes must be valid Unicode scalar values".to_string());
+                Stage::Glitch { chance, .. } if !(0.0..=1.0).contains(&chance) => {
+                    errors.push(ScrambleError::InvalidChance(chance));
                 }
+                _ => {}
             }
         }
+        if let Some(Stage::Sweep { .. }) = self.pipeline.last() {
+            errors.push(ScrambleError::UnrevealedPipeline);
+        }
 
-        Ok(())
+        errors
     }
 }
 
@@ -157,17 +1522,53 @@ impl Default for UseScrambleProps {
         UseScrambleProps {
             play_on_mount: None,
             text: String::new(),
+            max_length: None,
+            overlength: Overlength::default(),
             speed: default_speed(),
+            max_fps: None,
             tick: default_tick(),
             step: default_step(),
-            chance: default_chance(),
+            chance: Chance::default(),
             seed: default_seed(),
             scramble: default_scramble(),
+            scramble_jitter: Jitter::default(),
             ignore: default_ignore(),
+            ignore_words: Vec::new(),
             range: default_range(),
-            overdrive: false,
-            overflow: false,
+            overdrive: Overdrive::default(),
+            overdrive_step: None,
+            overflow: default_overflow(),
+            pipeline: Vec::new(),
+            placeholder_char: None,
+            mismatch: Mismatch::Space,
+            whitespace: Whitespace::default(),
+            dir: Direction::default(),
+            transform: None,
+            mask: None,
             hover_replay: false,
+            triggers: Vec::new(),
+            spoiler: false,
+            conceal_after: None,
+            scroll_scrub: false,
+            hover_scrub: false,
+            hover_scrub_radius: default_hover_scrub_radius(),
+            hover_scrub_settle: default_hover_scrub_settle(),
+            css_vars: false,
+            content_selector: None,
+            target: WriteTarget::default(),
+            shared_target: SharedTarget::default(),
+            pause_when_hidden: default_pause_when_hidden(),
+            pause_on_blur: false,
+            idle: false,
+            virtualize: false,
+            spans: false,
+            reduced_motion: ReducedMotion::default(),
+            a11y: false,
+            aria_live: None,
+            announce_on_complete: false,
+            strict: default_strict(),
+            debug: false,
+            overlay: false,
         }
     }
 }
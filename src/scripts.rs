@@ -0,0 +1,124 @@
+//! Character pools for `range: "auto"`, drawn from the scripts the text is written in.
+//!
+//! Each letter of the text is matched to a script by its Unicode block, and the pool holds
+//! the letters of every script found, in the order they first appear. Text in none of them,
+//! such as digits or emoji, scrambles with Latin letters.
+
+use std::ops::RangeInclusive;
+
+use crate::bidi::{ARABIC_LETTERS, HEBREW_LETTERS};
+
+struct Script {
+    /// Blocks the script's letters are in
+    blocks: &'static [RangeInclusive<u32>],
+    /// Letters drawn for it
+    pool: &'static [RangeInclusive<u32>],
+    /// Only every `stride`th letter of `pool` is drawn, for scripts with thousands of them
+    stride: usize,
+}
+
+/// Known scripts, Latin first.
+static SCRIPTS: &[Script] = &[
+    Script {
+        blocks: &[0x41..=0x5A, 0x61..=0x7A, 0xC0..=0x24F, 0x1E00..=0x1EFF],
+        pool: &[0x41..=0x5A, 0x61..=0x7A],
+        stride: 1,
+    },
+    // Greek
+    Script {
+        blocks: &[0x370..=0x3FF, 0x1F00..=0x1FFF],
+        pool: &[0x391..=0x3A1, 0x3A3..=0x3A9, 0x3B1..=0x3C9],
+        stride: 1,
+    },
+    // Cyrillic
+    Script {
+        blocks: &[0x400..=0x52F],
+        pool: &[0x410..=0x44F],
+        stride: 1,
+    },
+    // Hebrew
+    Script {
+        blocks: &[0x590..=0x5FF, 0xFB1D..=0xFB4F],
+        pool: HEBREW_LETTERS,
+        stride: 1,
+    },
+    // Arabic
+    Script {
+        blocks: &[
+            0x600..=0x6FF,
+            0x750..=0x77F,
+            0xFB50..=0xFDFF,
+            0xFE70..=0xFEFF,
+        ],
+        pool: ARABIC_LETTERS,
+        stride: 1,
+    },
+    // Devanagari
+    Script {
+        blocks: &[0x900..=0x97F],
+        pool: &[0x905..=0x939],
+        stride: 1,
+    },
+    // Thai
+    Script {
+        blocks: &[0xE00..=0xE7F],
+        pool: &[0xE01..=0xE2E],
+        stride: 1,
+    },
+    // Hiragana
+    Script {
+        blocks: &[0x3040..=0x309F],
+        pool: &[0x3041..=0x3096],
+        stride: 1,
+    },
+    // Katakana, halfwidth ones included
+    Script {
+        blocks: &[0x30A0..=0x30FF, 0xFF66..=0xFF9D],
+        pool: &[0x30A1..=0x30FA],
+        stride: 1,
+    },
+    // Hangul
+    Script {
+        blocks: &[0x1100..=0x11FF, 0x3130..=0x318F, 0xAC00..=0xD7A3],
+        pool: &[0xAC00..=0xD7A3],
+        stride: 3,
+    },
+    // CJK ideographs
+    Script {
+        blocks: &[0x3400..=0x4DBF, 0x4E00..=0x9FFF, 0xF900..=0xFAFF],
+        pool: &[0x4E00..=0x9FFF],
+        stride: 6,
+    },
+];
+
+impl Script {
+    fn contains(&self, c: char) -> bool {
+        self.blocks.iter().any(|block| block.contains(&(c as u32)))
+    }
+
+    fn letters(&self) -> impl Iterator<Item = char> + '_ {
+        self.pool
+            .iter()
+            .flat_map(|range| range.clone().step_by(self.stride))
+            .filter_map(char::from_u32)
+    }
+}
+
+/// Letters of the scripts `text` is written in.
+pub(crate) fn pool(text: &str) -> Vec<char> {
+    let mut found: Vec<usize> = Vec::new();
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        if let Some(index) = SCRIPTS.iter().position(|script| script.contains(c)) {
+            if !found.contains(&index) {
+                found.push(index);
+            }
+        }
+    }
+    if found.is_empty() {
+        found.push(0);
+    }
+    found
+        .into_iter()
+        .flat_map(|index| SCRIPTS[index].letters())
+        .collect()
+}
@@ -0,0 +1,143 @@
+//! Per-character rendering, enabled by the `spans` prop.
+//!
+//! Each character of a frame, or each emoji sequence, is drawn in its own `<span>` with a
+//! `data-state` of `settled`, `scrambling` or `waiting`. Settled characters also get a
+//! `data-age`, the frames since they settled, so that styles can fade them in. Frames are
+//! diffed against the spans, and only what changed is touched.
+
+use std::cell::RefCell;
+use web_sys::{Element, Node};
+
+use crate::emoji::{self, Unit};
+use crate::engine::Frame;
+
+/// Age settled characters stop counting at, in frames.
+const MAX_AGE: u32 = 60;
+
+const SETTLED: &str = "settled";
+const SCRAMBLING: &str = "scrambling";
+const WAITING: &str = "waiting";
+
+struct Span {
+    node: Element,
+    text: String,
+    state: &'static str,
+    age: Option<u32>,
+}
+
+pub(crate) struct SpanText {
+    element: Element,
+    spans: RefCell<Vec<Span>>,
+}
+
+impl SpanText {
+    pub(crate) fn new(element: Element) -> SpanText {
+        SpanText {
+            element,
+            spans: RefCell::default(),
+        }
+    }
+
+    pub(crate) fn element(&self) -> &Element {
+        &self.element
+    }
+
+    /// Position in the text of the first character drawn in the span holding `node`, such as
+    /// the target of a pointer event.
+    pub(crate) fn char_index(&self, node: &Node) -> Option<usize> {
+        let mut index = 0;
+        for span in self.spans.borrow().iter() {
+            if span.node.contains(Some(node)) {
+                return Some(index);
+            }
+            index += span.text.chars().count();
+        }
+        None
+    }
+
+    /// Writes `text` across the spans, marking each with its state in `frame`. Text written
+    /// without a frame counts as settled.
+    pub(crate) fn write(&self, text: &str, frame: Option<&Frame>) {
+        let Some(document) = self.element.owner_document() else {
+            return;
+        };
+        let mut spans = self.spans.borrow_mut();
+        // The element's own text gives way to the spans on the first write
+        if spans.is_empty() {
+            self.element.set_text_content(None);
+        }
+
+        let chars: Vec<char> = text.chars().collect();
+        let units = emoji::units(&chars);
+        let mut states = vec![if frame.is_some() { WAITING } else { SETTLED }; chars.len()];
+        let mut fresh = vec![false; chars.len()];
+        if let Some(frame) = frame {
+            let mut mark = |positions: &[usize], state| {
+                for &i in positions.iter().filter(|&&i| i < chars.len()) {
+                    states[i] = state;
+                }
+            };
+            mark(&frame.scrambled, SCRAMBLING);
+            mark(&frame.settled, SETTLED);
+            for &i in frame.newly_settled.iter().filter(|&&i| i < chars.len()) {
+                fresh[i] = true;
+            }
+        }
+
+        let mut count = 0;
+        let mut i = 0;
+        while i < chars.len() {
+            let len = match units.get(i) {
+                Some(&Unit::Emoji(len)) => len.min(chars.len() - i),
+                _ => 1,
+            };
+            if count == spans.len() {
+                let Ok(node) = document.create_element("span") else {
+                    return;
+                };
+                let _ = self.element.append_child(&node);
+                spans.push(Span {
+                    node,
+                    text: String::new(),
+                    state: "",
+                    age: None,
+                });
+            }
+
+            let span = &mut spans[count];
+            let unit = &chars[i..i + len];
+            if !span.text.chars().eq(unit.iter().copied()) {
+                span.text = unit.iter().collect();
+                span.node.set_text_content(Some(&span.text));
+            }
+
+            let state = states[i];
+            let age = match (state, span.age) {
+                (SETTLED, _) if fresh[i] => Some(0),
+                (SETTLED, Some(age)) if frame.is_some() => Some((age + 1).min(MAX_AGE)),
+                (SETTLED, Some(age)) => Some(age),
+                (SETTLED, None) if frame.is_some() => Some(0),
+                (SETTLED, None) => Some(MAX_AGE),
+                _ => None,
+            };
+            if span.state != state {
+                let _ = span.node.set_attribute("data-state", state);
+                span.state = state;
+            }
+            if span.age != age {
+                let _ = match age {
+                    Some(age) => span.node.set_attribute("data-age", &age.to_string()),
+                    None => span.node.remove_attribute("data-age"),
+                };
+                span.age = age;
+            }
+
+            count += 1;
+            i += len;
+        }
+
+        for span in spans.drain(count..) {
+            span.node.remove();
+        }
+    }
+}
@@ -0,0 +1,448 @@
+//! Splitting an element's text into spans of words, characters or lines, each animated by its
+//! own instance of a `ScrambleGroup`.
+
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
+use std::str::FromStr;
+
+use js_sys::{Object, Reflect};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{Document, Element, Node, ResizeObserver};
+
+use crate::emoji::{self, Unit};
+use crate::group::{GroupState, ScrambleGroup};
+use crate::options::plain_props;
+use crate::web::{PlayState, ScrambleText};
+
+/// Pieces `split_target` wraps in spans.
+#[derive(Clone, Copy, PartialEq)]
+pub enum SplitBy {
+    /// Runs of characters between whitespace
+    Words,
+    /// Each character other than whitespace. Emoji sequences and letters with combining marks
+    /// count as one.
+    Chars,
+    /// The words laid out on each line of the element
+    Lines,
+}
+
+impl SplitBy {
+    /// Class of the spans wrapping each piece.
+    fn class(&self) -> &'static str {
+        match self {
+            SplitBy::Words => "scramble-word",
+            SplitBy::Chars => "scramble-char",
+            SplitBy::Lines => "scramble-line",
+        }
+    }
+}
+
+impl FromStr for SplitBy {
+    type Err = String;
+
+    /// Parses `words`, `chars` or `lines`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "words" => Ok(SplitBy::Words),
+            "chars" => Ok(SplitBy::Chars),
+            "lines" => Ok(SplitBy::Lines),
+            _ => Err(format!("Unknown split {}", s)),
+        }
+    }
+}
+
+/// What a split by lines does once a resize wraps the element's text into different lines.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum SplitResize {
+    /// Running animations carry on from the frame showing. Lines still waiting on their
+    /// stagger delay start at once.
+    #[default]
+    Continue,
+    /// Running animations start over, staggered again
+    Restart,
+}
+
+impl FromStr for SplitResize {
+    type Err = String;
+
+    /// Parses `continue` or `restart`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "continue" => Ok(SplitResize::Continue),
+            "restart" => Ok(SplitResize::Restart),
+            _ => Err(format!("Unknown resize behavior {}", s)),
+        }
+    }
+}
+
+/// Markup of an element before `split_target` replaced it, put back by `restore()`.
+pub(crate) struct Split {
+    element: Element,
+    html: String,
+    aria_label: Option<String>,
+    /// Re-splits the lines on resize, for splits by line
+    _lines: Option<LineObserver>,
+}
+
+/// What re-splitting an element into lines needs.
+struct Lines {
+    element: Element,
+    text: String,
+    props: JsValue,
+    on_resize: SplitResize,
+    /// Width the lines were last split at
+    width: Cell<f64>,
+}
+
+/// Splits the element of a group into lines again whenever a resize changes its width.
+/// Disconnected on drop.
+struct LineObserver {
+    observer: ResizeObserver,
+    _closure: Closure<dyn FnMut()>,
+}
+
+impl LineObserver {
+    fn new(group: Weak<RefCell<GroupState>>, lines: Lines) -> Result<LineObserver, JsValue> {
+        let element = lines.element.clone();
+        let closure = Closure::<dyn FnMut()>::new(move || {
+            let Some(group) = group.upgrade() else {
+                return;
+            };
+            if let Err(e) = resplit(&group, &lines) {
+                web_sys::console::error_1(&e.into());
+            }
+        });
+        let observer = ResizeObserver::new(closure.as_ref().unchecked_ref())?;
+        observer.observe(&element);
+        Ok(LineObserver {
+            observer,
+            _closure: closure,
+        })
+    }
+}
+
+impl Drop for LineObserver {
+    fn drop(&mut self) {
+        self.observer.disconnect();
+    }
+}
+
+impl Split {
+    pub(crate) fn restore(&self) {
+        self.element.set_inner_html(&self.html);
+        let _ = match &self.aria_label {
+            Some(label) => self.element.set_attribute("aria-label", label),
+            None => self.element.remove_attribute("aria-label"),
+        };
+    }
+}
+
+fn split_error(e: JsValue) -> JsError {
+    JsError::new(&format!("Failed to split the text: {:?}", e))
+}
+
+/// `text` cut into alternating runs of whitespace and of other characters, in order.
+fn runs(text: &str) -> Vec<&str> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    let mut last = None;
+    for (i, c) in text.char_indices() {
+        let space = c.is_whitespace();
+        if last.is_some_and(|last| last != space) {
+            runs.push(&text[start..i]);
+            start = i;
+        }
+        last = Some(space);
+    }
+    if start < text.len() {
+        runs.push(&text[start..]);
+    }
+    runs
+}
+
+/// Whether `c` combines with the character before it, such as an accent.
+fn is_combining_mark(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x0300..=0x036F | 0x1AB0..=0x1AFF | 0x1DC0..=0x1DFF | 0x20D0..=0x20FF | 0xFE20..=0xFE2F
+    )
+}
+
+/// `run` cut into what shows as one character each: emoji sequences, and characters with the
+/// combining marks after them, stay whole.
+fn characters(run: &str) -> Vec<String> {
+    let chars: Vec<char> = run.chars().collect();
+    let units = emoji::units(&chars);
+    let mut characters: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let len = match units.get(i) {
+            Some(&Unit::Emoji(len)) => len.min(chars.len() - i),
+            _ => 1,
+        };
+        match characters.last_mut() {
+            Some(last) if len == 1 && is_combining_mark(chars[i]) => last.push(chars[i]),
+            _ => characters.push(chars[i..i + len].iter().collect()),
+        }
+        i += len;
+    }
+    characters
+}
+
+/// Appends a span holding `text`, hidden from assistive technology.
+fn append_span(
+    document: &Document,
+    parent: &Node,
+    class: &str,
+    text: &str,
+) -> Result<Element, JsValue> {
+    let span = document.create_element("span")?;
+    span.set_class_name(class);
+    span.set_attribute("aria-hidden", "true")?;
+    span.set_text_content(Some(text));
+    parent.append_child(&span)?;
+    Ok(span)
+}
+
+/// Replaces the content of `element` with spans of `by`, leaving the whitespace between them
+/// as plain text. Returns the spans in order.
+fn wrap(element: &Element, text: &str, by: SplitBy) -> Result<Vec<Element>, JsValue> {
+    let document = element
+        .owner_document()
+        .ok_or_else(|| JsValue::from_str("The element is not in a document"))?;
+    element.set_text_content(None);
+
+    let mut spans = Vec::new();
+    for run in runs(text) {
+        if run.starts_with(char::is_whitespace) {
+            element.append_child(&document.create_text_node(run))?;
+            continue;
+        }
+        if by == SplitBy::Chars {
+            for character in characters(run) {
+                spans.push(append_span(&document, element, by.class(), &character)?);
+            }
+        } else {
+            spans.push(append_span(
+                &document,
+                element,
+                SplitBy::Words.class(),
+                run,
+            )?);
+        }
+    }
+    if by == SplitBy::Lines {
+        spans = join_lines(element, &document, spans)?;
+    }
+    Ok(spans)
+}
+
+/// Regroups the word spans of `element` into a span per line, by where the words are laid
+/// out. The whitespace between words of the same line moves into that line's span.
+fn join_lines(
+    element: &Element,
+    document: &Document,
+    words: Vec<Element>,
+) -> Result<Vec<Element>, JsValue> {
+    let mut lines: Vec<(f64, Vec<&Element>)> = Vec::new();
+    for word in &words {
+        let top = word.get_bounding_client_rect().top();
+        match lines.last_mut() {
+            Some((line_top, line)) if (top - *line_top).abs() < 1.0 => line.push(word),
+            _ => lines.push((top, vec![word])),
+        }
+    }
+
+    let mut spans = Vec::with_capacity(lines.len());
+    for (_, line) in lines {
+        let (first, last) = (line[0], line[line.len() - 1]);
+        let span = document.create_element("span")?;
+        span.set_class_name(SplitBy::Lines.class());
+        span.set_attribute("aria-hidden", "true")?;
+        element.insert_before(&span, Some(first.as_ref()))?;
+
+        // The words, and the whitespace between them, up to the line's last word
+        let mut text = String::new();
+        let mut node = Some(Node::from(first.clone()));
+        while let Some(current) = node {
+            text.push_str(&current.text_content().unwrap_or_default());
+            node = current.next_sibling();
+            element.remove_child(&current)?;
+            if current.is_same_node(Some(last.as_ref())) {
+                break;
+            }
+        }
+        span.set_text_content(Some(&text));
+        spans.push(span);
+    }
+    Ok(spans)
+}
+
+/// An instance for each span, with `props` and the span's text.
+fn instances(spans: Vec<Element>, props: &JsValue) -> Result<Vec<ScrambleText>, JsError> {
+    spans
+        .into_iter()
+        .map(|span| {
+            let props = Object::<JsValue>::assign(&Object::new(), props.unchecked_ref());
+            let text = span.text_content().unwrap_or_default();
+            Reflect::set(&props, &"text".into(), &text.into()).map_err(split_error)?;
+            ScrambleText::new(span.into(), props.into())
+        })
+        .collect()
+}
+
+/// The text `element` shows, with the characters of `waiting` spans replaced by NULs: spans
+/// still waiting for their staggered start show their final text, but haven't revealed it.
+fn shown_text(element: &Element, waiting: &[Element]) -> String {
+    let children = element.child_nodes();
+    let mut shown = String::new();
+    for child in (0..children.length()).filter_map(|i| children.get(i)) {
+        let text = child.text_content().unwrap_or_default();
+        if waiting.iter().any(|span| child.is_same_node(Some(span))) {
+            shown.extend(text.chars().map(|_| '\0'));
+        } else {
+            shown.push_str(&text);
+        }
+    }
+    shown
+}
+
+/// Splits the lines of a group's element again if its width changed since the last split,
+/// replacing the group's instances. Running animations carry on or restart, following
+/// `on_resize`.
+fn resplit(group: &Rc<RefCell<GroupState>>, lines: &Lines) -> Result<(), JsError> {
+    let width = lines.element.get_bounding_client_rect().width();
+    if width == lines.width.replace(width) {
+        return Ok(());
+    }
+
+    let mut group = group.borrow_mut();
+    let waiting: Vec<Element> = group
+        .instances
+        .iter()
+        .filter(|instance| instance.play_state() == PlayState::Delayed)
+        .filter_map(ScrambleText::element)
+        .collect();
+    let running = !waiting.is_empty()
+        || group.instances.iter().any(|instance| {
+            matches!(
+                instance.play_state(),
+                PlayState::Playing | PlayState::Paused
+            )
+        });
+    let shown = shown_text(&lines.element, &waiting);
+
+    group.dispose();
+    let spans = wrap(&lines.element, &lines.text, SplitBy::Lines).map_err(split_error)?;
+    group.instances = instances(spans.clone(), &lines.props)?;
+    if !running {
+        return Ok(());
+    }
+
+    match lines.on_resize {
+        SplitResize::Restart => group.start(),
+        SplitResize::Continue => {
+            // Each line resumes from its share of the text showing before the resize. The
+            // new lines do not match the old ones, so pending stagger delays are dropped and
+            // every line starts now.
+            let mut shown = shown.chars();
+            let mut resumed = spans.iter().zip(group.instances.iter_mut()).peekable();
+            let children = lines.element.child_nodes();
+            for child in (0..children.length()).filter_map(|i| children.get(i)) {
+                let frame: String = shown
+                    .by_ref()
+                    .take(child.text_content().unwrap_or_default().chars().count())
+                    .collect();
+                if let Some((_, instance)) =
+                    resumed.next_if(|(span, _)| child.is_same_node(Some(span)))
+                {
+                    instance.resume_from(frame);
+                    instance.start()?;
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Splits the text of `element` into spans of `"words"`, `"chars"` or `"lines"`, and returns a
+/// group animating each span with its own instance, ready to be staggered with `stagger()`.
+///
+/// The spans have the class `scramble-word`, `scramble-char` or `scramble-line`, and the
+/// whitespace between them is kept as plain text. Any markup inside the element is replaced by
+/// its text. The element gets an `aria-label` with the text, and the spans are hidden from
+/// assistive technology, so screen readers read the text whole. `props` apply to every
+/// instance, their `text` being the span's own. `destroy()` on the group puts the original
+/// markup back.
+///
+/// Lines are split again whenever a resize changes the element's width. `resize` sets what
+/// running animations do then: `"continue"` (the default) carries each new line on from the
+/// frame showing, lines still waiting on their stagger delay starting at once, and
+/// `"restart"` starts them over, staggered again.
+#[wasm_bindgen]
+pub fn split_target(
+    element: Element,
+    by: &str,
+    #[wasm_bindgen(unchecked_optional_param_type = "Partial<ScrambleProps> | ScrambleOptions")]
+    props: JsValue,
+    #[wasm_bindgen(unchecked_optional_param_type = "ScrambleSplitResize")] resize: Option<String>,
+) -> Result<ScrambleGroup, JsError> {
+    let by: SplitBy = by.parse().map_err(|e: String| JsError::new(&e))?;
+    let on_resize: SplitResize = match resize {
+        Some(resize) => resize.parse().map_err(|e: String| JsError::new(&e))?,
+        None => SplitResize::default(),
+    };
+    let props = if props.is_undefined() || props.is_null() {
+        Object::new().into()
+    } else {
+        plain_props(props)?
+    };
+
+    let text = element.text_content().unwrap_or_default();
+    let mut split = Split {
+        element: element.clone(),
+        html: element.inner_html(),
+        aria_label: element.get_attribute("aria-label"),
+        _lines: None,
+    };
+    let label = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let spans = wrap(&element, &text, by).and_then(|spans| {
+        element.set_attribute("aria-label", &label)?;
+        Ok(spans)
+    });
+    let spans = match spans {
+        Ok(spans) => spans,
+        Err(e) => {
+            split.restore();
+            return Err(split_error(e));
+        }
+    };
+
+    let instances = match instances(spans, &props) {
+        Ok(instances) => instances,
+        Err(e) => {
+            split.restore();
+            return Err(e);
+        }
+    };
+    let group = Rc::new(RefCell::new(GroupState::new(instances)));
+    if by == SplitBy::Lines {
+        let lines = Lines {
+            width: Cell::new(element.get_bounding_client_rect().width()),
+            element,
+            text,
+            props,
+            on_resize,
+        };
+        match LineObserver::new(Rc::downgrade(&group), lines) {
+            Ok(observer) => split._lines = Some(observer),
+            Err(e) => {
+                group.borrow_mut().dispose();
+                split.restore();
+                return Err(split_error(e));
+            }
+        }
+    }
+    Ok(ScrambleGroup::split(group, split))
+}
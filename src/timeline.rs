@@ -0,0 +1,163 @@
+//! Sequences of scramble animations with staggered starts.
+//!
+//! ```js
+//! const timeline = new ScrambleTimeline()
+//!   .add(title, { text: 'Hello' }, 0)
+//!   .add(subtitle, { text: 'World' }, 400);
+//! timeline.set_on_complete(() => console.log('done'));
+//! timeline.play();
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::Node;
+
+use crate::options::props_from_js;
+use crate::scramble::UseScrambleProps;
+use crate::web::{ScrambleText, Timeout};
+
+struct TimelineEntry {
+    scramble: ScrambleText,
+    offset: f64,
+    duration: f64,
+}
+
+/// Animations started at fixed offsets from a shared start, with one callback once the last
+/// of them finishes.
+#[wasm_bindgen]
+pub struct ScrambleTimeline {
+    entries: Vec<TimelineEntry>,
+    /// Starts of the current `play()` that are still due.
+    pending: Vec<Timeout>,
+    /// Entries of the current `play()` that have not finished yet.
+    remaining: Rc<Cell<usize>>,
+    on_complete: Rc<RefCell<Option<js_sys::Function>>>,
+    // Dropped after the entries, whose end callbacks they are
+    on_end: Vec<Closure<dyn FnMut()>>,
+}
+
+#[wasm_bindgen]
+impl ScrambleTimeline {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> ScrambleTimeline {
+        ScrambleTimeline {
+            entries: Vec::new(),
+            pending: Vec::new(),
+            remaining: Rc::default(),
+            on_complete: Rc::default(),
+            on_end: Vec::new(),
+        }
+    }
+
+    /// Appends an animation of `target` starting `offset_ms` after `play()`. Returns the
+    /// timeline, so calls can be chained.
+    pub fn add(
+        self,
+        target: Node,
+        #[wasm_bindgen(unchecked_param_type = "ScrambleProps | ScrambleOptions")] props: JsValue,
+        offset_ms: f64,
+    ) -> Result<ScrambleTimeline, JsError> {
+        let props = props_from_js(props)?;
+        self.add_props(target, props, offset_ms)
+    }
+
+    /// Time from `play()` until the last animation is expected to finish, in milliseconds.
+    /// Infinite when an animation has a speed of 0.
+    #[wasm_bindgen(getter)]
+    pub fn duration(&self) -> f64 {
+        self.entries
+            .iter()
+            .map(|entry| entry.offset + entry.duration)
+            .fold(0.0, f64::max)
+    }
+
+    /// Number of animations in the timeline.
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Called once every animation of a `play()` has finished. Stopping the timeline skips it.
+    pub fn set_on_complete(&mut self, callback: js_sys::Function) {
+        *self.on_complete.borrow_mut() = Some(callback);
+    }
+
+    /// Plays every animation from the start, restarting the timeline if it is playing.
+    pub fn play(&mut self) -> Result<(), JsError> {
+        self.stop()?;
+        if self.entries.is_empty() {
+            return Ok(());
+        }
+
+        self.remaining.set(self.entries.len());
+        for entry in &mut self.entries {
+            if entry.offset <= 0.0 {
+                entry.scramble.start()?;
+            } else {
+                self.pending.push(entry.scramble.start_after(entry.offset)?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Stops every animation and cancels the ones that have not started yet.
+    pub fn stop(&mut self) -> Result<(), JsError> {
+        // Ignore the end callbacks fired by stopping
+        self.remaining.set(0);
+        self.pending.clear();
+        self.entries
+            .iter_mut()
+            .try_for_each(|entry| entry.scramble.stop())
+    }
+}
+
+impl ScrambleTimeline {
+    /// Appends an animation from already deserialized props.
+    pub fn add_props(
+        mut self,
+        target: Node,
+        props: UseScrambleProps,
+        offset_ms: f64,
+    ) -> Result<ScrambleTimeline, JsError> {
+        let duration = props.max_duration();
+        let mut scramble = ScrambleText::with_props(target, props)?;
+
+        let remaining = self.remaining.clone();
+        let on_complete = self.on_complete.clone();
+        let on_end = Closure::<dyn FnMut()>::new(move || {
+            if remaining.get() == 0 {
+                return;
+            }
+            remaining.set(remaining.get() - 1);
+            if remaining.get() == 0 {
+                let callback = on_complete.borrow().clone();
+                if let Some(callback) = callback {
+                    let _ = callback.call0(&JsValue::null());
+                }
+            }
+        });
+        scramble.set_on_animation_end(on_end.as_ref().unchecked_ref::<js_sys::Function>().clone());
+
+        self.entries.push(TimelineEntry {
+            scramble,
+            offset: offset_ms.max(0.0),
+            duration,
+        });
+        self.on_end.push(on_end);
+        Ok(self)
+    }
+}
+
+impl Default for ScrambleTimeline {
+    fn default() -> Self {
+        ScrambleTimeline::new()
+    }
+}
+
+impl Drop for ScrambleTimeline {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
@@ -0,0 +1,55 @@
+//! Swapping an element's text for another: the text showing scrambles out, running a reveal
+//! in reverse, and the new text scrambles in, as one operation with a single promise.
+
+use js_sys::Promise;
+use wasm_bindgen::prelude::*;
+use web_sys::Element;
+
+use crate::scramble::UseScrambleProps;
+use crate::web::{merge_props, ScrambleText};
+
+/// `promise.then(callback)`, with the callback a JS function that may return a promise for
+/// the result to follow.
+fn then(promise: &Promise, callback: &JsValue) -> Result<Promise, JsError> {
+    let then: js_sys::Function = js_sys::Reflect::get(promise, &"then".into())
+        .map_err(|_| JsError::new("Failed to chain the transition"))?
+        .unchecked_into();
+    then.call1(promise, callback)
+        .map(JsCast::unchecked_into)
+        .map_err(|_| JsError::new("Failed to chain the transition"))
+}
+
+/// Scrambles out the text of `element`, then scrambles in `new_text`, with `props` for both
+/// runs. The returned promise resolves once `new_text` is revealed.
+#[wasm_bindgen]
+pub fn transition_text(
+    element: Element,
+    new_text: String,
+    #[wasm_bindgen(unchecked_optional_param_type = "Partial<ScrambleProps>")] props: JsValue,
+) -> Result<Promise, JsError> {
+    let props = UseScrambleProps {
+        text: element.text_content().unwrap_or_default(),
+        play_on_mount: Some(false),
+        ..merge_props(&UseScrambleProps::default(), &props)?
+    };
+    let mut scramble = ScrambleText::with_props(element.into(), props)?;
+    scramble.conceal()?;
+    let concealed = scramble.completion(None)?;
+
+    let reveal = Closure::once_into_js(move |_: JsValue| -> JsValue {
+        let revealed = scramble
+            .change_props(|props| props.text = new_text)
+            .and_then(|_| scramble.start_js(None));
+        match revealed {
+            Ok(promise) => {
+                // The instance lives until its reveal is over, as dropping it stops the run
+                let release = Closure::once_into_js(move |_: JsValue| drop(scramble));
+                then(&promise, &release)
+                    .map(JsValue::from)
+                    .unwrap_or_else(|e| Promise::reject(&e.into()).into())
+            }
+            Err(e) => Promise::reject(&e.into()).into(),
+        }
+    });
+    then(&concealed, &reveal)
+}
@@ -0,0 +1,258 @@
+//! TypeScript definitions for the plain objects crossing the JS boundary.
+//!
+//! wasm-bindgen types `JsValue` parameters as `any`. The interfaces below describe the props
+//! objects, frames and errors instead, and the bindings refer to them through
+//! `unchecked_param_type` and `unchecked_return_type`. Keep them in sync with the serde
+//! representation of the Rust types.
+
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_DEFINITIONS: &str = r#"
+/** Interaction that replays the animation. */
+export type ScrambleTrigger = "hover" | "focus" | "keydown" | "click" | "touchstart";
+
+/** How the animation reacts to `prefers-reduced-motion`. */
+export type ScrambleReducedMotion = "respect" | "ignore" | "force";
+
+/** What happens to text longer than `max_length`. */
+export type ScrambleOverlength = "error" | "truncate" | "chunk";
+
+/** What settled positions show while they hold a character other than their final one. */
+export type ScrambleMismatch = "space" | "final" | "scrambled" | "placeholder";
+
+/** How spaces are kept from collapsing mid-animation. */
+export type ScrambleWhitespace = "normal" | "nbsp" | "figure" | "pre";
+
+/** Direction the text reads in. */
+export type ScrambleDirection = "auto" | "ltr" | "rtl";
+
+/** Stylized variant of the text that the animation lands on. */
+export type ScrambleTransform = "leetspeak" | "uppercase" | "small-caps";
+
+/** Where an instance is in its lifecycle, as reported by its `state` getter. */
+export type ScramblePlayState = "idle" | "delayed" | "playing" | "paused" | "completed" | "disposed";
+
+/** What a new instance does when another one already draws into its node. */
+export type ScrambleSharedTarget = "queue" | "error" | "adopt";
+
+/** What a `split_target` group split into lines does when a resize wraps the text anew. */
+export type ScrambleSplitResize = "continue" | "restart";
+
+/** Politeness of the `aria-live` region announcing the text. */
+export type ScrambleAriaLive = "off" | "polite" | "assertive";
+
+/** Stage of a `pipeline`, run once the one before it is done. Options left out default to
+ * the props of the same names, or for a glitch to 30 frames with a 0.05 chance. */
+export type ScrambleStage =
+  | { stage: "sweep"; char?: string | null }
+  | { stage: "scramble"; overflow?: boolean | null; overdrive?: boolean | number | null }
+  | { stage: "typewriter" }
+  | { stage: "glitch"; frames?: number; chance?: number };
+
+/** Code point, as a number, the character itself such as `"あ"`, or a hex string such as
+ * `"0x2591"` or `"U+2591"`. */
+export type ScrambleCodePoint = number | string;
+
+/** Inclusive code point range, a list of code points to pick from, or `"auto"` for letters of
+ * the scripts the text is written in. */
+export type ScrambleRange =
+  | { Range: [ScrambleCodePoint, ScrambleCodePoint] }
+  | { range: [ScrambleCodePoint, ScrambleCodePoint] }
+  | { Codes: ScrambleCodePoint[] }
+  | { codes: ScrambleCodePoint[] }
+  | "auto";
+
+/** Props accepted by `ScrambleText`, `ScrambleEngine`, `ScrambleWorker` and `ScrambleTimeline`. */
+export interface ScrambleProps {
+  /** Play automatically when a text input is first provided. */
+  play_on_mount?: boolean | null;
+  /** Text to reveal. */
+  text: string;
+  /** Longest text animated at once, in characters. No limit by default. */
+  max_length?: number | null;
+  /** Defaults to `"error"`. */
+  overlength?: ScrambleOverlength;
+  /** 0-1, where 1 redraws 60 times a second and 0 pauses. Defaults to 1. */
+  speed?: number;
+  /** Cap on redraws per second, without slowing the reveal. */
+  max_fps?: number | null;
+  /** Frames between each step of the reveal front. Defaults to 1. */
+  tick?: number;
+  /** Characters the reveal front moves forward on every tick. Defaults to 1. */
+  step?: number;
+  /** 0-1 chance of scrambling a character, a list with the chance of each character, or a
+   * function giving the chance of each. Defaults to 1. */
+  chance?: number | number[] | ((index: number, length: number) => number);
+  /** Number of characters to randomly scramble ahead of the front. Defaults to 1. */
+  seed?: number;
+  /** How many times each character is scrambled. Defaults to 1. */
+  scramble?: number;
+  /** Most extra times each character is scrambled, at random: a number, or a share of
+   * `scramble`. Defaults to `"50%"`; 0 settles every character after `scramble` times. */
+  scramble_jitter?: number | `${number}%`;
+  /** Characters that are never scrambled, as a string such as `".,!?"` or a list of strings.
+   * Defaults to `[" "]`. */
+  ignore?: string | string[];
+  /** Words never scrambled, matched whole and case-sensitively. */
+  ignore_words?: string[];
+  /** Code points to scramble with. Defaults to `{ Range: [65, 125] }`. */
+  range?: ScrambleRange;
+  /** `true` for an underscore sweep, or the code point to sweep with. */
+  overdrive?: boolean | number;
+  /** Characters the overdrive sweep moves forward on every frame. Defaults to `step`. */
+  overdrive_step?: number | null;
+  /** Scramble the full-length text from the first frame. Defaults to true. */
+  overflow?: boolean;
+  /** Stages run in order, e.g. a sweep, a scramble reveal, then a glitch. Empty by default,
+   * for a single scramble reveal. */
+  pipeline?: ScrambleStage[];
+  /** Single character shown where the reveal hasn't reached yet, instead of nothing. */
+  placeholder_char?: string | null;
+  /** Defaults to `"space"`. */
+  mismatch?: ScrambleMismatch;
+  /** Defaults to `"normal"`. */
+  whitespace?: ScrambleWhitespace;
+  /** Defaults to `"auto"`: right to left when the text's first letter, or the element, is. */
+  dir?: ScrambleDirection;
+  /** Style the animation lands on; the props keep the original text. */
+  transform?: ScrambleTransform | null;
+  /** Character revealed characters show as, e.g. `"•"`; `set_masked(false)` shows the text. */
+  mask?: string | null;
+  /** Replay on hover, same as a `"hover"` trigger. */
+  hover_replay?: boolean;
+  /** Interactions that replay the animation. */
+  triggers?: ScrambleTrigger[];
+  /** Start scrambled, reveal on activation, and scramble back when left or activated again. */
+  spoiler?: boolean;
+  /** Milliseconds after a reveal completes before the text scrambles back. */
+  conceal_after?: number | null;
+  /** Reveal as the element scrolls into view instead of over time. */
+  scroll_scrub?: boolean;
+  /** Scramble the characters under the pointer again, settling back after a delay. */
+  hover_scrub?: boolean;
+  /** Neighbors on each side of the pointer that `hover_scrub` scrambles too. Defaults to 1. */
+  hover_scrub_radius?: number;
+  /** Milliseconds `hover_scrub` characters take to settle back. Defaults to 300. */
+  hover_scrub_settle?: number;
+  /** Set `--scramble-progress` (0-1) and `--scramble-active-index` on the element every frame. */
+  css_vars?: boolean;
+  /** Animate the target's descendants matching this CSS selector instead, sharing the text in document order. */
+  content_selector?: string | null;
+  /** `"textContent"` (default), `"value"`, or an attribute name such as `"placeholder"`. */
+  target?: "textContent" | "value" | (string & {});
+  /** Defaults to `"queue"`. */
+  shared_target?: ScrambleSharedTarget;
+  /** Pause while the document is hidden. Defaults to true. */
+  pause_when_hidden?: boolean;
+  /** Pause while the window is blurred. */
+  pause_on_blur?: boolean;
+  /** Draw frames when the browser is idle, for purely decorative text. */
+  idle?: boolean;
+  /** Only scramble and redraw the chunks of long text near the viewport. */
+  virtualize?: boolean;
+  /** Draw each character in a span with `data-state` and, once settled, `data-age`. */
+  spans?: boolean;
+  /** Defaults to `"respect"`. */
+  reduced_motion?: ScrambleReducedMotion;
+  /** Give screen readers the final text instead of the scrambled frames. */
+  a11y?: boolean;
+  aria_live?: ScrambleAriaLive | null;
+  /** Announce the final text once the animation completes. */
+  announce_on_complete?: boolean;
+  /** Reject unknown keys. Defaults to true. */
+  strict?: boolean;
+  /** Log runs, frames and rejected props to the console. */
+  debug?: boolean;
+  /** Show a panel with the live scramble state. Needs the `overlay` build feature. */
+  overlay?: boolean;
+}
+
+/** The props in effect, with defaults filled in. */
+export type ResolvedScrambleProps = Omit<Required<ScrambleProps>, "play_on_mount" | "max_length" | "max_fps" | "overdrive_step" | "ignore" | "placeholder_char" | "transform" | "mask" | "conceal_after" | "content_selector" | "aria_live"> &
+  Pick<ScrambleProps, "play_on_mount" | "max_length" | "max_fps" | "overdrive_step" | "placeholder_char" | "transform" | "mask" | "conceal_after" | "content_selector" | "aria_live"> & {
+    /** Sorted, one character each. */
+    ignore: string[];
+  };
+
+/** A frame drawn by `ScrambleEngine`. */
+export interface ScrambleFrame {
+  /** Text to display for this frame. */
+  text: string;
+  /** Character positions that show their final character. */
+  settled: number[];
+  /** Character positions that landed on their final character in this frame. */
+  newly_settled: number[];
+  /** Character positions that drew a new random character in this frame. */
+  scrambled: number[];
+  /** Index in `text` where each position of the text is drawn, in characters. */
+  offsets: number[];
+  /** True once `text` matches the target text. */
+  complete: boolean;
+}
+
+/** A frame captured by `record_frames`, drawn `time` milliseconds into the run. */
+export interface ScrambleRecordedFrame extends ScrambleFrame {
+  time: number;
+}
+
+/** Every frame of a run, as returned by `record_frames`. */
+export interface ScrambleRecording {
+  frames: ScrambleRecordedFrame[];
+  /** Time until the last frame, in milliseconds. */
+  duration: number;
+  /** False when the run was cut short by the frame limit, or paused by a speed of 0. */
+  complete: boolean;
+}
+
+/** Snapshot of a run, as returned by `export_state`. */
+export interface ScrambleState {
+  text: string;
+  /** Per position: the scrambles left, the character showing, or null when not drawn. */
+  control: (number | string | null)[];
+  frame_count: number;
+  scramble_index: number;
+  overdrive_index: number;
+  stage: number;
+  stage_frames: number;
+  chunk_start: number;
+  elapsed: number;
+  complete: boolean;
+  /** Position in the noise of a seeded instance. */
+  random: { seed: number; drawn: number } | null;
+}
+
+/** Machine-readable `code` of errors thrown for invalid props or setups. */
+export type ScrambleErrorCode =
+  | "invalid_max_length"
+  | "text_too_long"
+  | "invalid_speed"
+  | "invalid_max_fps"
+  | "invalid_tick"
+  | "invalid_step"
+  | "invalid_overdrive_step"
+  | "invalid_chance"
+  | "invalid_seed"
+  | "invalid_scramble"
+  | "invalid_scramble_jitter"
+  | "invalid_range"
+  | "empty_codes"
+  | "invalid_code"
+  | "empty_target_attribute"
+  | "invalid_overdrive"
+  | "unrevealed_pipeline"
+  | "no_window"
+  | "no_document"
+  | "disposed"
+  | "target_in_use"
+  | "state_mismatch"
+  | "unknown_prop"
+  | "invalid_props";
+
+/** Error thrown for invalid props or setups. */
+export interface ScrambleError extends Error {
+  code: ScrambleErrorCode;
+  /** One error per problem, for `invalid_props`. */
+  errors?: ScrambleError[];
+}
+"#;
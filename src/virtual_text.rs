@@ -0,0 +1,192 @@
+//! Virtualized rendering of long texts, enabled by the `virtualize` prop.
+//!
+//! The text is split into chunks of at most [`CHUNK_LEN`] characters, each in its own `<span>`
+//! watched by an `IntersectionObserver`. Only the characters of chunks near the viewport are
+//! scrambled and rewritten every frame; the rest show their final text, written once.
+
+use std::cell::{Cell, RefCell};
+use std::ops::Range;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{
+    Element, IntersectionObserver, IntersectionObserverEntry, IntersectionObserverInit, Text,
+};
+
+/// Longest chunk, in characters. Lines are chunked separately.
+const CHUNK_LEN: usize = 200;
+
+/// How far outside the viewport chunks still count as visible, so that text scrolled in
+/// during a run is already scrambling.
+const ROOT_MARGIN: &str = "200px";
+
+struct Chunk {
+    node: Text,
+    /// Characters of the text in this chunk.
+    range: Range<usize>,
+    /// Whether the chunk shows its final text while off screen, so it needs no more writes.
+    synced: Cell<bool>,
+}
+
+/// Visibility of each chunk, set by the observer. Every chunk counts as visible until the
+/// observer first reports.
+type Visibility = Rc<RefCell<Vec<bool>>>;
+
+pub(crate) struct VirtualText {
+    element: Element,
+    /// Text the chunks were built for.
+    text: RefCell<String>,
+    chunks: RefCell<Vec<Chunk>>,
+    visibility: Visibility,
+    observer: IntersectionObserver,
+    _on_intersect: Closure<dyn FnMut(js_sys::Array)>,
+}
+
+/// Char ranges of the chunks of `text`: every line, split further into runs of at most
+/// `CHUNK_LEN` characters.
+fn chunk_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    for (i, c) in text.chars().enumerate() {
+        if c == '\n' || i + 1 - start == CHUNK_LEN {
+            ranges.push(start..i + 1);
+            start = i + 1;
+        }
+    }
+    let len = text.chars().count();
+    if start < len || ranges.is_empty() {
+        ranges.push(start..len);
+    }
+    ranges
+}
+
+impl VirtualText {
+    pub(crate) fn new(element: Element) -> Result<VirtualText, JsValue> {
+        let visibility: Visibility = Rc::default();
+        let on_intersect = {
+            let visibility = visibility.clone();
+            Closure::<dyn FnMut(js_sys::Array)>::new(move |entries: js_sys::Array| {
+                let mut visibility = visibility.borrow_mut();
+                for entry in entries.iter() {
+                    let entry: IntersectionObserverEntry = entry.unchecked_into();
+                    let index = entry
+                        .target()
+                        .get_attribute("data-scramble-chunk")
+                        .and_then(|index| index.parse::<usize>().ok());
+                    if let Some(visible) = index.and_then(|index| visibility.get_mut(index)) {
+                        *visible = entry.is_intersecting();
+                    }
+                }
+            })
+        };
+        let options = IntersectionObserverInit::new();
+        options.set_root_margin(ROOT_MARGIN);
+        let observer = IntersectionObserver::new_with_options(
+            on_intersect.as_ref().unchecked_ref(),
+            &options,
+        )?;
+
+        Ok(VirtualText {
+            element,
+            text: RefCell::default(),
+            chunks: RefCell::default(),
+            visibility,
+            observer,
+            _on_intersect: on_intersect,
+        })
+    }
+
+    pub(crate) fn element(&self) -> &Element {
+        &self.element
+    }
+
+    /// Splits the element into chunks for `text`, unless they were already built for it.
+    pub(crate) fn prepare(&self, text: &str) -> Result<(), JsValue> {
+        if *self.text.borrow() == text && !self.chunks.borrow().is_empty() {
+            return Ok(());
+        }
+
+        self.observer.disconnect();
+        let document = self.element.owner_document().ok_or("No document found")?;
+        let chars: Vec<char> = text.chars().collect();
+        let ranges = chunk_ranges(text);
+        let mut chunks = Vec::with_capacity(ranges.len());
+
+        self.element.set_text_content(None);
+        for (i, range) in ranges.into_iter().enumerate() {
+            let wrapper = document.create_element("span")?;
+            wrapper.set_attribute("data-scramble-chunk", &i.to_string())?;
+            let node = document.create_text_node(&chars[range.clone()].iter().collect::<String>());
+            wrapper.append_child(&node)?;
+            self.element.append_child(&wrapper)?;
+            self.observer.observe(&wrapper);
+            chunks.push(Chunk {
+                node,
+                range,
+                synced: Cell::new(true),
+            });
+        }
+
+        // Every chunk counts as visible until the observer first reports
+        self.visibility.borrow_mut().clear();
+        self.visibility.borrow_mut().resize(chunks.len(), true);
+        *self.chunks.borrow_mut() = chunks;
+        *self.text.borrow_mut() = text.to_string();
+        Ok(())
+    }
+
+    /// Characters of the chunks near the viewport, or `None` when every chunk is.
+    pub(crate) fn visible_range(&self) -> Option<Range<usize>> {
+        let visibility = self.visibility.borrow();
+        if visibility.iter().all(|&visible| visible) {
+            return None;
+        }
+        let chunks = self.chunks.borrow();
+        let first = visibility.iter().position(|&visible| visible);
+        let last = visibility.iter().rposition(|&visible| visible);
+        match (first, last) {
+            (Some(first), Some(last)) => Some(chunks[first].range.start..chunks[last].range.end),
+            _ => Some(0..0),
+        }
+    }
+
+    /// Writes a frame across the chunks, skipping off screen chunks that already show their
+    /// final text.
+    pub(crate) fn write(&self, text: &str) {
+        let chunks = self.chunks.borrow();
+        if chunks.is_empty() {
+            self.element.set_text_content(Some(text));
+            return;
+        }
+
+        let visibility = self.visibility.borrow();
+        let final_text = self.text.borrow();
+        let mut rest = text;
+        let mut final_rest = final_text.as_str();
+        for (i, chunk) in chunks.iter().enumerate() {
+            let len = chunk.range.len();
+            let split = |s: &str| {
+                s.char_indices()
+                    .nth(len)
+                    .map_or(s.len(), |(index, _)| index)
+            };
+            let (head, tail) = rest.split_at(split(rest));
+            let (final_head, final_tail) = final_rest.split_at(split(final_rest));
+            rest = tail;
+            final_rest = final_tail;
+
+            let visible = visibility.get(i).copied().unwrap_or(true);
+            if !visible && chunk.synced.get() {
+                continue;
+            }
+            chunk.node.set_data(head);
+            chunk.synced.set(head == final_head);
+        }
+    }
+}
+
+impl Drop for VirtualText {
+    fn drop(&mut self) {
+        self.observer.disconnect();
+    }
+}
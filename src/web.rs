@@ -0,0 +1,1886 @@
+use js_sys::Promise;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::{Rc, Weak};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{
+    AbortSignal, Animation, Document, DocumentFragment, Element, Event, EventTarget, KeyboardEvent,
+    Node,
+};
+
+use crate::a11y::{Announcer, StableLabel};
+use crate::attributes::props_from_attributes;
+use crate::engine::{EngineState, Frame, ScrambleEngine};
+use crate::error::ScrambleError;
+use crate::motion::prefers_reduced_motion;
+use crate::options::{check_prop_keys, props_from_js, resolve_chance};
+#[cfg(feature = "overlay")]
+use crate::overlay::Overlay;
+use crate::plugin::{FramePlugin, JsPlugin};
+use crate::render::{PreservedWhitespace, ProgressVars, RenderTarget};
+use crate::scheduler;
+use crate::scramble::{
+    AriaLive, Direction, RangeOrCharCodes, ReducedMotion, SharedTarget, Trigger, UseScrambleProps,
+    Whitespace,
+};
+
+/// Characters of the text that `debug` logs show for each instance.
+const DEBUG_LABEL_LEN: usize = 16;
+
+thread_local! {
+    /// Instances by the node they draw into, so that instances sharing a node take turns.
+    static TARGETS: RefCell<Vec<(Node, Weak<RefCell<AnimationState>>)>> = RefCell::default();
+}
+
+/// State of the test hooks, set from tests with the `test-hooks` feature.
+#[cfg(feature = "test-hooks")]
+#[derive(Default)]
+struct TestHooks {
+    /// Set by `use_fake_clock()`. The browser's frames no longer drive the instance, only
+    /// `advance_clock()` and `step_frame()` do.
+    fake_clock: bool,
+    /// Set by `seed_random()`, seeding the engine of every run.
+    random_seed: Option<u64>,
+}
+
+/// Animation state shared between the instance, the scheduler and the closures it hands to
+/// the browser.
+pub(crate) struct AnimationState {
+    target: RenderTarget,
+    /// Props as configured. Each run builds its engine from these, damped for reduced motion
+    /// when that applies.
+    props: UseScrambleProps,
+    engine: ScrambleEngine,
+    running: bool,
+    /// Whether the last run completed on its own, rather than being stopped.
+    completed: bool,
+    /// Alive while a start scheduled by `start_after()` is still due.
+    delay: Weak<()>,
+    /// Instance drawing into the same node whose run this one's start waits for, with
+    /// `shared_target: "queue"`.
+    waiting_on: Option<Weak<RefCell<AnimationState>>>,
+    /// Whether the target was in the document on the last frame.
+    connected: bool,
+    /// Set when a scroll scrubbed run needs redrawing.
+    scrub_dirty: bool,
+    scrub_listeners: Vec<Listener>,
+    /// Web Animation whose progress the run follows, set by `bind_to_timeline()`.
+    timeline: Option<Animation>,
+    /// Runs queued by `then_text()`, each started once the previous one completes.
+    queued: VecDeque<UseScrambleProps>,
+    /// Frame already showing, set by `hydrate()`, that the next run resumes from.
+    resume_frame: Option<String>,
+    /// Snapshot set by `import_state()`, that the next run resumes from.
+    resume_state: Option<EngineState>,
+    /// Buffer each frame is drawn into, reused from frame to frame.
+    frame: Frame,
+    /// Text last written to the target during this run, so unchanged frames are skipped.
+    written: Option<String>,
+    disposed: bool,
+    trigger_listeners: Vec<Listener>,
+    /// Settles the promise returned by the JS `start()`, as `(resolve, reject)`.
+    completion: Option<(js_sys::Function, js_sys::Function)>,
+    abort_listener: Option<Listener>,
+    stable_label: Option<StableLabel>,
+    announcer: Option<Announcer>,
+    /// Panel showing the scramble state, with the `overlay` prop.
+    #[cfg(feature = "overlay")]
+    overlay: Option<Overlay>,
+    /// Inline `white-space` set with `whitespace: "pre"`.
+    preserved_whitespace: Option<PreservedWhitespace>,
+    /// Custom properties following the reveal, with the `css_vars` prop.
+    progress_vars: Option<ProgressVars>,
+    #[cfg(feature = "test-hooks")]
+    test_hooks: TestHooks,
+    on_animation_start: Option<js_sys::Function>,
+    on_animation_end: Option<js_sys::Function>,
+    on_animation_frame: Option<js_sys::Function>,
+    /// Called with the positions that settled in each frame where some did.
+    on_settle_sound: Option<js_sys::Function>,
+    on_dispose: Option<js_sys::Function>,
+    /// Maps the text to the one runs land on, set by `set_transform()`.
+    transform: Option<js_sys::Function>,
+    /// Link set as the target's `href` once a run completes, by `obfuscate_contact()`.
+    reveal_href: Option<String>,
+    /// Progress of a run scrambling the text back, started by `conceal()`.
+    concealing: Option<f64>,
+    /// Whether the run only settles the characters `hover_scrub` scrambled after the last run
+    /// completed. It ends without calling back or resolving anything.
+    hover_scrubbing: bool,
+    /// Whether the text has been revealed since it was last concealed.
+    revealed: bool,
+    /// Conceals the text once `conceal_after` has passed since the last reveal.
+    conceal_timer: Option<Timeout>,
+    /// Set by `set_masked(false)`, showing the text itself in place of its `mask`.
+    unmasked: bool,
+}
+
+impl AnimationState {
+    pub(crate) fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Logs `message` to the console with the `debug` prop, prefixed with the start of the text
+    /// to tell instances apart.
+    pub(crate) fn debug_log(&self, message: impl FnOnce() -> String) {
+        if !self.props.debug {
+            return;
+        }
+        let line = format!("[scramble-text] {:?}: {}", self.debug_label(), message());
+        web_sys::console::log_1(&line.into());
+    }
+
+    /// Start of the text, telling instances apart in `debug` logs and the overlay.
+    fn debug_label(&self) -> String {
+        let mut label: String = self.props.text.chars().take(DEBUG_LABEL_LEN).collect();
+        if label.len() < self.props.text.len() {
+            label.push('…');
+        }
+        label
+    }
+
+    /// Adds or removes the overlay panel to match the `overlay` prop.
+    #[cfg(feature = "overlay")]
+    fn sync_overlay(&mut self) -> Result<(), JsError> {
+        if self.props.overlay && self.overlay.is_none() {
+            let overlay = Overlay::new()
+                .map_err(|e| JsError::new(&format!("Failed to add the overlay: {:?}", e)))?;
+            overlay.update(&self.debug_label(), &self.engine, &self.frame.text);
+            self.overlay = Some(overlay);
+        } else if !self.props.overlay {
+            self.overlay = None;
+        }
+        Ok(())
+    }
+
+    /// Sets or restores the target's inline `white-space` to match the `whitespace` prop.
+    fn sync_whitespace(&mut self) {
+        if self.props.whitespace != Whitespace::Pre {
+            self.preserved_whitespace = None;
+        } else if self.preserved_whitespace.is_none() {
+            self.preserved_whitespace = PreservedWhitespace::new(&self.target);
+        }
+    }
+
+    /// Adds or removes the progress custom properties to match the `css_vars` prop.
+    fn sync_progress_vars(&mut self) {
+        if !self.props.css_vars {
+            self.progress_vars = None;
+        } else if self.progress_vars.is_none() {
+            self.progress_vars = ProgressVars::new(&self.target);
+        }
+    }
+
+    /// Moves a concealing run `share` (0-1) of the way back towards fully scrambled text.
+    fn conceal_by(&mut self, share: f64) {
+        let progress = self
+            .concealing
+            .map_or(0.0, |progress| (progress - share).max(0.0));
+        self.concealing = Some(progress);
+        self.engine.seek_into(progress, &mut self.frame);
+    }
+
+    /// Sets a spoiler's `aria-pressed` to whether it is revealed.
+    fn sync_pressed(&self) {
+        if !self.props.spoiler {
+            return;
+        }
+        if let Some(element) = self.target.event_element() {
+            let pressed = if self.revealed { "true" } else { "false" };
+            let _ = element.set_attribute("aria-pressed", pressed);
+        }
+    }
+
+    /// Writes the current frame to the target, unless it is what the target already shows.
+    fn write_current_frame(&mut self) {
+        if let Some(vars) = &mut self.progress_vars {
+            vars.update(self.engine.progress(), self.engine.reveal_front());
+        }
+        if self.written.as_deref() == Some(self.frame.text.as_str()) && !self.target.ages() {
+            return;
+        }
+        self.target.write_frame(&self.frame);
+        let written = self.written.get_or_insert_with(String::new);
+        written.clear();
+        written.push_str(&self.frame.text);
+    }
+
+    /// Whether the run is drawn when the browser is idle rather than on every animation frame.
+    pub(crate) fn is_idle(&self) -> bool {
+        self.props.idle
+    }
+
+    /// Whether the run has a frame to draw. Scroll scrubbed runs only redraw after scrolling,
+    /// and runs slowed to a speed of 0 not at all.
+    pub(crate) fn wants_frame(&self) -> bool {
+        if !self.running {
+            return false;
+        }
+        if self.timeline.is_some() {
+            true
+        } else if self.props.scroll_scrub {
+            self.scrub_dirty
+        } else {
+            self.props.speed > 0.0
+        }
+    }
+
+    /// Whether the run follows the scroll position or a timeline rather than time. Such runs
+    /// keep following it until stopped, even once the text is revealed.
+    fn follows_input(&self) -> bool {
+        self.props.scroll_scrub || self.timeline.is_some()
+    }
+
+    /// Tracks whether a running animation's target is in the document, returning true once
+    /// it has been removed. Targets that were never connected, such as fragments, don't count.
+    pub(crate) fn left_document(&mut self) -> bool {
+        let connected = self.target.is_connected();
+        let left = self.running && self.connected && !connected;
+        self.connected = connected;
+        left
+    }
+
+    /// Whether the run is damped when the user turns on reduced motion.
+    pub(crate) fn respects_reduced_motion(&self) -> bool {
+        self.props.reduced_motion == ReducedMotion::Respect
+    }
+
+    /// Whether the animation is held while the document is hidden or the window is blurred.
+    pub(crate) fn is_paused(&self, hidden: bool, blurred: bool) -> bool {
+        #[cfg(feature = "test-hooks")]
+        if self.test_hooks.fake_clock {
+            return true;
+        }
+        let props = self.engine.props();
+        (hidden && props.pause_when_hidden) || (blurred && props.pause_on_blur)
+    }
+}
+
+fn call_callback(callback: Option<js_sys::Function>, arg: Option<&JsValue>) {
+    if let Some(callback) = callback {
+        let this = JsValue::null();
+        let _ = match arg {
+            Some(arg) => callback.call1(&this, arg),
+            None => callback.call0(&this),
+        };
+    }
+}
+
+/// What a run leaves behind once it ends, released after the state is no longer borrowed.
+struct RunEnd {
+    resolve: Option<js_sys::Function>,
+    _abort_listener: Option<Listener>,
+    _stable_label: Option<StableLabel>,
+    _scrub_listeners: Vec<Listener>,
+}
+
+impl RunEnd {
+    /// Resolves the run's promise. The abort listener and stable label are removed as `self`
+    /// is dropped.
+    fn finish(self) {
+        call_callback(self.resolve, None);
+    }
+}
+
+/// Marks the current run as over.
+fn end_run(state: &mut AnimationState) -> RunEnd {
+    state.running = false;
+    state.waiting_on = None;
+    state.timeline = None;
+    state.concealing = None;
+    state.hover_scrubbing = false;
+    RunEnd {
+        resolve: state.completion.take().map(|(resolve, _)| resolve),
+        _abort_listener: state.abort_listener.take(),
+        _stable_label: state.stable_label.take(),
+        _scrub_listeners: std::mem::take(&mut state.scrub_listeners),
+    }
+}
+
+fn start_animation(state: &Rc<RefCell<AnimationState>>) -> Result<(), JsError> {
+    // A new reveal is concealed after its own delay
+    let conceal_timer = state.borrow_mut().conceal_timer.take();
+    drop(conceal_timer);
+    start_run(state, None)
+}
+
+/// Scrambles revealed text back, running the reveal in reverse from where it has got to.
+fn conceal_animation(state: &Rc<RefCell<AnimationState>>) -> Result<(), JsError> {
+    let progress = {
+        let state = state.borrow();
+        if state.disposed {
+            return Err(ScrambleError::Disposed.into());
+        }
+        if !state.revealed {
+            return Ok(());
+        }
+        if state.running {
+            state.engine.progress()
+        } else {
+            1.0
+        }
+    };
+
+    start_run(state, None)?;
+    let mut state = state.borrow_mut();
+    state.concealing = Some(progress);
+    state.revealed = false;
+    state.sync_pressed();
+    state.debug_log(|| "concealing".into());
+    Ok(())
+}
+
+/// Conceals the text after `delay_ms`, unless it has been concealed or replayed by then.
+fn conceal_timer(state: &Rc<RefCell<AnimationState>>, delay_ms: f64) -> Result<Timeout, JsError> {
+    let weak_state = Rc::downgrade(state);
+    let closure = Closure::<dyn FnMut()>::new(move || {
+        if let Some(state) = weak_state.upgrade() {
+            if state.borrow().running {
+                return;
+            }
+            if let Err(e) = conceal_animation(&state) {
+                web_sys::console::error_1(&e.into());
+            }
+        }
+    });
+    Timeout::new(delay_ms, closure)
+}
+
+/// Scrambles the character drawn in `node` again, with `hover_scrub_radius` neighbors. Once a
+/// run has completed, frames are drawn again until they settle.
+fn hover_scrub(state: &Rc<RefCell<AnimationState>>, node: &Node) -> Result<(), JsError> {
+    {
+        let state = &mut *state.borrow_mut();
+        if state.disposed || !(state.running || state.completed) {
+            return Ok(());
+        }
+        let Some(index) = state.target.char_index(node) else {
+            return Ok(());
+        };
+        let radius = state.props.hover_scrub_radius as usize;
+        let frames = state.props.hover_scrub_settle * 60.0 * state.props.speed as f64 / 1000.0;
+        let range = index.saturating_sub(radius)..index.saturating_add(radius + 1);
+        if !state.engine.rescramble(range, frames.ceil() as u32) || state.running {
+            return Ok(());
+        }
+        state.running = true;
+        state.hover_scrubbing = true;
+    }
+    scheduler::register(state)
+}
+
+/// Starts a run, following `timeline` instead of time when given.
+fn start_run(
+    state: &Rc<RefCell<AnimationState>>,
+    timeline: Option<Animation>,
+) -> Result<(), JsError> {
+    if state.borrow().disposed {
+        return Err(ScrambleError::Disposed.into());
+    }
+
+    // Another instance's run on the same node goes first
+    if state.borrow().props.shared_target == SharedTarget::Queue {
+        if let Some(other) = running_on_target(state) {
+            let mut state = state.borrow_mut();
+            state.waiting_on = Some(Rc::downgrade(&other));
+            state.debug_log(|| "run waiting for another instance on the same node".into());
+            return Ok(());
+        }
+    }
+
+    // Clean up any existing animation, keeping the runs queued after it and the instances
+    // waiting for it
+    stop_run(state);
+
+    // Call the start callback if it exists
+    let on_start = state.borrow().on_animation_start.clone();
+    call_callback(on_start, None);
+
+    // Reset animation state, honoring the current reduced motion preference
+    {
+        let mut state = state.borrow_mut();
+        let props = state.run_props(&state.props);
+        let final_text = props.final_text();
+        state.completed = false;
+        state.engine.replace(ScrambleEngine::new(props)?);
+        #[cfg(feature = "test-hooks")]
+        if let Some(seed) = state.test_hooks.random_seed {
+            state.engine.seed_random(seed);
+        }
+        // The target may have been changed since the last run, so its first frame is written
+        state.written = None;
+        state.revealed = true;
+        state.sync_pressed();
+        state.target.prepare(&final_text)?;
+        if let Some(resume) = state.resume_state.take() {
+            state.engine.import_state(&resume)?;
+        } else if let Some(frame) = state.resume_frame.take() {
+            state.engine.resume_from(&frame);
+        }
+    }
+
+    // A speed of 0 pauses the animation, unless it follows the scroll position or a timeline
+    let (speed, scroll_scrub) = {
+        let state = state.borrow();
+        (state.props.speed, state.props.scroll_scrub)
+    };
+    if speed == 0.0 && !scroll_scrub && timeline.is_none() {
+        state
+            .borrow()
+            .debug_log(|| "run paused at speed 0, waiting for a speed above 0".into());
+        return Ok(());
+    }
+
+    let scrub_listeners = if scroll_scrub {
+        scrub_listeners(state)?
+    } else {
+        Vec::new()
+    };
+
+    {
+        let state = &mut *state.borrow_mut();
+
+        // Screen readers get the final text while the frames are scrambled
+        if let Some(element) = state.target.text_element().filter(|_| state.props.a11y) {
+            let label = StableLabel::new(element, &state.spoken_text())
+                .map_err(|e| JsError::new(&format!("Failed to add a stable label: {:?}", e)))?;
+            state.stable_label = Some(label);
+        }
+
+        if let Some(announcer) = &state.announcer {
+            announcer.clear();
+        }
+
+        state.running = true;
+        state.connected = false;
+        state.scrub_dirty = scroll_scrub;
+        state.scrub_listeners = scrub_listeners;
+        state.timeline = timeline;
+
+        let driver = if state.timeline.is_some() {
+            "a timeline"
+        } else if scroll_scrub {
+            "the scroll position"
+        } else {
+            "time"
+        };
+        state.debug_log(|| format!("run started, following {}", driver));
+    }
+    scheduler::register(state)
+}
+
+/// Redraws a scroll scrubbed run whenever the page scrolls or the viewport resizes.
+fn scrub_listeners(state: &Rc<RefCell<AnimationState>>) -> Result<Vec<Listener>, JsError> {
+    let window = web_sys::window().ok_or(ScrambleError::NoWindow)?;
+
+    ["scroll", "resize"]
+        .into_iter()
+        .map(|event| {
+            let weak_state = Rc::downgrade(state);
+            let closure = Closure::<dyn FnMut(Event)>::new(move |_event: Event| {
+                if let Some(state) = weak_state.upgrade() {
+                    state.borrow_mut().scrub_dirty = true;
+                    let _ = scheduler::register(&state);
+                }
+            });
+            Listener::new(window.clone().into(), event, closure)
+        })
+        .collect()
+}
+
+/// How far the target has scrolled into view: 0 as its top enters at the bottom of the
+/// viewport, and 1 once its center reaches the middle.
+fn scroll_progress(target: &RenderTarget) -> Option<f64> {
+    let element = target.event_element()?;
+    let viewport = web_sys::window()?.inner_height().ok()?.as_f64()?;
+    let rect = element.get_bounding_client_rect();
+    let distance = (viewport + rect.height()) / 2.0;
+    Some(((viewport - rect.top()) / distance).clamp(0.0, 1.0))
+}
+
+/// The effect's progress through its current iteration. Outside the active phase, where the
+/// effect has no progress unless it fills, the reveal holds at its start or end.
+fn timeline_progress(animation: &Animation) -> Option<f64> {
+    let timing = animation.effect()?.get_computed_timing();
+    if let Some(progress) = timing.get_progress() {
+        return Some(progress);
+    }
+
+    let local_time = timing.get_local_time()?;
+    let delay = timing.get_delay().unwrap_or(0.0);
+    Some(if local_time < delay { 0.0 } else { 1.0 })
+}
+
+/// Advances a running animation by `delta` ms, returning a frame when one is due. This only
+/// reads layout and touches engine state, so the scheduler can compute every frame before
+/// writing any.
+pub(crate) fn compute_frame(state: &Rc<RefCell<AnimationState>>, delta: f64) -> bool {
+    let state = &mut *state.borrow_mut();
+    if !state.running {
+        return false;
+    }
+    state.engine.set_visible_range(state.target.visible_range());
+
+    // Concealing runs the reveal backwards over the same time, whatever drives the reveal
+    if state.concealing.is_some() {
+        state.conceal_by(delta / state.engine.props().max_duration());
+        return true;
+    }
+    if let Some(animation) = &state.timeline {
+        let Some(progress) = timeline_progress(animation) else {
+            return false;
+        };
+        state.engine.seek_into(progress, &mut state.frame);
+        return true;
+    }
+    if state.props.scroll_scrub {
+        if !state.scrub_dirty {
+            return false;
+        }
+        state.scrub_dirty = false;
+        let Some(progress) = scroll_progress(&state.target) else {
+            return false;
+        };
+        state.engine.seek_into(progress, &mut state.frame);
+        return true;
+    }
+    let drawn = state.engine.advance_into(delta, &mut state.frame);
+    if drawn {
+        state.debug_log(|| state.engine.debug_summary());
+    }
+    drawn
+}
+
+/// Draws the next frame of a running animation right away, whatever the time, returning
+/// whether there was one.
+fn step_frame(state: &Rc<RefCell<AnimationState>>) -> bool {
+    {
+        let state = &mut *state.borrow_mut();
+        if !state.running {
+            return false;
+        }
+        if state.concealing.is_some() {
+            state.conceal_by(1.0 / state.engine.props().max_frames() as f64);
+        } else {
+            state.engine.tick_into(&mut state.frame);
+        }
+    }
+    write_frame(state);
+    finish_frame(state);
+    true
+}
+
+/// Writes the computed frame to the DOM.
+pub(crate) fn write_frame(state: &Rc<RefCell<AnimationState>>) {
+    let mut state = state.borrow_mut();
+    state.write_current_frame();
+    #[cfg(feature = "overlay")]
+    if let Some(overlay) = &state.overlay {
+        overlay.update(&state.debug_label(), &state.engine, &state.frame.text);
+    }
+}
+
+/// Runs the callbacks for the written frame, ending the animation once it is complete.
+pub(crate) fn finish_frame(state: &Rc<RefCell<AnimationState>>) {
+    // An earlier callback in the same frame may have stopped this animation
+    let (on_frame, text, frame, on_settle_sound, newly_settled, complete) = {
+        let state = state.borrow();
+        if !state.running {
+            return;
+        }
+        // Only copied out to JS when there is a callback to read it
+        let text = state
+            .on_animation_frame
+            .as_ref()
+            .map(|_| JsValue::from_str(&state.frame.text));
+        let frame = state
+            .on_animation_frame
+            .as_ref()
+            .filter(|callback| callback.length() > 1)
+            .and_then(|_| serde_wasm_bindgen::to_value(&state.frame).ok());
+        let newly_settled = state
+            .on_settle_sound
+            .as_ref()
+            .filter(|_| !state.frame.newly_settled.is_empty())
+            .and_then(|_| serde_wasm_bindgen::to_value(&state.frame.newly_settled).ok());
+        (
+            state.on_animation_frame.clone(),
+            text,
+            frame,
+            state.on_settle_sound.clone(),
+            newly_settled,
+            state.frame.complete,
+        )
+    };
+
+    // Call the frame callback if it exists, with the whole frame if it takes a second argument
+    match (&on_frame, &text, &frame) {
+        (Some(on_frame), Some(text), Some(frame)) => {
+            let _ = on_frame.call2(&JsValue::null(), text, frame);
+        }
+        _ => call_callback(on_frame, text.as_ref()),
+    }
+    if newly_settled.is_some() {
+        call_callback(on_settle_sound, newly_settled.as_ref());
+    }
+
+    // The frame callback may have stopped or restarted the animation already
+    if state.borrow().concealing == Some(0.0) {
+        end_animation(state);
+        return;
+    }
+    if complete {
+        let (on_end, conceal_after, run_end) = {
+            let mut state = state.borrow_mut();
+            if !state.running || !state.engine.is_complete() || state.follows_input() {
+                return;
+            }
+            if state.hover_scrubbing {
+                drop(end_run(&mut state));
+                return;
+            }
+            if let Some(announcer) = &state.announcer {
+                announcer.announce(&state.spoken_text());
+            }
+            if let Some(href) = state.reveal_href.take() {
+                if let Some(element) = state.target.event_element() {
+                    let _ = element.set_attribute("href", &href);
+                }
+            }
+            state.debug_log(|| format!("run complete after {} frames", state.engine.frame_count()));
+            state.completed = true;
+            (
+                state.on_animation_end.clone(),
+                state.props.conceal_after,
+                end_run(&mut state),
+            )
+        };
+        call_callback(on_end, None);
+        run_end.finish();
+        start_waiting(state);
+        if let Some(delay) = conceal_after {
+            match conceal_timer(state, delay) {
+                Ok(timer) => state.borrow_mut().conceal_timer = Some(timer),
+                Err(e) => web_sys::console::error_1(&e.into()),
+            }
+        }
+        start_queued(state);
+    }
+}
+
+/// Starts the next run queued by `then_text()`, unless the end callback already started or
+/// stopped the animation.
+fn start_queued(state: &Rc<RefCell<AnimationState>>) {
+    {
+        let mut state = state.borrow_mut();
+        if state.running || state.disposed {
+            return;
+        }
+        let Some(props) = state.queued.pop_front() else {
+            return;
+        };
+        state.props = props;
+    }
+    if let Err(e) = start_animation(state) {
+        web_sys::console::error_1(&e.into());
+    }
+}
+
+/// `overrides`, a partial props object, on top of `base`.
+pub(crate) fn merge_props(
+    base: &UseScrambleProps,
+    overrides: &JsValue,
+) -> Result<UseScrambleProps, JsError> {
+    if overrides.is_undefined() || overrides.is_null() {
+        return Ok(base.clone());
+    }
+    if base.strict {
+        check_prop_keys(overrides)?;
+    }
+    let merged = serde_wasm_bindgen::to_value(base)?;
+    js_sys::Object::<JsValue>::assign(merged.unchecked_ref(), overrides.unchecked_ref());
+    Ok(serde_wasm_bindgen::from_value(resolve_chance(merged)?)?)
+}
+
+impl AnimationState {
+    /// Props for the engine of a run, damped for reduced motion when that applies, reading
+    /// right to left in RTL elements, landing on the text returned by the `set_transform()`
+    /// callback, if any, and unmasked after `set_masked(false)`.
+    fn run_props(&self, props: &UseScrambleProps) -> UseScrambleProps {
+        let mut props = if props.reduced_motion.applies(prefers_reduced_motion()) {
+            props.clone().with_reduced_motion()
+        } else {
+            props.clone()
+        };
+        // Text in an RTL element reads right to left, even when it starts with a Latin word
+        if props.dir == Direction::Auto && self.target.is_rtl() {
+            props.dir = Direction::Rtl;
+        }
+        let transformed = self
+            .transform
+            .as_ref()
+            .and_then(|transform| {
+                let text = props.transformed_text().into();
+                transform.call1(&JsValue::null(), &text).ok()
+            })
+            .and_then(|text| text.as_string());
+        if let Some(text) = transformed {
+            props.text = text;
+            props.transform = None;
+        }
+        if self.unmasked {
+            props.mask = None;
+        }
+        props
+    }
+
+    /// Text screen readers get for the run: the text as given, or the mask it lands on.
+    fn spoken_text(&self) -> String {
+        let props = self.engine.props();
+        if props.mask.is_some() {
+            props.final_text()
+        } else {
+            self.props.text.clone()
+        }
+    }
+}
+
+/// Jumps a running animation to its final frame and ends it.
+pub(crate) fn complete_animation(state: &Rc<RefCell<AnimationState>>) {
+    {
+        let mut state = state.borrow_mut();
+        if !state.running {
+            return;
+        }
+        state.frame = state.engine.finish();
+    }
+    write_frame(state);
+    finish_frame(state);
+}
+
+/// Stops the animation for good, releasing its listeners and callbacks. Fires `on_dispose`.
+pub(crate) fn dispose_animation(state: &Rc<RefCell<AnimationState>>) {
+    let (trigger_listeners, announcer, conceal_timer, on_dispose, run_end) = {
+        let mut state = state.borrow_mut();
+        if state.disposed {
+            return;
+        }
+        state.debug_log(|| "disposed".into());
+        state.disposed = true;
+        state.on_animation_start = None;
+        state.on_animation_end = None;
+        state.on_animation_frame = None;
+        state.on_settle_sound = None;
+        state.queued.clear();
+        state.preserved_whitespace = None;
+        state.progress_vars = None;
+        #[cfg(feature = "overlay")]
+        {
+            state.overlay = None;
+        }
+        (
+            std::mem::take(&mut state.trigger_listeners),
+            state.announcer.take(),
+            state.conceal_timer.take(),
+            state.on_dispose.take(),
+            end_run(&mut state),
+        )
+    };
+
+    // Remove the listeners outside the borrow, as dropping them releases their closures
+    drop(trigger_listeners);
+    drop(announcer);
+    drop(conceal_timer);
+    run_end.finish();
+    call_callback(on_dispose, None);
+    scheduler::untrack(state);
+    start_waiting(state);
+}
+
+/// Stops the run in response to its abort signal, rejecting its promise with `reason`.
+fn abort_animation(state: &Rc<RefCell<AnimationState>>, reason: &JsValue) {
+    let reject = state
+        .borrow_mut()
+        .completion
+        .take()
+        .map(|(_, reject)| reject);
+    let _ = stop_animation(state);
+    call_callback(reject, Some(reason));
+}
+
+/// Cancels a start scheduled by `start_after()` that is still due.
+pub(crate) fn cancel_delayed_start(state: &RefCell<AnimationState>) {
+    state.borrow_mut().delay = Weak::new();
+}
+
+/// Stops the animation, dropping the runs queued after it.
+pub(crate) fn stop_animation(state: &Rc<RefCell<AnimationState>>) -> Result<(), JsError> {
+    state.borrow_mut().queued.clear();
+    end_animation(state);
+    Ok(())
+}
+
+/// Ends the current run early, firing `on_animation_end`, and starts the next run waiting for
+/// it on the same node.
+fn end_animation(state: &Rc<RefCell<AnimationState>>) {
+    if stop_run(state) {
+        start_waiting(state);
+    }
+}
+
+/// Ends the current run early, firing `on_animation_end`, or drops a start still waiting for
+/// another instance. Returns whether a run ended.
+fn stop_run(state: &Rc<RefCell<AnimationState>>) -> bool {
+    let (on_end, run_end, ended) = {
+        let mut state = state.borrow_mut();
+        if state.hover_scrubbing {
+            // Not a run of its own, so nothing ends with it
+            (None, end_run(&mut state), false)
+        } else if state.running {
+            state.debug_log(|| format!("run stopped: {}", state.engine.debug_summary()));
+            // The scheduler drops the animation on its next frame
+            (state.on_animation_end.clone(), end_run(&mut state), true)
+        } else if state.waiting_on.is_some() {
+            // Never started, so only its promise settles
+            (None, end_run(&mut state), false)
+        } else {
+            return false;
+        }
+    };
+
+    // Call the end callback if it exists
+    call_callback(on_end, None);
+    run_end.finish();
+    ended
+}
+
+/// Another instance's run drawing into the same node as `state`.
+fn running_on_target(state: &Rc<RefCell<AnimationState>>) -> Option<Rc<RefCell<AnimationState>>> {
+    let this = Rc::downgrade(state);
+    TARGETS.with(|targets| {
+        let targets = targets.borrow();
+        let nodes: Vec<&Node> = targets
+            .iter()
+            .filter(|(_, other)| other.ptr_eq(&this))
+            .map(|(node, _)| node)
+            .collect();
+        targets
+            .iter()
+            .filter(|(other, _)| nodes.contains(&other))
+            .filter_map(|(_, other)| other.upgrade())
+            .find(|other| {
+                !Rc::ptr_eq(other, state) && other.try_borrow().is_ok_and(|other| other.running)
+            })
+    })
+}
+
+/// Starts the first run waiting for `ended`'s on the same node. Any others wait for that one in
+/// turn.
+fn start_waiting(ended: &Rc<RefCell<AnimationState>>) {
+    let ended = Rc::downgrade(ended);
+    let mut waiting: Vec<_> = TARGETS.with(|targets| {
+        targets
+            .borrow()
+            .iter()
+            .filter_map(|(_, state)| state.upgrade())
+            .filter(|state| {
+                state.try_borrow().is_ok_and(|state| {
+                    state
+                        .waiting_on
+                        .as_ref()
+                        .is_some_and(|waiting_on| waiting_on.ptr_eq(&ended))
+                })
+            })
+            .collect()
+    });
+    // Instances across several elements are registered once for each
+    waiting.dedup_by(|a, b| Rc::ptr_eq(a, b));
+    let Some((next, rest)) = waiting.split_first() else {
+        return;
+    };
+    for state in rest {
+        state.borrow_mut().waiting_on = Some(Rc::downgrade(next));
+    }
+    next.borrow_mut().waiting_on = None;
+    if let Err(e) = start_run(next, None) {
+        web_sys::console::error_1(&e.into());
+    }
+}
+
+/// A live instance drawing into `node`, forgetting the disposed ones.
+fn instance_on(node: &Node) -> Option<Rc<RefCell<AnimationState>>> {
+    TARGETS.with(|targets| {
+        let mut targets = targets.borrow_mut();
+        targets.retain(|(_, state)| {
+            state
+                .upgrade()
+                .is_some_and(|state| state.try_borrow().map_or(true, |state| !state.disposed))
+        });
+        targets
+            .iter()
+            .filter(|(other, _)| other == node)
+            .find_map(|(_, state)| state.upgrade())
+    })
+}
+
+/// Callback scheduled with `setTimeout`, cancelled if it is dropped before firing.
+pub(crate) struct Timeout {
+    id: i32,
+    _closure: Closure<dyn FnMut()>,
+}
+
+impl Timeout {
+    fn new(delay_ms: f64, closure: Closure<dyn FnMut()>) -> Result<Timeout, JsError> {
+        let window = web_sys::window().ok_or(ScrambleError::NoWindow)?;
+        let id = window
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                closure.as_ref().unchecked_ref(),
+                delay_ms as i32,
+            )
+            .map_err(|_| JsError::new("Failed to start animation timer"))?;
+        Ok(Timeout {
+            id,
+            _closure: closure,
+        })
+    }
+}
+
+impl Drop for Timeout {
+    fn drop(&mut self) {
+        if let Some(window) = web_sys::window() {
+            window.clear_timeout_with_handle(self.id);
+        }
+    }
+}
+
+/// Event listener that is removed from its target when dropped.
+struct Listener {
+    target: EventTarget,
+    event: &'static str,
+    closure: Closure<dyn FnMut(Event)>,
+}
+
+impl Listener {
+    fn new(
+        target: EventTarget,
+        event: &'static str,
+        closure: Closure<dyn FnMut(Event)>,
+    ) -> Result<Listener, JsError> {
+        target
+            .add_event_listener_with_callback(event, closure.as_ref().unchecked_ref())
+            .map_err(|e| JsError::new(&format!("Failed to add event listener: {:?}", e)))?;
+        Ok(Listener {
+            target,
+            event,
+            closure,
+        })
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        let _ = self
+            .target
+            .remove_event_listener_with_callback(self.event, self.closure.as_ref().unchecked_ref());
+    }
+}
+
+/// The descendants of `node` matching `selector` in document order, for the
+/// `content_selector` prop.
+fn content_nodes(node: &Node, selector: &str) -> Result<Vec<Element>, JsError> {
+    let found = if let Some(element) = node.dyn_ref::<Element>() {
+        element.query_selector_all(selector)
+    } else if let Some(fragment) = node.dyn_ref::<DocumentFragment>() {
+        fragment.query_selector_all(selector)
+    } else if let Some(document) = node.dyn_ref::<Document>() {
+        document.query_selector_all(selector)
+    } else {
+        return Err(JsError::new(
+            "content_selector needs an element, fragment or document to search",
+        ));
+    };
+    let found =
+        found.map_err(|e| JsError::new(&format!("Invalid selector {}: {:?}", selector, e)))?;
+    let elements: Vec<Element> = (0..found.length())
+        .filter_map(|i| found.item(i))
+        .filter_map(|node| node.dyn_into().ok())
+        .collect();
+    if elements.is_empty() {
+        return Err(JsError::new(&format!(
+            "No element matches selector {}",
+            selector
+        )));
+    }
+    Ok(elements)
+}
+
+/// The first element of the document matching a CSS selector.
+pub(crate) fn query_element(selector: &str) -> Result<Element, JsError> {
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or(ScrambleError::NoDocument)?;
+    document
+        .query_selector(selector)
+        .map_err(|e| JsError::new(&format!("Invalid selector {}: {:?}", selector, e)))?
+        .ok_or_else(|| JsError::new(&format!("No element matches selector {}", selector)))
+}
+
+/// Where an instance is in its lifecycle, as reported by its `state` getter.
+///
+/// An instance starts `Idle`. `start()` moves it to `Playing` from any state but `Disposed`,
+/// restarting a run already in progress rather than animating twice. A run is `Paused` while
+/// the page holds it or its speed is 0, and ends `Completed` once it lands on the text, or
+/// `Idle` when it is stopped. `Delayed` covers a start staggered by a group or timeline that
+/// is still due, and one waiting for another instance's run on the same node. `dispose()` is
+/// final.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PlayState {
+    Idle,
+    Delayed,
+    Playing,
+    Paused,
+    Completed,
+    Disposed,
+}
+
+impl PlayState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PlayState::Idle => "idle",
+            PlayState::Delayed => "delayed",
+            PlayState::Playing => "playing",
+            PlayState::Paused => "paused",
+            PlayState::Completed => "completed",
+            PlayState::Disposed => "disposed",
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub struct ScrambleText {
+    state: Rc<RefCell<AnimationState>>,
+}
+
+#[wasm_bindgen]
+impl ScrambleText {
+    #[wasm_bindgen(constructor)]
+    /// Creates an instance drawing into `target`, which may be an element or any other node,
+    /// such as a single text node inside a larger element. SVG `<text>` elements keep their
+    /// `<tspan>`s, each receiving its share of the frame.
+    pub fn new(
+        target: Node,
+        #[wasm_bindgen(unchecked_param_type = "ScrambleProps | ScrambleOptions")] props: JsValue,
+    ) -> Result<ScrambleText, JsError> {
+        let props = props_from_js(props)?;
+        ScrambleText::with_props(target, props)
+    }
+
+    /// Creates an instance for the first element matching a CSS selector.
+    pub fn from_selector(
+        selector: &str,
+        #[wasm_bindgen(unchecked_param_type = "ScrambleProps | ScrambleOptions")] props: JsValue,
+    ) -> Result<ScrambleText, JsError> {
+        ScrambleText::new(query_element(selector)?.into(), props)
+    }
+
+    /// Creates an instance drawing one text across several elements, such as the lines or
+    /// words from a text splitter. Each element shows as many characters as it held when the
+    /// instance was created, in order, and the last one takes whatever remains.
+    pub fn across(
+        elements: Vec<Element>,
+        #[wasm_bindgen(unchecked_param_type = "ScrambleProps | ScrambleOptions")] props: JsValue,
+    ) -> Result<ScrambleText, JsError> {
+        let props = props_from_js(props)?;
+        ScrambleText::across_with_props(elements, props)
+    }
+
+    /// Takes over a server-rendered element whose content is a scrambled frame, such as the
+    /// one from `initial_frame`. The final text is read from its `data-scramble-final`
+    /// attribute and the props from `data-scramble-*` attributes. The animation resumes from
+    /// the frame on the page, unless `data-scramble-play-on-mount` is `false`.
+    pub fn hydrate(element: Element) -> Result<ScrambleText, JsError> {
+        let text = element
+            .get_attribute("data-scramble-final")
+            .ok_or_else(|| JsError::new("Element has no data-scramble-final attribute"))?;
+        let props = props_from_attributes(&element, "data-scramble-", text)?;
+        let play_on_mount = props.plays_on_mount();
+        let frame = element.text_content().unwrap_or_default();
+
+        let mut scramble = ScrambleText::with_props(element.into(), props)?;
+        scramble.resume_from(frame);
+        if play_on_mount {
+            scramble.start()?;
+        }
+        Ok(scramble)
+    }
+
+    #[wasm_bindgen]
+    pub fn set_on_animation_start(&mut self, callback: js_sys::Function) {
+        self.state.borrow_mut().on_animation_start = Some(callback);
+    }
+
+    /// Called once a run ends, whether it completes on its own or is stopped. Runs that
+    /// follow scrolling or a scroll timeline end only when stopped.
+    #[wasm_bindgen]
+    pub fn set_on_animation_end(&mut self, callback: js_sys::Function) {
+        self.state.borrow_mut().on_animation_end = Some(callback);
+    }
+
+    /// Called with the text of every frame. A callback taking a second argument also gets the
+    /// frame, with the positions that settled or drew a new random character in it.
+    #[wasm_bindgen]
+    pub fn set_on_animation_frame(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "(text: string, frame: ScrambleFrame) => void")]
+        callback: js_sys::Function,
+    ) {
+        self.state.borrow_mut().on_animation_frame = Some(callback);
+    }
+
+    /// Called with the positions that landed on their final character, in each frame where
+    /// any did, e.g. to play a tick per character in time with the reveal.
+    #[wasm_bindgen]
+    pub fn set_on_settle_sound(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "(indices: number[]) => void")]
+        callback: js_sys::Function,
+    ) {
+        self.state.borrow_mut().on_settle_sound = Some(callback);
+    }
+
+    /// Called once the instance is disposed, either by `dispose()` or because its target was
+    /// removed from the document while animating.
+    #[wasm_bindgen]
+    pub fn set_on_dispose(&mut self, callback: js_sys::Function) {
+        self.state.borrow_mut().on_dispose = Some(callback);
+    }
+
+    /// Adds `plugin`, called with the text of every frame and the state of each position
+    /// (the scrambles left, the character showing, or null where nothing is drawn yet) before
+    /// the frame is written. A string it returns is written instead, e.g. with color tags or
+    /// zalgo marks added. Plugins run in the order they are added, for every run, and must not
+    /// call back into the instance.
+    #[wasm_bindgen]
+    pub fn add_plugin(
+        &mut self,
+        #[wasm_bindgen(
+            unchecked_param_type = "(text: string, states: (number | string | null)[]) => string | void"
+        )]
+        plugin: js_sys::Function,
+    ) {
+        self.add_frame_plugin(JsPlugin(plugin));
+    }
+
+    /// Makes runs land on `callback(text)` rather than the text, after the `transform` prop if
+    /// both are set. The `text` getter, the props and announcements keep the original text.
+    /// Applies from the next run.
+    #[wasm_bindgen]
+    pub fn set_transform(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "(text: string) => string")]
+        callback: js_sys::Function,
+    ) {
+        self.state.borrow_mut().transform = Some(callback);
+    }
+
+    /// Starts the animation. The returned promise resolves once the run ends, whether it
+    /// completes or is stopped, and rejects with the signal's reason if `signal` aborts it.
+    #[wasm_bindgen(js_name = start)]
+    pub fn start_js(&mut self, signal: Option<AbortSignal>) -> Result<Promise, JsError> {
+        if let Some(signal) = signal.as_ref().filter(|signal| signal.aborted()) {
+            return Ok(Promise::reject(&signal.reason()));
+        }
+
+        start_animation(&self.state)?;
+        self.completion(signal)
+    }
+
+    pub fn stop(&mut self) -> Result<(), JsError> {
+        stop_animation(&self.state)
+    }
+
+    /// Whether the text shows as its `mask`.
+    #[wasm_bindgen(getter)]
+    pub fn masked(&self) -> bool {
+        let state = self.state.borrow();
+        state.props.mask.is_some() && !state.unmasked
+    }
+
+    /// Shows the text itself in place of its `mask`, or the mask again, e.g. once a password
+    /// has been "decrypted". A running animation jumps to its end first, and later runs land
+    /// on the text as shown.
+    pub fn set_masked(&mut self, masked: bool) -> Result<(), JsError> {
+        complete_animation(&self.state);
+        let state = &mut *self.state.borrow_mut();
+        if state.disposed {
+            return Err(ScrambleError::Disposed.into());
+        }
+        state.unmasked = !masked;
+        if state.props.mask.is_none() {
+            return Ok(());
+        }
+        let props = state.run_props(&state.props);
+        state.engine.set_props(props)?;
+        state.frame = state.engine.finish();
+        state.write_current_frame();
+        Ok(())
+    }
+
+    /// Scrambles the text back, running the reveal in reverse from where it has got to. Does
+    /// nothing when the text is already concealed.
+    pub fn conceal(&mut self) -> Result<(), JsError> {
+        conceal_animation(&self.state)
+    }
+
+    /// Queues a follow-up animation revealing `text` on the same target once the current run
+    /// completes, or starts it right away when idle. `props_override` may change any props
+    /// for this run, on top of those of the run before it; props that set up the instance,
+    /// such as `target` and `triggers`, keep their initial values. Stopping the animation
+    /// drops the queue.
+    pub fn then_text(
+        &mut self,
+        text: String,
+        #[wasm_bindgen(unchecked_optional_param_type = "Partial<ScrambleProps>")]
+        props_override: JsValue,
+    ) -> Result<(), JsError> {
+        let props = {
+            let state = self.state.borrow();
+            let base = state.queued.back().unwrap_or(&state.props);
+            UseScrambleProps {
+                text,
+                ..merge_props(base, &props_override)?
+            }
+        };
+        self.then_props(props)
+    }
+
+    /// Merges `props`, a partial props object, into the current props without recreating the
+    /// instance. A running animation picks up changes such as `speed`, `chance` or `range` on
+    /// its next frame, and restarts for a new `text`. `target`, `triggers` and the
+    /// accessibility props keep their initial values, and `scroll_scrub` applies from the
+    /// next run.
+    pub fn update_props(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "Partial<ScrambleProps>")] props: JsValue,
+    ) -> Result<(), JsError> {
+        let props = merge_props(&self.state.borrow().props, &props)?;
+        self.set_props(props)
+    }
+
+    /// Text the animation reveals.
+    #[wasm_bindgen(getter)]
+    pub fn text(&self) -> String {
+        self.state.borrow().props.text.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn speed(&self) -> f32 {
+        self.state.borrow().props.speed
+    }
+
+    /// Whether a run is in progress, including one held by a pause. Characters rescrambled by
+    /// `hover_scrub` settling back do not count.
+    #[wasm_bindgen(getter)]
+    pub fn is_playing(&self) -> bool {
+        let state = self.state.borrow();
+        state.running && !state.hover_scrubbing
+    }
+
+    /// Where the instance is in its lifecycle, like `play_state()`.
+    #[wasm_bindgen(getter = state, unchecked_return_type = "ScramblePlayState")]
+    pub fn play_state_js(&self) -> String {
+        self.play_state().as_str().to_string()
+    }
+
+    /// Share of the text showing its final characters (0-1), as of the last drawn frame.
+    #[wasm_bindgen(getter)]
+    pub fn progress(&self) -> f64 {
+        self.state.borrow().engine.progress()
+    }
+
+    /// Number of frames drawn by the current or last run.
+    #[wasm_bindgen(getter)]
+    pub fn frame_count(&self) -> u32 {
+        self.state.borrow().engine.frame_count()
+    }
+
+    /// The props in effect, with defaults filled in.
+    #[wasm_bindgen(getter, unchecked_return_type = "ResolvedScrambleProps")]
+    pub fn props(&self) -> Result<JsValue, JsError> {
+        Ok(serde_wasm_bindgen::to_value(&self.state.borrow().props)?)
+    }
+
+    /// Changes the speed (0-1). A running animation redraws at the new rate from its next
+    /// frame, and holds its current frame at 0.
+    pub fn set_speed(&mut self, speed: f32) -> Result<(), JsError> {
+        self.change_props(|props| props.speed = speed)
+    }
+
+    /// Changes the chance (0-1) of scrambling a character, from the next frame.
+    pub fn set_chance(&mut self, chance: f32) -> Result<(), JsError> {
+        self.change_props(|props| props.chance = chance.into())
+    }
+
+    /// Changes the range of code points scrambled characters are drawn from to `min`-`max`,
+    /// from the next frame.
+    pub fn set_range(&mut self, min: u32, max: u32) -> Result<(), JsError> {
+        self.change_props(|props| props.range = RangeOrCharCodes::Range(min, max))
+    }
+
+    /// Drives the reveal from a Web Animations `Animation` instead of time. Every frame samples
+    /// the animation's progress, so the scramble follows its playback, sequencing and
+    /// scroll-driven timelines. The binding lasts until `stop()`.
+    pub fn bind_to_timeline(&mut self, animation: Animation) -> Result<(), JsError> {
+        start_run(&self.state, Some(animation))
+    }
+
+    /// Draws the next `n` frames of the running animation synchronously, without waiting for
+    /// the browser, and returns the text of the last frame drawn. Callbacks fire for every
+    /// frame, and drawing stops early once the run completes. Lets tests and stories assert
+    /// on intermediate frames, e.g. `scramble.start(); scramble.run_frames(5)`.
+    pub fn run_frames(&mut self, n: u32) -> String {
+        for _ in 0..n {
+            if !step_frame(&self.state) {
+                break;
+            }
+        }
+        self.state.borrow().frame.text.clone()
+    }
+
+    /// Draws the frame `progress` (0-1) of the way through the reveal, without starting a run.
+    pub fn seek(&mut self, progress: f64) -> Result<(), JsError> {
+        let state = &mut *self.state.borrow_mut();
+        if state.disposed {
+            return Err(ScrambleError::Disposed.into());
+        }
+        state.engine.seek_into(progress, &mut state.frame);
+        state.write_current_frame();
+        Ok(())
+    }
+
+    /// Snapshot of the current or last run, as a plain object that `import_state()` resumes
+    /// from, e.g. to restore an animation after a route change or a back/forward cache
+    /// restore.
+    #[wasm_bindgen(unchecked_return_type = "ScrambleState")]
+    pub fn export_state(&self) -> Result<JsValue, JsError> {
+        Ok(serde_wasm_bindgen::to_value(
+            &self.state.borrow().engine.export_state(),
+        )?)
+    }
+
+    /// Shows the frame a snapshot from `export_state()` was taken at. A running animation
+    /// carries on from there, and otherwise the next `start()` does. The snapshot must be of a
+    /// run revealing the same text.
+    pub fn import_state(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "ScrambleState")] state: JsValue,
+    ) -> Result<(), JsError> {
+        let state: EngineState = serde_wasm_bindgen::from_value(state)?;
+        let running = {
+            let animation = &mut *self.state.borrow_mut();
+            if animation.disposed {
+                return Err(ScrambleError::Disposed.into());
+            }
+            animation.frame = animation.engine.import_state(&state)?;
+            animation.write_current_frame();
+            animation.resume_state = Some(state);
+            animation.running
+        };
+        if running {
+            start_animation(&self.state)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Stops the animation and releases its event listeners and callbacks right away, instead
+    /// of whenever the JS wrapper is garbage collected. The instance can't be started again.
+    pub fn dispose(&mut self) {
+        dispose_animation(&self.state);
+    }
+
+    /// Alias of `dispose`.
+    pub fn destroy(&mut self) {
+        self.dispose();
+    }
+}
+
+/// Hooks making runs reproducible in tests, with the `test-hooks` feature.
+#[cfg(feature = "test-hooks")]
+#[wasm_bindgen]
+impl ScrambleText {
+    /// Takes the instance off the browser's frames. From then on it only moves on when
+    /// `advance_clock()` or `step_frame()` is called.
+    pub fn use_fake_clock(&mut self) {
+        self.state.borrow_mut().test_hooks.fake_clock = true;
+    }
+
+    /// Moves the fake clock on by `ms`, drawing the frame due by then, if any, like a browser
+    /// frame would. Returns whether a frame was drawn.
+    pub fn advance_clock(&mut self, ms: f64) -> bool {
+        let drawn = compute_frame(&self.state, ms);
+        if drawn {
+            write_frame(&self.state);
+            finish_frame(&self.state);
+        }
+        drawn
+    }
+
+    /// Draws exactly one frame of the running animation, synchronously, and returns the text
+    /// of the last frame drawn.
+    pub fn step_frame(&mut self) -> String {
+        self.run_frames(1)
+    }
+
+    /// Seeds the noise of every run from the next one, so that runs of instances seeded alike
+    /// draw the same frames.
+    pub fn seed_random(&mut self, seed: u32) {
+        self.state.borrow_mut().test_hooks.random_seed = Some(seed.into());
+    }
+}
+
+impl ScrambleText {
+    /// Starts the animation, restarting it if it is already running.
+    pub fn start(&mut self) -> Result<(), JsError> {
+        start_animation(&self.state)
+    }
+
+    /// Scrambles the characters under the pointer again as it moves, for `hover_scrub`.
+    fn follow_pointer(&self) -> Result<(), JsError> {
+        let element = self
+            .element()
+            .ok_or_else(|| JsError::new("hover_scrub needs an element to draw into"))?;
+        let weak_state = Rc::downgrade(&self.state);
+        let closure = Closure::<dyn FnMut(Event)>::new(move |event: Event| {
+            let Some(state) = weak_state.upgrade() else {
+                return;
+            };
+            let Some(node) = event
+                .target()
+                .and_then(|target| target.dyn_into::<Node>().ok())
+            else {
+                return;
+            };
+            if let Err(e) = hover_scrub(&state, &node) {
+                web_sys::console::error_1(&e.into());
+            }
+        });
+        let listener = Listener::new(element.into(), "pointermove", closure)?;
+        self.state.borrow_mut().trigger_listeners.push(listener);
+        Ok(())
+    }
+
+    /// Makes the element a spoiler: a toggle button showing scrambled text, revealed by
+    /// activating it and concealed again when activated, left or blurred.
+    fn spoil(&self) -> Result<(), JsError> {
+        let element = self
+            .element()
+            .ok_or_else(|| JsError::new("Spoilers need an element, or a text node inside one"))?;
+        let aria_error = |e| JsError::new(&format!("Failed to set up the spoiler: {:?}", e));
+        element
+            .set_attribute("role", "button")
+            .map_err(aria_error)?;
+        if !element.has_attribute("tabindex") {
+            element.set_attribute("tabindex", "0").map_err(aria_error)?;
+        }
+
+        for event in ["click", "keydown", "mouseleave", "focusout"] {
+            let weak_state = Rc::downgrade(&self.state);
+            let closure = Closure::wrap(Box::new(move |e: Event| {
+                if event == "keydown" {
+                    let activates = e
+                        .dyn_ref::<KeyboardEvent>()
+                        .is_some_and(|e| matches!(e.key().as_str(), "Enter" | " "));
+                    if !activates {
+                        return;
+                    }
+                    // Space would scroll the page as well
+                    e.prevent_default();
+                }
+                let Some(state) = weak_state.upgrade() else {
+                    return;
+                };
+
+                let revealed = state.borrow().revealed;
+                let result = if revealed {
+                    conceal_animation(&state)
+                } else if matches!(event, "click" | "keydown") {
+                    start_animation(&state)
+                } else {
+                    Ok(())
+                };
+                if let Err(e) = result {
+                    web_sys::console::error_1(&e.into());
+                }
+            }) as Box<dyn FnMut(Event)>);
+
+            let listener = Listener::new(element.clone().into(), event, closure)?;
+            self.state.borrow_mut().trigger_listeners.push(listener);
+        }
+
+        // Scrambled from the start
+        let state = &mut *self.state.borrow_mut();
+        state.engine.seek_into(0.0, &mut state.frame);
+        state.write_current_frame();
+        state.sync_pressed();
+        Ok(())
+    }
+
+    /// Promise settling once the current run ends: resolved whether it completes or is
+    /// stopped, and rejected with the signal's reason if `signal` aborts it. Resolved right
+    /// away when nothing is running.
+    pub(crate) fn completion(&self, signal: Option<AbortSignal>) -> Result<Promise, JsError> {
+        let pending = {
+            let state = self.state.borrow();
+            state.running || state.waiting_on.is_some()
+        };
+        if !pending {
+            return Ok(Promise::resolve(&JsValue::undefined()));
+        }
+
+        let mut completion = None;
+        let promise = Promise::new(&mut |resolve, reject| completion = Some((resolve, reject)));
+        let abort_listener = match signal {
+            Some(signal) => {
+                let weak_state = Rc::downgrade(&self.state);
+                let reason_signal = signal.clone();
+                let closure = Closure::<dyn FnMut(Event)>::new(move |_event: Event| {
+                    if let Some(state) = weak_state.upgrade() {
+                        abort_animation(&state, &reason_signal.reason());
+                    }
+                });
+                Some(Listener::new(signal.into(), "abort", closure)?)
+            }
+            None => None,
+        };
+
+        let mut state = self.state.borrow_mut();
+        state.completion = completion;
+        state.abort_listener = abort_listener;
+        Ok(promise)
+    }
+
+    /// Adds a Rust `plugin`, like `add_plugin()`.
+    pub fn add_frame_plugin(&self, plugin: impl FramePlugin + 'static) {
+        self.state.borrow_mut().engine.add_plugin(plugin);
+    }
+
+    /// Sets `href` on the target once the next run completes.
+    pub(crate) fn link_on_reveal(&self, href: String) {
+        self.state.borrow_mut().reveal_href = Some(href);
+    }
+
+    /// Where the instance is in its lifecycle.
+    pub fn play_state(&self) -> PlayState {
+        // Asked before borrowing the state, which the scheduler borrows as well
+        let held = scheduler::is_held(&self.state);
+        let state = self.state.borrow();
+        if state.disposed {
+            PlayState::Disposed
+        } else if state.running && !state.hover_scrubbing {
+            let stalled = state.props.speed == 0.0 && !state.follows_input();
+            if held || stalled {
+                PlayState::Paused
+            } else {
+                PlayState::Playing
+            }
+        } else if state.delay.strong_count() > 0 || state.waiting_on.is_some() {
+            PlayState::Delayed
+        } else if state.completed {
+            PlayState::Completed
+        } else {
+            PlayState::Idle
+        }
+    }
+
+    /// Makes the next run resume from `frame` showing instead of starting over, counting the
+    /// leading characters that match the text as revealed.
+    pub(crate) fn resume_from(&self, frame: String) {
+        self.state.borrow_mut().resume_frame = Some(frame);
+    }
+
+    /// Starts the animation after `delay_ms`, unless the returned handle is dropped first.
+    pub(crate) fn start_after(&self, delay_ms: f64) -> Result<Timeout, JsError> {
+        let weak_state = Rc::downgrade(&self.state);
+        // Reports the instance as delayed until the timer fires or is cancelled
+        let delay = Rc::new(());
+        self.state.borrow_mut().delay = Rc::downgrade(&delay);
+        let mut delay = Some(delay);
+        let closure = Closure::<dyn FnMut()>::new(move || {
+            let Some(delay) = delay.take() else {
+                return;
+            };
+            if let Some(state) = weak_state.upgrade() {
+                // `stop_all()` cancels the start by forgetting it
+                let due = state.borrow().delay.ptr_eq(&Rc::downgrade(&delay));
+                drop(delay);
+                if due {
+                    if let Err(e) = start_animation(&state) {
+                        web_sys::console::error_1(&e.into());
+                    }
+                }
+            }
+        });
+        Timeout::new(delay_ms, closure)
+    }
+
+    /// The element the animation draws into, or the parent of a target text node.
+    pub(crate) fn element(&self) -> Option<Element> {
+        self.state.borrow().target.event_element()
+    }
+
+    /// Replaces the props with already deserialized ones, like `update_props()`.
+    pub fn set_props(&mut self, props: UseScrambleProps) -> Result<(), JsError> {
+        if let Err(e) = props.validate() {
+            self.state
+                .borrow()
+                .debug_log(|| format!("props rejected: {}", e));
+            return Err(e.into());
+        }
+        let restart = {
+            let mut state = self.state.borrow_mut();
+            if state.disposed {
+                return Err(ScrambleError::Disposed.into());
+            }
+            let restart = state.running
+                && !state.hover_scrubbing
+                && (state.props.text != props.text
+                    || state.props.transform != props.transform
+                    || state.props.mask != props.mask);
+            let engine_props = state.run_props(&props);
+            state.engine.set_props(engine_props)?;
+            state.props = props;
+            state.sync_whitespace();
+            state.sync_progress_vars();
+            #[cfg(feature = "overlay")]
+            state.sync_overlay()?;
+            restart
+        };
+
+        if restart {
+            start_animation(&self.state)
+        } else if self.state.borrow().running {
+            // Frames may be due again, e.g. after speeding up from 0
+            scheduler::register(&self.state)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Applies `change` to a copy of the current props, then sets them like `set_props()`.
+    pub(crate) fn change_props(
+        &mut self,
+        change: impl FnOnce(&mut UseScrambleProps),
+    ) -> Result<(), JsError> {
+        let mut props = self.state.borrow().props.clone();
+        change(&mut props);
+        self.set_props(props)
+    }
+
+    /// Queues a follow-up run with already deserialized props, like `then_text()`.
+    pub fn then_props(&mut self, props: UseScrambleProps) -> Result<(), JsError> {
+        props.validate()?;
+        let idle = {
+            let mut state = self.state.borrow_mut();
+            if state.disposed {
+                return Err(ScrambleError::Disposed.into());
+            }
+            state.queued.push_back(props);
+            !state.running
+        };
+        if idle {
+            start_queued(&self.state);
+        }
+        Ok(())
+    }
+
+    /// Creates an instance from already deserialized props.
+    pub fn with_props(target: Node, props: UseScrambleProps) -> Result<ScrambleText, JsError> {
+        let target = match &props.content_selector {
+            Some(selector) => {
+                let mut elements = content_nodes(&target, selector)?;
+                if elements.len() > 1 {
+                    // Several matches share the text like `across()`
+                    return ScrambleText::across_with_props(elements, props);
+                }
+                Node::from(elements.remove(0))
+            }
+            None => target,
+        };
+        let render_target = RenderTarget::new(target.clone(), &props.target)?;
+        ScrambleText::with_render_target(vec![target], render_target, props)
+    }
+
+    /// Creates an instance drawing across `elements`, like `across()`, from already
+    /// deserialized props.
+    pub fn across_with_props(
+        elements: Vec<Element>,
+        props: UseScrambleProps,
+    ) -> Result<ScrambleText, JsError> {
+        let nodes = elements.iter().cloned().map(Node::from).collect();
+        let render_target = RenderTarget::distributed(elements, &props.target)?;
+        ScrambleText::with_render_target(nodes, render_target, props)
+    }
+
+    /// Creates an instance drawing into `target`, registered as running on `nodes`.
+    fn with_render_target(
+        nodes: Vec<Node>,
+        mut target: RenderTarget,
+        props: UseScrambleProps,
+    ) -> Result<ScrambleText, JsError> {
+        if let Some(existing) = nodes.iter().find_map(instance_on) {
+            match props.shared_target {
+                SharedTarget::Queue => {}
+                SharedTarget::Error => return Err(ScrambleError::TargetInUse.into()),
+                SharedTarget::Adopt => {
+                    let mut instance = ScrambleText { state: existing };
+                    instance.set_props(props)?;
+                    return Ok(instance);
+                }
+            }
+        }
+
+        let mut triggers = props.triggers.clone();
+        if props.hover_replay && !triggers.contains(&Trigger::Hover) {
+            triggers.push(Trigger::Hover);
+        }
+        if props.virtualize {
+            target = target.virtualize()?;
+        }
+        if props.spans || props.hover_scrub {
+            target = target.spans()?;
+        }
+        let engine = ScrambleEngine::new(props.clone())?;
+        let event_element = target.event_element();
+        let spoiler = props.spoiler;
+        let hover_scrub = props.hover_scrub;
+
+        // Either the target announces its frames, or a separate region announces the final
+        // text once
+        let mut announcer = None;
+        if let Some(element) = &event_element {
+            let aria_error = |e| JsError::new(&format!("Failed to set up aria-live: {:?}", e));
+            if props.announce_on_complete {
+                let politeness = props.aria_live.unwrap_or(AriaLive::Polite);
+                announcer = Some(Announcer::new(element, politeness).map_err(aria_error)?);
+            } else if let Some(aria_live) = props.aria_live {
+                element
+                    .set_attribute("aria-live", aria_live.as_str())
+                    .map_err(aria_error)?;
+            }
+        }
+
+        let instance = ScrambleText {
+            state: Rc::new(RefCell::new(AnimationState {
+                target,
+                props,
+                engine,
+                running: false,
+                completed: false,
+                delay: Weak::new(),
+                waiting_on: None,
+                connected: false,
+                scrub_dirty: false,
+                scrub_listeners: Vec::new(),
+                timeline: None,
+                queued: VecDeque::new(),
+                resume_frame: None,
+                resume_state: None,
+                frame: Frame::default(),
+                written: None,
+                disposed: false,
+                trigger_listeners: Vec::new(),
+                completion: None,
+                abort_listener: None,
+                stable_label: None,
+                announcer,
+                #[cfg(feature = "overlay")]
+                overlay: None,
+                preserved_whitespace: None,
+                progress_vars: None,
+                #[cfg(feature = "test-hooks")]
+                test_hooks: TestHooks::default(),
+                on_animation_start: None,
+                on_animation_end: None,
+                on_animation_frame: None,
+                on_settle_sound: None,
+                on_dispose: None,
+                transform: None,
+                reveal_href: None,
+                concealing: None,
+                hover_scrubbing: false,
+                revealed: !spoiler,
+                conceal_timer: None,
+                unmasked: false,
+            })),
+        };
+        let state = instance.state.borrow();
+        state.debug_log(|| {
+            let props = &state.props;
+            format!(
+                "created with valid props: speed {}, tick {}, step {}, chance {}, seed {}, scramble {}",
+                props.speed, props.tick, props.step, props.chance, props.seed, props.scramble
+            )
+        });
+        drop(state);
+        instance.state.borrow_mut().sync_whitespace();
+        instance.state.borrow_mut().sync_progress_vars();
+        #[cfg(feature = "overlay")]
+        instance.state.borrow_mut().sync_overlay()?;
+
+        if !triggers.is_empty() {
+            let element = event_element.ok_or_else(|| {
+                JsError::new("Replay triggers need an element, or a text node inside one")
+            })?;
+
+            for trigger in triggers {
+                let weak_state = Rc::downgrade(&instance.state);
+                let closure = Closure::wrap(Box::new(move |event: Event| {
+                    // Keyboard replay is limited to the keys that activate controls
+                    if trigger == Trigger::Keydown
+                        && !event
+                            .dyn_ref::<KeyboardEvent>()
+                            .is_some_and(|event| matches!(event.key().as_str(), "Enter" | " "))
+                    {
+                        return;
+                    }
+
+                    if let Some(state) = weak_state.upgrade() {
+                        if !state.borrow().running {
+                            let _ = start_animation(&state);
+                        }
+                    }
+                }) as Box<dyn FnMut(Event)>);
+
+                let listener = Listener::new(element.clone().into(), trigger.event(), closure)?;
+                instance.state.borrow_mut().trigger_listeners.push(listener);
+            }
+        }
+
+        TARGETS.with(|targets| {
+            let mut targets = targets.borrow_mut();
+            for node in nodes {
+                targets.push((node, Rc::downgrade(&instance.state)));
+            }
+        });
+        scheduler::track(&instance.state);
+        if spoiler {
+            instance.spoil()?;
+        }
+        if hover_scrub {
+            instance.follow_pointer()?;
+        }
+
+        Ok(instance)
+    }
+}
+
+impl Drop for ScrambleText {
+    fn drop(&mut self) {
+        // Handles adopting an animation share it, and the last one stops it
+        if Rc::strong_count(&self.state) == 1 {
+            let _ = self.stop();
+        }
+    }
+}
@@ -0,0 +1,468 @@
+//! Runs scramble engines in a Web Worker, so the main thread only applies finished frames.
+//!
+//! The main thread creates a [`ScrambleWorker`], which spawns a module worker loading this same
+//! wasm bundle. Text targets receive frame strings posted back from the worker; canvases are
+//! transferred to the worker as an `OffscreenCanvas` and drawn there directly.
+
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{
+    DedicatedWorkerGlobalScope, HtmlCanvasElement, MessageEvent, Node, OffscreenCanvas,
+    OffscreenCanvasRenderingContext2d, Worker,
+};
+
+use crate::clock::Clock;
+use crate::engine::ScrambleEngine;
+use crate::options::props_from_js;
+use crate::render::RenderTarget;
+use crate::scramble::UseScrambleProps;
+
+#[wasm_bindgen(inline_js = r#"
+export function spawnScrambleWorker(moduleUrl) {
+  const url = new URL(moduleUrl, location.href).href;
+  // Messages that arrive while the wasm module is still loading are replayed afterwards
+  const source = `
+    import init, { run_scramble_worker } from ${JSON.stringify(url)};
+    const pending = [];
+    self.onmessage = (event) => pending.push(event);
+    init().then(() => run_scramble_worker(pending));
+  `;
+  const blobUrl = URL.createObjectURL(new Blob([source], { type: 'text/javascript' }));
+  const worker = new Worker(blobUrl, { type: 'module' });
+  URL.revokeObjectURL(blobUrl);
+  return worker;
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = spawnScrambleWorker, catch)]
+    fn spawn_scramble_worker(module_url: &str) -> Result<Worker, JsValue>;
+}
+
+/// Messages exchanged between the main thread and the worker. A canvas, when present, is
+/// attached to `Start` as a separate transferable `canvas` field.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum WorkerMessage {
+    Start {
+        id: u32,
+        props: Box<UseScrambleProps>,
+        font: Option<String>,
+        fill_style: Option<String>,
+    },
+    Stop {
+        id: u32,
+    },
+    Frame {
+        id: u32,
+        text: String,
+        #[serde(default)]
+        offsets: Vec<usize>,
+        complete: bool,
+    },
+    /// A canvas animation, whose frames stay in the worker, finished
+    Ended {
+        id: u32,
+    },
+}
+
+fn post_message(
+    post: impl FnOnce(&JsValue) -> Result<(), JsValue>,
+    message: &WorkerMessage,
+) -> Result<(), JsError> {
+    let message = serde_wasm_bindgen::to_value(message)?;
+    post(&message).map_err(|e| JsError::new(&format!("Failed to post message: {:?}", e)))
+}
+
+/// Where a [`ScrambleWorker`] sends its messages.
+enum Port {
+    Worker {
+        worker: Worker,
+        _on_message: Closure<dyn FnMut(MessageEvent)>,
+    },
+    /// Jobs run on this thread, ticked by hand, with the `test-hooks` feature.
+    #[cfg(feature = "test-hooks")]
+    Local(WorkerJobs),
+}
+
+/// Main-thread handle to a worker running scramble animations.
+#[wasm_bindgen]
+pub struct ScrambleWorker {
+    port: Port,
+    next_id: u32,
+    targets: Rc<RefCell<HashMap<u32, RenderTarget>>>,
+    on_animation_end: Rc<RefCell<Option<js_sys::Function>>>,
+}
+
+/// Applies a message posted back by the worker: writes frames to their target, and reports
+/// animations that finished.
+fn deliver(
+    targets: &RefCell<HashMap<u32, RenderTarget>>,
+    on_animation_end: &RefCell<Option<js_sys::Function>>,
+    data: JsValue,
+) {
+    let (id, complete) = match serde_wasm_bindgen::from_value(data) {
+        Ok(WorkerMessage::Frame {
+            id,
+            text,
+            offsets,
+            complete,
+        }) => {
+            if let Some(target) = targets.borrow().get(&id) {
+                target.write_split(&text, &offsets);
+            }
+            (id, complete)
+        }
+        Ok(WorkerMessage::Ended { id }) => (id, true),
+        _ => return,
+    };
+    if complete {
+        targets.borrow_mut().remove(&id);
+        if let Some(callback) = on_animation_end.borrow().as_ref() {
+            let _ = callback.call1(&JsValue::null(), &id.into());
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl ScrambleWorker {
+    /// Spawns a worker loading the wasm-pack generated module at `module_url`
+    /// (e.g. `new URL('./pkg/scramble_text.js', import.meta.url)`).
+    #[wasm_bindgen(constructor)]
+    pub fn new(module_url: &str) -> Result<ScrambleWorker, JsError> {
+        let worker = spawn_scramble_worker(module_url)
+            .map_err(|e| JsError::new(&format!("Failed to spawn worker: {:?}", e)))?;
+        let targets: Rc<RefCell<HashMap<u32, RenderTarget>>> = Rc::default();
+        let on_animation_end: Rc<RefCell<Option<js_sys::Function>>> = Rc::default();
+
+        let on_message = {
+            let targets = targets.clone();
+            let on_animation_end = on_animation_end.clone();
+            Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+                deliver(&targets, &on_animation_end, event.data());
+            })
+        };
+        worker.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+        Ok(ScrambleWorker {
+            port: Port::Worker {
+                worker,
+                _on_message: on_message,
+            },
+            next_id: 0,
+            targets,
+            on_animation_end,
+        })
+    }
+
+    /// Called with the animation id whenever an animation finishes.
+    pub fn set_on_animation_end(&mut self, callback: js_sys::Function) {
+        *self.on_animation_end.borrow_mut() = Some(callback);
+    }
+
+    /// Starts an animation computed in the worker and written to `target` on this thread.
+    /// Returns the animation id.
+    pub fn scramble(
+        &mut self,
+        target: Node,
+        #[wasm_bindgen(unchecked_param_type = "ScrambleProps | ScrambleOptions")] props: JsValue,
+    ) -> Result<u32, JsError> {
+        let props = props_from_js(props)?;
+        props.validate()?;
+        let target = RenderTarget::new(target, &props.target)?;
+
+        let id = self.next_id();
+        self.targets.borrow_mut().insert(id, target);
+        post_message(
+            |message| self.post(message, None),
+            &WorkerMessage::Start {
+                id,
+                props: Box::new(props),
+                font: None,
+                fill_style: None,
+            },
+        )?;
+        Ok(id)
+    }
+
+    /// Hands `canvas` to the worker, which draws every frame to it without involving this
+    /// thread. A canvas can only be transferred once. Returns the animation id.
+    pub fn scramble_canvas(
+        &mut self,
+        canvas: HtmlCanvasElement,
+        #[wasm_bindgen(unchecked_param_type = "ScrambleProps | ScrambleOptions")] props: JsValue,
+        font: Option<String>,
+        fill_style: Option<String>,
+    ) -> Result<u32, JsError> {
+        let props = props_from_js(props)?;
+        props.validate()?;
+        let offscreen = canvas
+            .transfer_control_to_offscreen()
+            .map_err(|e| JsError::new(&format!("Failed to transfer canvas: {:?}", e)))?;
+
+        let id = self.next_id();
+        let message = serde_wasm_bindgen::to_value(&WorkerMessage::Start {
+            id,
+            props: Box::new(props),
+            font,
+            fill_style,
+        })?;
+        js_sys::Reflect::set(&message, &"canvas".into(), &offscreen)
+            .map_err(|e| JsError::new(&format!("Failed to attach canvas: {:?}", e)))?;
+        self.post(&message, Some(&js_sys::Array::of1(&offscreen)))
+            .map_err(|e| JsError::new(&format!("Failed to post message: {:?}", e)))?;
+        Ok(id)
+    }
+
+    /// Stops the animation with the given id, leaving its target showing the last frame.
+    pub fn stop(&mut self, id: u32) -> Result<(), JsError> {
+        self.targets.borrow_mut().remove(&id);
+        post_message(
+            |message| self.post(message, None),
+            &WorkerMessage::Stop { id },
+        )
+    }
+
+    /// Stops every animation and shuts the worker down.
+    pub fn terminate(&mut self) {
+        self.targets.borrow_mut().clear();
+        match &mut self.port {
+            Port::Worker { worker, .. } => worker.terminate(),
+            #[cfg(feature = "test-hooks")]
+            Port::Local(jobs) => jobs.jobs.clear(),
+        }
+    }
+
+    fn next_id(&mut self) -> u32 {
+        self.next_id += 1;
+        self.next_id
+    }
+}
+
+impl ScrambleWorker {
+    /// Sends `message` to the worker, handing over the objects in `transfer`.
+    fn post(&mut self, message: &JsValue, transfer: Option<&js_sys::Array>) -> Result<(), JsValue> {
+        match &mut self.port {
+            Port::Worker { worker, .. } => match transfer {
+                Some(transfer) => worker.post_message_with_transfer(message, transfer),
+                None => worker.post_message(message),
+            },
+            #[cfg(feature = "test-hooks")]
+            Port::Local(jobs) => jobs.receive(message),
+        }
+    }
+}
+
+/// Hooks running the worker's jobs on this thread in tests, with the `test-hooks` feature.
+#[cfg(feature = "test-hooks")]
+#[wasm_bindgen]
+impl ScrambleWorker {
+    /// A handle whose jobs run on this thread instead of a worker, and only move on when
+    /// `advance_clock()` is called. Messages go through the same protocol as a worker's.
+    pub fn in_thread() -> ScrambleWorker {
+        ScrambleWorker {
+            port: Port::Local(WorkerJobs::default()),
+            next_id: 0,
+            targets: Rc::default(),
+            on_animation_end: Rc::default(),
+        }
+    }
+
+    /// Moves the jobs of an `in_thread()` handle on by `ms`, delivering the messages they post
+    /// back like a worker tick would.
+    pub fn advance_clock(&mut self, ms: f64) -> Result<(), JsError> {
+        let Port::Local(jobs) = &mut self.port else {
+            return Err(JsError::new("Only in_thread() workers have a fake clock"));
+        };
+        for message in jobs.tick(ms) {
+            let data = serde_wasm_bindgen::to_value(&message)?;
+            deliver(&self.targets, &self.on_animation_end, data);
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ScrambleWorker {
+    fn drop(&mut self) {
+        match &self.port {
+            Port::Worker { worker, .. } => {
+                worker.set_onmessage(None);
+                worker.terminate();
+            }
+            #[cfg(feature = "test-hooks")]
+            Port::Local(_) => {}
+        }
+    }
+}
+
+struct WorkerJob {
+    engine: ScrambleEngine,
+    canvas: Option<(OffscreenCanvas, OffscreenCanvasRenderingContext2d)>,
+}
+
+/// The animations a worker runs, apart from the timer driving them.
+#[derive(Default)]
+struct WorkerJobs {
+    jobs: HashMap<u32, WorkerJob>,
+}
+
+impl WorkerJobs {
+    /// Starts or stops a job as asked by a message from the main thread.
+    fn receive(&mut self, data: &JsValue) -> Result<(), JsValue> {
+        let message: WorkerMessage = serde_wasm_bindgen::from_value(data.clone())?;
+        match message {
+            WorkerMessage::Start {
+                id,
+                props,
+                font,
+                fill_style,
+            } => {
+                let canvas = js_sys::Reflect::get(data, &"canvas".into())?
+                    .dyn_into::<OffscreenCanvas>()
+                    .ok();
+                let canvas = match canvas {
+                    Some(canvas) => {
+                        let context = canvas
+                            .get_context("2d")?
+                            .ok_or_else(|| JsError::new("Failed to get a 2d context"))?
+                            .unchecked_into::<OffscreenCanvasRenderingContext2d>();
+                        context.set_font(font.as_deref().unwrap_or("16px monospace"));
+                        context.set_text_baseline("top");
+                        if let Some(fill_style) = fill_style {
+                            context.set_fill_style_str(&fill_style);
+                        }
+                        Some((canvas, context))
+                    }
+                    None => None,
+                };
+
+                let engine = ScrambleEngine::new(*props).map_err(JsError::from)?;
+                self.jobs.insert(id, WorkerJob { engine, canvas });
+            }
+            WorkerMessage::Stop { id } => {
+                self.jobs.remove(&id);
+            }
+            WorkerMessage::Frame { .. } | WorkerMessage::Ended { .. } => {}
+        }
+        Ok(())
+    }
+
+    /// Advances every job by `delta` ms, drawing canvases, and returns the messages to post
+    /// back: text frames, and the end of canvas animations.
+    fn tick(&mut self, delta: f64) -> Vec<WorkerMessage> {
+        let mut messages = Vec::new();
+        self.jobs.retain(|id, job| {
+            let Some(frame) = job.engine.advance(delta) else {
+                return true;
+            };
+            match &job.canvas {
+                Some((canvas, context)) => {
+                    draw_to_canvas(canvas, context, &frame.text);
+                    if frame.complete {
+                        messages.push(WorkerMessage::Ended { id: *id });
+                    }
+                }
+                None => messages.push(WorkerMessage::Frame {
+                    id: *id,
+                    text: frame.text,
+                    offsets: frame.offsets,
+                    complete: frame.complete,
+                }),
+            }
+            !frame.complete
+        });
+        messages
+    }
+}
+
+#[derive(Default)]
+struct WorkerState {
+    jobs: WorkerJobs,
+    interval: Option<(i32, Closure<dyn FnMut()>)>,
+    clock: Clock,
+}
+
+thread_local! {
+    static WORKER: RefCell<WorkerState> = RefCell::default();
+}
+
+fn worker_scope() -> DedicatedWorkerGlobalScope {
+    js_sys::global().unchecked_into()
+}
+
+fn draw_to_canvas(
+    canvas: &OffscreenCanvas,
+    context: &OffscreenCanvasRenderingContext2d,
+    text: &str,
+) {
+    context.clear_rect(0.0, 0.0, canvas.width() as f64, canvas.height() as f64);
+    let _ = context.fill_text(text, 0.0, 0.0);
+}
+
+/// Advances every job by the time elapsed since the last call and delivers due frames.
+fn worker_tick() {
+    let frames = WORKER.with(|state| {
+        let mut state = state.borrow_mut();
+        let delta = state.clock.delta();
+        let frames = state.jobs.tick(delta);
+
+        if state.jobs.jobs.is_empty() {
+            if let Some((id, _)) = state.interval.take() {
+                worker_scope().clear_interval_with_handle(id);
+            }
+        }
+        frames
+    });
+
+    let scope = worker_scope();
+    for frame in &frames {
+        let _ = post_message(|message| scope.post_message(message), frame);
+    }
+}
+
+fn handle_message(data: JsValue) -> Result<(), JsValue> {
+    WORKER.with(|state| {
+        let mut state = state.borrow_mut();
+        state.jobs.receive(&data)?;
+
+        if !state.jobs.jobs.is_empty() && state.interval.is_none() {
+            let closure = Closure::<dyn FnMut()>::new(worker_tick);
+            let handle = worker_scope().set_interval_with_callback_and_timeout_and_arguments_0(
+                closure.as_ref().unchecked_ref(),
+                1000 / 60,
+            )?;
+            // Start counting from now, so the first tick gets its time
+            state.clock.reset();
+            state.clock.delta();
+            state.interval = Some((handle, closure));
+        }
+        Ok(())
+    })
+}
+
+/// Worker entry point, called by the glue script spawned from [`ScrambleWorker::new`] with the
+/// messages that arrived while the module was loading.
+#[doc(hidden)]
+#[wasm_bindgen]
+pub fn run_scramble_worker(pending: js_sys::Array) {
+    let on_message = Closure::<dyn FnMut(MessageEvent)>::new(|event: MessageEvent| {
+        if let Err(e) = handle_message(event.data()) {
+            web_sys::console::error_1(&e);
+        }
+    });
+
+    for event in pending.iter() {
+        if let Ok(event) = event.dyn_into::<MessageEvent>() {
+            if let Err(e) = handle_message(event.data()) {
+                web_sys::console::error_1(&e);
+            }
+        }
+    }
+
+    worker_scope().set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    // The handler stays installed for the lifetime of the worker
+    on_message.forget();
+}
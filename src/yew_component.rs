@@ -0,0 +1,111 @@
+//! `<Scramble>` component for Yew apps.
+//!
+//! ```ignore
+//! html! { <Scramble text="Hello World" speed={0.5} /> }
+//! ```
+//!
+//! The component renders a `<span>` and drives it with a [`ScrambleText`], so it shares the
+//! animation loop, pausing and reduced motion handling of the wasm bindings.
+
+use web_sys::Node;
+use yew::prelude::*;
+
+use crate::scramble::{Overdrive, RangeOrCharCodes, UseScrambleProps};
+use crate::web::ScrambleText;
+
+/// Props of [`Scramble`], mirroring the scramble props of the same names.
+#[derive(Properties, Clone, PartialEq)]
+pub struct ScrambleProps {
+    /// Text to reveal. Changing it replays the animation.
+    pub text: AttrValue,
+    #[prop_or(1.0)]
+    pub speed: f32,
+    #[prop_or(1)]
+    pub tick: i32,
+    #[prop_or(1)]
+    pub step: i32,
+    #[prop_or(1.0)]
+    pub chance: f32,
+    #[prop_or(1)]
+    pub seed: i32,
+    #[prop_or(1)]
+    pub scramble: i32,
+    #[prop_or_else(|| vec![" ".to_string()])]
+    pub ignore: Vec<String>,
+    #[prop_or(RangeOrCharCodes::Range(65, 125))]
+    pub range: RangeOrCharCodes,
+    #[prop_or_default]
+    pub overdrive: bool,
+    #[prop_or(true)]
+    pub overflow: bool,
+    #[prop_or_default]
+    pub hover_replay: bool,
+    /// When false, the text is shown as is until `hover_replay` replays it.
+    #[prop_or(true)]
+    pub play_on_mount: bool,
+    /// Classes of the rendered `<span>`.
+    #[prop_or_default]
+    pub class: Classes,
+}
+
+impl ScrambleProps {
+    fn scramble_props(&self) -> UseScrambleProps {
+        UseScrambleProps {
+            play_on_mount: Some(self.play_on_mount),
+            text: self.text.to_string(),
+            speed: self.speed,
+            tick: self.tick,
+            step: self.step,
+            chance: self.chance.into(),
+            seed: self.seed,
+            scramble: self.scramble,
+            ignore: self
+                .ignore
+                .iter()
+                .flat_map(|ignore| ignore.chars())
+                .collect(),
+            range: self.range.clone(),
+            overdrive: Overdrive::Enabled(self.overdrive),
+            overflow: self.overflow,
+            hover_replay: self.hover_replay,
+            ..Default::default()
+        }
+    }
+}
+
+/// Sets up the animation of a mounted node, playing it unless `play_on_mount` is off.
+fn mount(node: Node, props: &ScrambleProps) -> Option<ScrambleText> {
+    node.set_text_content(Some(&props.text));
+    let mut scramble = match ScrambleText::with_props(node, props.scramble_props()) {
+        Ok(scramble) => scramble,
+        Err(e) => {
+            web_sys::console::error_1(&e.into());
+            return None;
+        }
+    };
+    if props.play_on_mount {
+        if let Err(e) = scramble.start() {
+            web_sys::console::error_1(&e.into());
+        }
+    }
+    Some(scramble)
+}
+
+/// Scrambles `text` into view. The animation is recreated when the props change and stopped
+/// when the component unmounts.
+#[function_component(Scramble)]
+pub fn scramble(props: &ScrambleProps) -> Html {
+    let node_ref = use_node_ref();
+
+    {
+        let node_ref = node_ref.clone();
+        use_effect_with(props.clone(), move |props| {
+            let scramble = node_ref.cast::<Node>().and_then(|node| mount(node, props));
+            // Dropping the instance stops it and removes its listeners
+            move || drop(scramble)
+        });
+    }
+
+    // The span has no children for Yew to reconcile, since the animation owns its text
+    html! { <span ref={node_ref} class={props.class.clone()}></span> }
+}
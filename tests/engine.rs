@@ -0,0 +1,1147 @@
+use scramble_text::{
+    check_prop_names, hydration_markup, initial_frame, record_frames, suggest_prop, Chance, Clock,
+    Direction, Frame, Jitter, Mismatch, Overdrive, Overlength, RangeOrCharCodes, ScrambleControl,
+    ScrambleEngine, ScrambleError, Stage, Transform, UseScrambleProps, Whitespace,
+};
+
+fn engine(text: &str) -> ScrambleEngine {
+    ScrambleEngine::new(UseScrambleProps {
+        text: text.to_string(),
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+fn run_to_completion(engine: &mut ScrambleEngine) -> usize {
+    let mut frames = 0;
+    while !engine.is_complete() {
+        engine.tick();
+        frames += 1;
+        assert!(frames < 10_000, "Animation should finish");
+    }
+    frames
+}
+
+#[test]
+fn test_tick_reaches_final_text() {
+    let mut engine = engine("Hello World");
+
+    let mut last = None;
+    while !engine.is_complete() {
+        last = Some(engine.tick());
+    }
+
+    let last = last.unwrap();
+    assert!(last.complete);
+    assert_eq!(last.text, "Hello World");
+    assert_eq!(last.settled, (0..11).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_settled_indices_only_grow() {
+    let mut engine = engine("Settling characters");
+
+    let mut previous = 0;
+    while !engine.is_complete() {
+        let frame = engine.tick();
+        assert!(frame.settled.len() >= previous);
+        previous = frame.settled.len();
+    }
+}
+
+#[test]
+fn test_advance_waits_for_frame_interval() {
+    let mut engine = ScrambleEngine::new(UseScrambleProps {
+        text: "Timing".to_string(),
+        speed: 0.5,
+        ..Default::default()
+    })
+    .unwrap();
+
+    // At half speed a frame is due every 33.3ms
+    assert!(engine.advance(16.0).is_none());
+    assert!(engine.advance(16.0).is_none());
+    assert!(engine.advance(16.0).is_some());
+}
+
+#[test]
+fn test_advance_catches_up_on_throttled_timers() {
+    let mut clock = Clock::new();
+    assert_eq!(clock.delta_to(500.0), 0.0);
+    assert_eq!(clock.delta_to(1500.0), 1000.0);
+    // Timestamps going back in time, or a tab suspended for a minute
+    assert_eq!(clock.delta_to(1400.0), 0.0);
+    assert_eq!(clock.delta_to(61_500.0), 1000.0);
+
+    // A background tab's timer firing once a second still reveals a second's worth
+    let mut engine = ScrambleEngine::new(UseScrambleProps {
+        text: "Throttled".to_string(),
+        tick: 100,
+        ..Default::default()
+    })
+    .unwrap();
+    engine.advance(1000.0).unwrap();
+    assert!((59..=60).contains(&engine.frame_count()));
+    // Fed directly to the engine, a long stall is capped too
+    engine.advance(60_000.0).unwrap();
+    assert!((119..=120).contains(&engine.frame_count()));
+}
+
+#[test]
+fn test_max_fps_skips_frames_without_slowing_the_reveal() {
+    // Returns the time taken to complete, in display frames, and the number of frames drawn
+    let run = |max_fps| {
+        let mut engine = ScrambleEngine::new(UseScrambleProps {
+            text: "Hello World".to_string(),
+            range: RangeOrCharCodes::Codes(vec!['#' as u32]),
+            scramble: 0,
+            max_fps,
+            ..Default::default()
+        })
+        .unwrap();
+        let (mut display_frames, mut drawn) = (0u32, 0u32);
+        while !engine.is_complete() {
+            display_frames += 1;
+            if engine.advance(17.0).is_some() {
+                drawn += 1;
+            }
+        }
+        (display_frames, drawn)
+    };
+
+    let (uncapped_time, uncapped_drawn) = run(None);
+    let (capped_time, capped_drawn) = run(Some(30.0));
+    assert!(capped_time.abs_diff(uncapped_time) <= 2);
+    assert!(capped_drawn <= uncapped_drawn / 2 + 1);
+
+    let invalid = UseScrambleProps {
+        max_fps: Some(0.0),
+        ..Default::default()
+    };
+    assert_eq!(invalid.validate(), Err(ScrambleError::InvalidMaxFps(0.0)));
+}
+
+#[test]
+fn test_advance_paused_at_zero_speed() {
+    let mut engine = ScrambleEngine::new(UseScrambleProps {
+        text: "Paused".to_string(),
+        speed: 0.0,
+        ..Default::default()
+    })
+    .unwrap();
+
+    assert!(engine.advance(1000.0).is_none());
+}
+
+#[test]
+fn test_set_props_applies_mid_run() {
+    let mut engine = engine("Live update");
+    engine.tick();
+    engine.tick();
+
+    let props = UseScrambleProps {
+        speed: 0.0,
+        ..engine.props().clone()
+    };
+    engine.set_props(props).unwrap();
+    assert!(engine.advance(1000.0).is_none());
+    assert!(!engine.is_complete());
+
+    let invalid = UseScrambleProps {
+        chance: 2.0.into(),
+        ..engine.props().clone()
+    };
+    assert!(engine.set_props(invalid).is_err());
+    assert_eq!(engine.props().speed, 0.0);
+}
+
+#[test]
+fn test_long_text_reveals_every_character() {
+    let text: String = "Lorem ipsum dolor sit amet, ".repeat(300);
+    let mut engine = ScrambleEngine::new(UseScrambleProps {
+        text: text.clone(),
+        step: 50,
+        ..Default::default()
+    })
+    .unwrap();
+    while !engine.is_complete() {
+        engine.tick();
+    }
+    assert_eq!(engine.finish().text, text);
+
+    // A new text takes effect on the next run
+    let props = UseScrambleProps {
+        text: "Short".to_string(),
+        ..engine.props().clone()
+    };
+    engine.set_props(props).unwrap();
+    engine.reset();
+    assert_eq!(engine.finish().text, "Short");
+}
+
+#[test]
+fn test_progress_and_frame_count() {
+    let mut engine = engine("Progress");
+    assert_eq!(engine.progress(), 0.0);
+    assert_eq!(engine.frame_count(), 0);
+
+    let mut previous = 0.0;
+    while !engine.is_complete() {
+        engine.tick();
+        assert!(engine.progress() >= previous);
+        previous = engine.progress();
+    }
+    assert_eq!(engine.progress(), 1.0);
+    assert!(engine.frame_count() > 0);
+}
+
+#[test]
+fn test_reset_replays_animation() {
+    let mut engine = engine("Replay");
+    run_to_completion(&mut engine);
+
+    engine.reset();
+    assert!(!engine.is_complete());
+    assert_ne!(engine.tick().text, "Replay");
+    run_to_completion(&mut engine);
+}
+
+#[test]
+fn test_reduced_motion_reveals_without_scrambling() {
+    let text = "Reduced motion";
+    let props = UseScrambleProps {
+        text: text.to_string(),
+        ..Default::default()
+    };
+    let mut engine = ScrambleEngine::new(props.with_reduced_motion()).unwrap();
+
+    let frames = run_to_completion(&mut engine);
+    assert!(frames <= 3, "Took {} frames", frames);
+    assert_eq!(engine.tick().text, text);
+}
+
+#[test]
+fn test_finish_skips_to_final_frame() {
+    let mut engine = engine("Finished early");
+    engine.tick();
+
+    let frame = engine.finish();
+    assert!(frame.complete);
+    assert!(engine.is_complete());
+    assert_eq!(frame.text, "Finished early");
+    assert_eq!(frame.settled, (0..14).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_seek_reveals_up_to_progress() {
+    let mut engine = engine("Scrubbed");
+
+    let frame = engine.seek(0.5);
+    assert!(frame.text.starts_with("Scru"));
+    assert_eq!(frame.settled, (0..4).collect::<Vec<_>>());
+    assert!(!frame.complete);
+
+    // Seeking is independent of previous frames, in either direction
+    let frame = engine.seek(1.0);
+    assert!(frame.complete);
+    assert_eq!(frame.text, "Scrubbed");
+
+    let frame = engine.seek(0.0);
+    assert!(!frame.complete);
+    assert!(frame.settled.is_empty());
+}
+
+#[test]
+fn test_max_frames_bounds_run_length() {
+    for (text, step, tick, scramble) in [("Bounded", 1, 1, 1), ("Bounded run", 2, 3, 6)] {
+        let props = UseScrambleProps {
+            text: text.to_string(),
+            step,
+            tick,
+            scramble,
+            ..Default::default()
+        };
+        let max_frames = props.max_frames() as usize;
+        for _ in 0..50 {
+            let mut engine = ScrambleEngine::new(props.clone()).unwrap();
+            assert!(run_to_completion(&mut engine) <= max_frames);
+        }
+    }
+}
+
+#[test]
+fn test_validation_errors_are_structured() {
+    let props = UseScrambleProps {
+        range: RangeOrCharCodes::Range(126, 33),
+        ..Default::default()
+    };
+    let error = ScrambleEngine::new(props).err().unwrap();
+    assert_eq!(error, ScrambleError::InvalidRange { min: 126, max: 33 });
+    assert_eq!(error.code(), "invalid_range");
+    assert!(error.to_string().contains("126-33"));
+}
+
+#[test]
+fn test_validate_all_reports_every_problem() {
+    let props = UseScrambleProps {
+        speed: 2.0,
+        step: 0,
+        range: RangeOrCharCodes::Codes(vec![65, 0xD800]),
+        ..Default::default()
+    };
+    let errors = vec![
+        ScrambleError::InvalidSpeed(2.0),
+        ScrambleError::InvalidStep(0),
+        ScrambleError::InvalidCode(0xD800),
+    ];
+    assert_eq!(props.validate_all(), errors);
+
+    let error = ScrambleEngine::new(props).err().unwrap();
+    assert_eq!(error, ScrambleError::InvalidProps(errors));
+    assert_eq!(error.code(), "invalid_props");
+    assert!(UseScrambleProps::default().validate_all().is_empty());
+}
+
+#[test]
+fn test_unknown_props_suggest_nearest_name() {
+    assert_eq!(suggest_prop("scrample"), Some("scramble"));
+    assert_eq!(suggest_prop("playOnMount"), Some("play_on_mount"));
+    assert_eq!(suggest_prop("colour"), None);
+
+    assert!(check_prop_names(["text", "speed"]).is_ok());
+    let error = check_prop_names(["text", "sped"]).err().unwrap();
+    assert_eq!(
+        error,
+        ScrambleError::UnknownProp {
+            name: "sped".to_string(),
+            suggestion: Some("speed"),
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "Unknown prop \"sped\", did you mean \"speed\"?"
+    );
+}
+
+#[test]
+fn test_record_frames() {
+    let props = UseScrambleProps {
+        text: "Recorded".to_string(),
+        speed: 0.5,
+        ..Default::default()
+    };
+
+    let recording = record_frames(props.clone(), 1000).unwrap();
+    assert!(recording.complete);
+    assert_eq!(recording.frames.last().unwrap().frame.text, "Recorded");
+    assert!(recording.frames.len() as u32 <= props.max_frames());
+    // Frames are drawn every other display frame at half speed
+    assert_eq!(recording.frames[1].time, 2000.0 / 60.0);
+    assert_eq!(recording.duration, recording.frames.last().unwrap().time);
+
+    let recording = record_frames(props, 2).unwrap();
+    assert_eq!(recording.frames.len(), 2);
+    assert!(!recording.complete);
+}
+
+#[test]
+fn test_initial_frame() {
+    let props = UseScrambleProps {
+        text: "Hello World".to_string(),
+        range: RangeOrCharCodes::Codes(vec!['#' as u32]),
+        ..Default::default()
+    };
+    // Ignored characters keep their place
+    assert_eq!(initial_frame(props.clone()).unwrap(), "##### #####");
+
+    let props = UseScrambleProps {
+        overflow: false,
+        ..props
+    };
+    assert_eq!(initial_frame(props).unwrap(), "");
+}
+
+#[test]
+fn test_placeholder_char() {
+    let props = UseScrambleProps {
+        text: "Hello".to_string(),
+        range: RangeOrCharCodes::Codes(vec!['#' as u32]),
+        overflow: false,
+        placeholder_char: Some('·'),
+        ..Default::default()
+    };
+    assert_eq!(initial_frame(props.clone()).unwrap(), "·····");
+
+    let mut engine = ScrambleEngine::new(props).unwrap();
+    let frame = engine.tick();
+    assert_eq!(frame.text.chars().count(), 5);
+    assert!(frame.text.ends_with("···"));
+    run_to_completion(&mut engine);
+    assert_eq!(engine.finish().text, "Hello");
+}
+
+#[test]
+fn test_whitespace_nbsp() {
+    let mut engine = ScrambleEngine::new(UseScrambleProps {
+        text: "Hello  World".to_string(),
+        whitespace: Whitespace::Nbsp,
+        ..Default::default()
+    })
+    .unwrap();
+    for _ in 0..6 {
+        let frame = engine.tick();
+        assert!(!frame.text.contains(' '));
+    }
+    assert!(engine.tick().text.contains('\u{a0}'));
+
+    // The final frame shows the text as given
+    run_to_completion(&mut engine);
+    assert_eq!(engine.finish().text, "Hello  World");
+}
+
+#[test]
+fn test_rtl_text_draws_its_own_script() {
+    let is_hebrew = |c: char| ('\u{5d0}'..='\u{5ea}').contains(&c);
+    let mut engine = engine("שלום עולם");
+    while !engine.is_complete() {
+        let frame = engine.tick();
+        assert!(frame.text.chars().all(|c| c == ' ' || is_hebrew(c)));
+    }
+
+    // Latin words in RTL text keep drawing from the range, and block glyphs are kept
+    let mut engine = ScrambleEngine::new(UseScrambleProps {
+        text: "abc".to_string(),
+        dir: Direction::Rtl,
+        ..Default::default()
+    })
+    .unwrap();
+    assert!(engine.tick().text.is_ascii());
+    let mut engine = ScrambleEngine::new(UseScrambleProps {
+        text: "שלום".to_string(),
+        range: RangeOrCharCodes::Codes(vec![0x2591, 0x2592]),
+        ..Default::default()
+    })
+    .unwrap();
+    assert!(engine.tick().text.chars().all(|c| !is_hebrew(c)));
+}
+
+#[test]
+fn test_auto_range_follows_the_text_scripts() {
+    let range: RangeOrCharCodes = "auto".parse().unwrap();
+    assert_eq!(range.to_string(), "auto");
+
+    let is_cyrillic = |c: char| ('А'..='я').contains(&c);
+    let frames = |text: &str| {
+        let mut engine = ScrambleEngine::new(UseScrambleProps {
+            text: text.to_string(),
+            range: range.clone(),
+            scramble: 4,
+            ..Default::default()
+        })
+        .unwrap();
+        let mut drawn = String::new();
+        while !engine.is_complete() {
+            drawn += &engine.tick().text;
+        }
+        drawn
+    };
+    assert!(frames("Привет мир")
+        .chars()
+        .all(|c| c == ' ' || is_cyrillic(c)));
+
+    // Mixed text draws from each of its scripts
+    let drawn = frames("Hello мир");
+    assert!(drawn
+        .chars()
+        .all(|c| c == ' ' || c.is_ascii_alphabetic() || is_cyrillic(c)));
+}
+
+#[test]
+fn test_emoji_sequences_scramble_as_one() {
+    let text = "Hi 👋🏽 👨‍👩‍👧 🇫🇷";
+    let sequences = ["👋🏽", "👨‍👩‍👧", "🇫🇷"];
+    let mut engine = ScrambleEngine::new(UseScrambleProps {
+        text: text.to_string(),
+        scramble: 4,
+        ..Default::default()
+    })
+    .unwrap();
+    let mut scrambled = false;
+    while !engine.is_complete() {
+        // Past the whole sequences, only the emoji scrambled through are left
+        let mut rest = engine.tick().text;
+        for sequence in sequences {
+            rest = rest.replace(sequence, "");
+        }
+        let emoticon = |c: char| ('\u{1F600}'..='\u{1F64F}').contains(&c);
+        assert!(rest.chars().all(|c| (c as u32) < 0x2000 || emoticon(c)));
+        scrambled |= rest.chars().any(emoticon);
+    }
+    assert!(scrambled);
+    assert_eq!(engine.finish().text, text);
+}
+
+#[test]
+fn test_transform_lands_on_stylized_text() {
+    let mut engine = ScrambleEngine::new(UseScrambleProps {
+        text: "Hello World".to_string(),
+        transform: Some(Transform::Leetspeak),
+        ..Default::default()
+    })
+    .unwrap();
+    let frames = run_to_completion(&mut engine);
+    assert!(frames > 1);
+    assert_eq!(engine.finish().text, "H3ll0 W0rld");
+    assert_eq!(engine.props().text, "Hello World");
+
+    assert_eq!(Transform::Uppercase.apply("straße"), "STRASSE");
+    assert_eq!(Transform::SmallCaps.apply("Hello"), "Hᴇʟʟᴏ");
+}
+
+#[test]
+fn test_controls_can_be_inspected_and_set() {
+    let mut engine = ScrambleEngine::new(UseScrambleProps {
+        text: "abc".to_string(),
+        overflow: false,
+        seed: 0,
+        ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(engine.controls(), [ScrambleControl::Pending; 3]);
+
+    // A position settled ahead of the reveal shows its character right away
+    engine.set_control(2, ScrambleControl::Settled('c'));
+    engine.set_control(3, ScrambleControl::Settled('d'));
+    let frame = engine.tick();
+    assert!(frame.text.ends_with('c'));
+    assert!(matches!(
+        engine.controls()[0],
+        ScrambleControl::Scrambling(_) | ScrambleControl::Settled('a')
+    ));
+    assert_eq!(engine.controls().len(), 3);
+    run_to_completion(&mut engine);
+    assert_eq!(engine.finish().text, "abc");
+}
+
+#[test]
+fn test_frame_plugins_change_drawn_frames() {
+    let mut engine = engine("Hello");
+    engine.add_plugin(|frame: &mut Frame, controls: &[ScrambleControl]| {
+        // Marks the positions still scrambling
+        let marked: String = frame
+            .text
+            .chars()
+            .zip(controls)
+            .map(|(c, control)| match control {
+                ScrambleControl::Scrambling(n) if *n > 0 => '*',
+                _ => c,
+            })
+            .collect();
+        frame.text = marked;
+    });
+    engine.add_plugin(|frame: &mut Frame, _: &[ScrambleControl]| frame.text.push('!'));
+
+    let first = engine.tick();
+    assert!(first.text.ends_with('!'));
+    assert!(first.text.contains('*'));
+    run_to_completion(&mut engine);
+    assert_eq!(engine.finish().text, "Hello!");
+}
+
+#[test]
+fn test_mismatch_rendering() {
+    let frame = |mismatch| {
+        let mut engine = ScrambleEngine::new(UseScrambleProps {
+            text: "abc".to_string(),
+            mismatch,
+            placeholder_char: Some('·'),
+            ..Default::default()
+        })
+        .unwrap();
+        // Behind the reveal front, the first position holds a character other than its own
+        let mut state = engine.export_state();
+        state.control = vec![
+            ScrambleControl::Settled('_'),
+            ScrambleControl::Settled('b'),
+            ScrambleControl::Pending,
+        ];
+        state.scramble_index = 2;
+        engine.import_state(&state).unwrap().text
+    };
+    assert_eq!(frame(Mismatch::Space), " b·");
+    assert_eq!(frame(Mismatch::Final), "ab·");
+    assert_eq!(frame(Mismatch::Scrambled), "_b·");
+    assert_eq!(frame(Mismatch::Placeholder), "·b·");
+}
+
+#[test]
+fn test_mask_shows_revealed_characters_masked() {
+    let mut engine = ScrambleEngine::new(UseScrambleProps {
+        text: "hunter2 pw".to_string(),
+        mask: Some('•'),
+        range: RangeOrCharCodes::Codes(vec!['#' as u32]),
+        ..Default::default()
+    })
+    .unwrap();
+    // Revealed characters are masked, and only scrambling ones are random
+    while !engine.is_complete() {
+        let frame = engine.tick();
+        assert!(frame.text.chars().all(|c| matches!(c, '•' | '#' | ' ')));
+    }
+    assert_eq!(engine.finish().text, "••••••• ••");
+    assert_eq!(engine.props().text, "hunter2 pw");
+}
+
+#[test]
+fn test_resume_from_rendered_frame() {
+    let props = UseScrambleProps {
+        text: "Tom & Jerry".to_string(),
+        range: RangeOrCharCodes::Codes(vec!['#' as u32]),
+        ..Default::default()
+    };
+    assert_eq!(
+        hydration_markup("h1", props.clone()).unwrap(),
+        r#"<h1 data-scramble-final="Tom &amp; Jerry">### # #####</h1>"#
+    );
+
+    // The revealed prefix stays, and only the rest is scrambled
+    let mut engine = ScrambleEngine::new(props).unwrap();
+    engine.resume_from("Tom ######");
+    assert!(engine.tick().text.starts_with("Tom "));
+    assert!(engine.progress() >= 4.0 / 11.0);
+}
+
+#[test]
+fn test_tick_into_reuses_buffer() {
+    let props = UseScrambleProps {
+        text: "Hello World".to_string(),
+        range: RangeOrCharCodes::Codes(vec!['#' as u32]),
+        scramble: 0,
+        ..Default::default()
+    };
+    let mut allocating = ScrambleEngine::new(props.clone()).unwrap();
+    let mut reusing = ScrambleEngine::new(props).unwrap();
+
+    let mut frame = Frame::default();
+    while !allocating.is_complete() {
+        let expected = allocating.tick();
+        reusing.tick_into(&mut frame);
+        assert_eq!(frame.text, expected.text);
+        assert_eq!(frame.settled, expected.settled);
+        assert_eq!(frame.complete, expected.complete);
+    }
+    assert_eq!(frame.text, "Hello World");
+}
+
+#[test]
+fn test_ranges_skip_surrogates() {
+    let props = UseScrambleProps {
+        text: "x".repeat(200),
+        range: RangeOrCharCodes::Range(0xD7FF, 0xE000),
+        ..Default::default()
+    };
+    let frame = initial_frame(props).unwrap();
+    assert!(frame.chars().all(|c| c == '\u{D7FF}' || c == '\u{E000}'));
+
+    // Too large to flatten into a table
+    let props = UseScrambleProps {
+        text: "x".repeat(200),
+        range: RangeOrCharCodes::Range(0xD000, 0x10FFFF),
+        ..Default::default()
+    };
+    let frame = initial_frame(props).unwrap();
+    assert!(frame.chars().all(|c| c as u32 >= 0xD000 && c != '_'));
+}
+
+#[test]
+fn test_code_points_as_strings() {
+    let parse = |s: &str| s.parse::<RangeOrCharCodes>().map(|range| range.to_string());
+    assert_eq!(parse("a-z"), Ok("97-122".to_string()));
+    // Digits are numbers, so that ranges round-trip through `to_string()`
+    assert_eq!(parse("0-127"), Ok("0-127".to_string()));
+    assert_eq!(parse("5,6"), Ok("5,6".to_string()));
+    assert_eq!(parse("1-9"), Ok("1-9".to_string()));
+    assert_eq!(parse("0x2591, U+2592, #"), Ok("9617,9618,35".to_string()));
+    assert!(parse("0x2591-zz").is_err());
+
+    // The surrogates are skipped inside a range, but can't bound one
+    let props = UseScrambleProps {
+        range: RangeOrCharCodes::Range(0xD800, 0xE000),
+        ..Default::default()
+    };
+    assert_eq!(
+        props.validate(),
+        Err(ScrambleError::InvalidRange {
+            min: 0xD800,
+            max: 0xE000
+        })
+    );
+}
+
+#[test]
+fn test_visible_range_settles_the_rest() {
+    let mut engine = ScrambleEngine::new(UseScrambleProps {
+        text: "Hello World".to_string(),
+        range: RangeOrCharCodes::Codes(vec!['#' as u32]),
+        ..Default::default()
+    })
+    .unwrap();
+    engine.set_visible_range(Some(0..5));
+
+    let frame = engine.tick();
+    assert!(frame.text.ends_with(" World"));
+    assert!(frame.text.starts_with('#'));
+    assert!((5..11).all(|i| frame.settled.contains(&i)));
+}
+
+#[test]
+fn test_max_length() {
+    let props = UseScrambleProps {
+        text: "Hello World".to_string(),
+        max_length: Some(5),
+        ..Default::default()
+    };
+    assert_eq!(
+        props.validate(),
+        Err(ScrambleError::TextTooLong { len: 11, max: 5 })
+    );
+
+    let mut engine = ScrambleEngine::new(UseScrambleProps {
+        overlength: Overlength::Truncate,
+        ..props.clone()
+    })
+    .unwrap();
+    run_to_completion(&mut engine);
+    assert_eq!(engine.finish().text, "Hello");
+
+    // Chunks are revealed one after the other, the rest of the text waiting its turn
+    let mut engine = ScrambleEngine::new(UseScrambleProps {
+        overlength: Overlength::Chunk,
+        ..props
+    })
+    .unwrap();
+    assert_eq!(engine.tick().text.chars().count(), 5);
+    let mut frame = engine.tick();
+    while !frame.complete {
+        assert!(frame.text.starts_with("Hello") || frame.text.chars().count() == 5);
+        frame = engine.tick();
+    }
+    assert_eq!(frame.text, "Hello World");
+    assert_eq!(frame.settled, (0..11).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_debug_summary() {
+    let mut engine = ScrambleEngine::new(UseScrambleProps {
+        text: "Hello".to_string(),
+        range: RangeOrCharCodes::Codes(vec!['#' as u32]),
+        ..Default::default()
+    })
+    .unwrap();
+    assert!(engine.debug_summary().starts_with("frame 0, front 0/5"));
+
+    run_to_completion(&mut engine);
+    let summary = engine.debug_summary();
+    assert!(summary.contains("front 5/5, 0 scrambling, 5 settled, 0 not drawn"));
+    assert!(summary.ends_with(", complete"));
+}
+
+#[test]
+fn test_seeded_engines_draw_the_same_frames() {
+    let run = |seed| {
+        let mut engine = engine("Seeded noise");
+        engine.seed_random(seed);
+        let mut frames = Vec::new();
+        while !engine.is_complete() {
+            frames.push(engine.tick().text);
+        }
+        frames
+    };
+    assert_eq!(run(7), run(7));
+    assert_ne!(run(7), run(8));
+}
+
+#[test]
+fn test_export_and_import_state() {
+    let mut engine = engine("Snapshot");
+    engine.seed_random(3);
+    for _ in 0..4 {
+        engine.tick();
+    }
+    let state = engine.export_state();
+    assert_eq!(state.frame_count, 4);
+
+    let mut resumed = self::engine("Snapshot");
+    let frame = resumed.import_state(&state).unwrap();
+    assert_eq!(frame.text.chars().count(), 8);
+    assert_eq!(resumed.export_state(), state);
+    while !engine.is_complete() {
+        assert_eq!(resumed.tick().text, engine.tick().text);
+    }
+    assert!(resumed.is_complete());
+
+    assert_eq!(
+        self::engine("Other").import_state(&state).unwrap_err(),
+        ScrambleError::StateMismatch
+    );
+}
+
+#[test]
+fn test_scramble_counts() {
+    let mut engine = ScrambleEngine::new(UseScrambleProps {
+        text: "Hello".to_string(),
+        scramble: 3,
+        ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(engine.scramble_counts(), vec![Some(3); 5]);
+    assert_eq!(engine.reveal_front(), 0);
+
+    run_to_completion(&mut engine);
+    assert_eq!(engine.scramble_counts(), vec![Some(0); 5]);
+    assert_eq!(engine.reveal_front(), 5);
+}
+
+#[test]
+fn test_pipeline_runs_stages_in_order() {
+    let props = UseScrambleProps {
+        text: "hello".to_string(),
+        pipeline: vec![
+            Stage::Sweep { char: None },
+            Stage::Typewriter,
+            Stage::Glitch {
+                frames: 5,
+                chance: 1.0,
+            },
+        ],
+        ..Default::default()
+    };
+    let max_frames = props.max_frames() as usize;
+    let mut engine = ScrambleEngine::new(props).unwrap();
+    let mut frames = Vec::new();
+    while !engine.is_complete() {
+        frames.push(engine.tick().text);
+        assert!(frames.len() <= max_frames, "Pipeline should finish");
+    }
+
+    // The sweep covers the text, the typewriter types over it, then the text glitches
+    assert!(frames.contains(&"_____".to_string()));
+    assert!(frames.contains(&"he___".to_string()));
+    let typed = frames.iter().position(|frame| frame == "hello").unwrap();
+    assert!(frames[typed..].iter().any(|frame| frame != "hello"));
+    assert_eq!(frames.last().unwrap(), "hello");
+
+    let invalid = UseScrambleProps {
+        text: "hello".to_string(),
+        pipeline: vec!["scramble".parse().unwrap(), "sweep".parse().unwrap()],
+        ..Default::default()
+    };
+    assert_eq!(invalid.validate(), Err(ScrambleError::UnrevealedPipeline));
+}
+
+#[test]
+fn test_frames_report_settled_and_scrambled_positions() {
+    let mut engine = engine("Hello");
+    engine.seed_random(5);
+    let mut newly_settled = Vec::new();
+    while !engine.is_complete() {
+        let frame = engine.tick();
+        let chars: Vec<char> = frame.text.chars().collect();
+        for &i in &frame.newly_settled {
+            assert_eq!(chars[i], "Hello".chars().nth(i).unwrap());
+        }
+        for &i in &frame.scrambled {
+            assert!(!frame.newly_settled.contains(&i));
+        }
+        newly_settled.extend(frame.newly_settled);
+    }
+    newly_settled.sort();
+    newly_settled.dedup();
+    assert_eq!(newly_settled, vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn test_frames_report_where_positions_are_drawn() {
+    let text = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467} ab";
+    let chars: Vec<char> = text.chars().collect();
+    let mut engine = engine(text);
+    engine.seed_random(2);
+    while !engine.is_complete() {
+        let frame = engine.tick();
+        let drawn: Vec<char> = frame.text.chars().collect();
+        // Settled characters show up where the offsets say, even while the emoji scrambles as
+        // a single character
+        for &i in frame.settled.iter().filter(|&&i| i > 5) {
+            assert_eq!(drawn[frame.offsets[i]], chars[i]);
+        }
+    }
+    let frame = engine.finish();
+    assert_eq!(frame.offsets, vec![0, 5, 5, 5, 5, 5, 6, 7]);
+}
+
+#[test]
+fn test_frames_report_positions_settling_on_a_matching_draw() {
+    // Every random draw is the final character, so the scramble ends on a frame that already
+    // matches the text
+    let mut engine = ScrambleEngine::new(UseScrambleProps {
+        text: "###".to_string(),
+        range: RangeOrCharCodes::Codes(vec!['#' as u32]),
+        ..Default::default()
+    })
+    .unwrap();
+    engine.seed_random(1);
+    let mut newly_settled = Vec::new();
+    while !engine.is_complete() {
+        let frame = engine.tick();
+        for &i in &frame.scrambled {
+            assert!(!frame.newly_settled.contains(&i));
+        }
+        newly_settled.extend(frame.newly_settled);
+    }
+    newly_settled.sort();
+    assert_eq!(newly_settled, vec![0, 1, 2]);
+}
+
+#[test]
+fn test_overdrive_step_sweeps_ahead_of_the_reveal() {
+    let mut engine = ScrambleEngine::new(UseScrambleProps {
+        text: "Hello World".to_string(),
+        overdrive: true.into(),
+        overdrive_step: Some(4),
+        seed: 0,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let frame = engine.tick();
+    assert_eq!(
+        frame.text.chars().skip(1).take(3).collect::<String>(),
+        "___"
+    );
+    assert_eq!(engine.reveal_front(), 1);
+    run_to_completion(&mut engine);
+
+    let invalid = UseScrambleProps {
+        overdrive_step: Some(0),
+        ..Default::default()
+    };
+    assert_eq!(
+        invalid.validate(),
+        Err(ScrambleError::InvalidOverdriveStep(0))
+    );
+}
+
+#[test]
+fn test_step_advances_the_reveal_front() {
+    let mut engine = ScrambleEngine::new(UseScrambleProps {
+        text: "abcdefgh".to_string(),
+        tick: 1,
+        step: 3,
+        chance: 1.0.into(),
+        scramble: 2,
+        scramble_jitter: Jitter::Times(0),
+        seed: 0,
+        ..Default::default()
+    })
+    .unwrap();
+    engine.seed_random(0);
+
+    // Every frame moves the front on by `step`, and positions behind it count down wherever
+    // they are
+    let fronts: Vec<usize> = (0..3)
+        .map(|_| {
+            engine.tick();
+            engine.reveal_front()
+        })
+        .collect();
+    assert_eq!(fronts, vec![3, 6, 8]);
+    assert!(engine.controls()[..3]
+        .iter()
+        .all(|control| matches!(control, ScrambleControl::Settled(_))));
+    // The positions the front reached last finish their scramble after it stops
+    assert_eq!(run_to_completion(&mut engine), 2);
+    let settled: Vec<ScrambleControl> = "abcdefgh".chars().map(ScrambleControl::Settled).collect();
+    assert_eq!(engine.controls(), settled.as_slice());
+}
+
+#[test]
+fn test_overdrive_keeps_ignored_characters() {
+    let mut engine = ScrambleEngine::new(UseScrambleProps {
+        text: "ab cd-ef".to_string(),
+        ignore: [' ', '-'].into(),
+        overdrive: Overdrive::Code('•' as u32),
+        overdrive_step: Some(8),
+        seed: 0,
+        ..Default::default()
+    })
+    .unwrap();
+
+    // The sweep covers the whole text in the first frame, leaving word boundaries visible
+    let frame = engine.tick();
+    assert_eq!(frame.text.chars().skip(1).collect::<String>(), "• ••-••");
+    run_to_completion(&mut engine);
+    assert_eq!(engine.tick().text, "ab cd-ef");
+}
+
+#[test]
+fn test_ignore_words_match_whole_words() {
+    let mut engine = ScrambleEngine::new(UseScrambleProps {
+        text: "Try Rust or Rusty".to_string(),
+        ignore_words: vec!["Rust".to_string()],
+        range: RangeOrCharCodes::Codes(vec!['#' as u32]),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let text: Vec<char> = engine.tick().text.chars().collect();
+    assert_eq!(text[4..8].iter().collect::<String>(), "Rust");
+    // Part of a longer word, so scrambled
+    assert!(text[12..17].iter().all(|&c| c == '#'));
+    run_to_completion(&mut engine);
+    assert_eq!(engine.tick().text, "Try Rust or Rusty");
+}
+
+#[test]
+fn test_chance_per_position() {
+    let mut engine = ScrambleEngine::new(UseScrambleProps {
+        text: "abcd".to_string(),
+        chance: vec![1.0, 1.0, 0.0].into(),
+        overflow: false,
+        ..Default::default()
+    })
+    .unwrap();
+    // Characters past the end of the list take its last chance
+    while !engine.is_complete() {
+        let frame = engine.tick();
+        assert!(frame.scrambled.iter().all(|&i| i < 2));
+    }
+
+    assert_eq!("1,0.5".parse::<Chance>().unwrap(), vec![1.0, 0.5].into());
+    let invalid = UseScrambleProps {
+        chance: vec![1.0, 1.5].into(),
+        ..Default::default()
+    };
+    assert_eq!(invalid.validate(), Err(ScrambleError::InvalidChance(1.5)));
+}
+
+#[test]
+fn test_scramble_jitter() {
+    let counts = |scramble_jitter| {
+        let mut engine = ScrambleEngine::new(UseScrambleProps {
+            text: "HelloWorld".to_string(),
+            step: 10,
+            seed: 0,
+            scramble: 4,
+            scramble_jitter,
+            ..Default::default()
+        })
+        .unwrap();
+        engine.tick();
+        engine.scramble_counts()
+    };
+    // Without jitter, every character has as many scrambles left after the first frame
+    assert_eq!(counts(Jitter::Times(0)), vec![Some(3); 10]);
+    assert!(counts(Jitter::Times(2))
+        .iter()
+        .all(|count| (3..=5).contains(&count.unwrap())));
+
+    assert_eq!("50%".parse::<Jitter>().unwrap(), Jitter::default());
+    assert_eq!(Jitter::default().max(3), 2);
+    let invalid = UseScrambleProps {
+        scramble_jitter: Jitter::Times(-1),
+        ..Default::default()
+    };
+    assert_eq!(
+        invalid.validate(),
+        Err(ScrambleError::InvalidScrambleJitter(Jitter::Times(-1)))
+    );
+}
+
+#[test]
+fn test_completion_waits_for_scrambles_to_run_out() {
+    // Every random character is the final one, so only the scramble counts tell frames apart
+    let mut engine = ScrambleEngine::new(UseScrambleProps {
+        text: "aaaa".to_string(),
+        range: RangeOrCharCodes::Codes(vec!['a' as u32]),
+        step: 4,
+        seed: 0,
+        scramble: 3,
+        scramble_jitter: Jitter::Times(0),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let first = engine.tick();
+    assert_eq!(first.text, "aaaa");
+    assert!(!first.complete);
+    let frames = run_to_completion(&mut engine);
+    assert_eq!(frames, 2);
+    assert!(engine
+        .controls()
+        .iter()
+        .all(|control| *control == ScrambleControl::Settled('a')));
+}
+
+#[test]
+fn test_rescramble_settles_back() {
+    let mut engine = engine("Hello world");
+    run_to_completion(&mut engine);
+
+    // The space is ignored, and the rest scramble for two frames
+    assert!(engine.rescramble(3..7, 2));
+    assert!(!engine.is_complete());
+    let frame = engine.tick();
+    assert_eq!(frame.scrambled, vec![3, 4, 6]);
+    assert!(!frame.complete);
+
+    assert!(run_to_completion(&mut engine) <= 2);
+    assert_eq!(engine.tick().text, "Hello world");
+    // Positions past the text are left alone
+    assert!(!engine.rescramble(20..30, 2));
+
+    // Frame counts past i32::MAX keep scrambling rather than wrapping around
+    assert!(engine.rescramble(0..1, u32::MAX));
+    engine.tick();
+    assert!(!engine.tick().complete);
+}
+
+#[cfg(feature = "dioxus")]
+#[test]
+fn test_dioxus_hook() {
+    use dioxus::prelude::*;
+    use scramble_text::dioxus::use_scramble;
+    use std::cell::RefCell;
+
+    thread_local! {
+        static RESULTS: RefCell<Vec<Result<String, ScrambleError>>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn app() -> Element {
+        let valid = use_scramble(UseScrambleProps {
+            text: "Hello".to_string(),
+            ..Default::default()
+        });
+        let invalid = use_scramble(UseScrambleProps {
+            text: "Hello".to_string(),
+            speed: 2.0,
+            ..Default::default()
+        });
+        RESULTS.with(|results| {
+            let mut results = results.borrow_mut();
+            results.push(valid.map(|scramble| scramble.text.cloned()));
+            results.push(invalid.map(|scramble| scramble.text.cloned()));
+        });
+        rsx! {}
+    }
+
+    VirtualDom::new(app).rebuild_in_place();
+    RESULTS.with(|results| {
+        assert_eq!(
+            *results.borrow(),
+            vec![
+                Ok("Hello".to_string()),
+                Err(ScrambleError::InvalidSpeed(2.0))
+            ]
+        );
+    });
+}
@@ -1,12 +1,17 @@
+#![cfg(feature = "web")]
+
 use js_sys::Promise;
-use wasm_bindgen::JsValue;
 use wasm_bindgen_futures::JsFuture;
 use wasm_bindgen_test::*;
 use web_sys::{Document, Element};
 
 wasm_bindgen_test_configure!(run_in_browser);
 
-use scramble_text::{ScrambleText, UseScrambleProps};
+use scramble_text::{
+    define_custom_element, init_all, pause_all, resume_all, split_target, stop_all,
+    transition_text, AriaLive, FramePlayer, PlayState, ScrambleOptions, ScrambleText,
+    ScrambleTimeline, SharedTarget, Trigger, UseScrambleProps, WriteTarget,
+};
 
 async fn sleep(ms: f64) {
     let promise = Promise::new(&mut |resolve, _| {
@@ -31,20 +36,16 @@ async fn test_basic_scramble() {
     let original_text = "Hello World";
 
     // Create scramble instance
-    let props = JsValue::from_serde(&UseScrambleProps {
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
         text: original_text.to_string(),
         speed: 1.0,
         ..Default::default()
     })
     .unwrap();
 
-    let mut scramble = ScrambleText::new(element.clone(), props).unwrap();
+    let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
 
     // Test animation callbacks
-    let mut start_called = false;
-    let mut frame_called = false;
-    let mut end_called = false;
-
     scramble.set_on_animation_start(js_sys::Function::new_no_args("start_called = true;"));
     scramble.set_on_animation_frame(js_sys::Function::new_with_args(
         "text",
@@ -84,34 +85,34 @@ fn test_props_validation() {
     ];
 
     for (speed, tick, chance, case) in invalid_cases {
-        let props = JsValue::from_serde(&UseScrambleProps {
+        let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
             text: "Test".to_string(),
             speed,
             tick,
-            chance,
+            chance: chance.into(),
             ..Default::default()
         })
         .unwrap();
 
         assert!(
-            ScrambleText::new(element.clone(), props).is_err(),
+            ScrambleText::new(element.clone().into(), props).is_err(),
             "Failed to catch {}",
             case
         );
     }
 
     // Test valid props
-    let valid_props = JsValue::from_serde(&UseScrambleProps {
+    let valid_props = serde_wasm_bindgen::to_value(&UseScrambleProps {
         text: "Test".to_string(),
         speed: 0.5,
-        chance: 0.8,
+        chance: 0.8.into(),
         tick: 1,
         ..Default::default()
     })
     .unwrap();
 
     assert!(
-        ScrambleText::new(element, valid_props).is_ok(),
+        ScrambleText::new(element.into(), valid_props).is_ok(),
         "Valid props should work"
     );
 }
@@ -122,15 +123,15 @@ async fn test_overdrive_mode() {
     let element = setup_test_element(&document);
     let original_text = "Test Overdrive";
 
-    let props = JsValue::from_serde(&UseScrambleProps {
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
         text: original_text.to_string(),
-        overdrive: true,
+        overdrive: true.into(),
         speed: 1.0,
         ..Default::default()
     })
     .unwrap();
 
-    let mut scramble = ScrambleText::new(element.clone(), props).unwrap();
+    let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
 
     // Start animation and wait briefly
     scramble.start().unwrap();
@@ -149,3 +150,1894 @@ async fn test_overdrive_mode() {
 
     scramble.stop().unwrap();
 }
+
+#[wasm_bindgen_test]
+async fn test_overflow_disabled_grows_text() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let original_text = "Growing text without overflow";
+
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: original_text.to_string(),
+        overflow: false,
+        tick: 4,
+        speed: 1.0,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
+    scramble.start().unwrap();
+    sleep(50.0).await;
+
+    // Only the characters behind the reveal front are drawn so far
+    let current_text = element.text_content().unwrap();
+    assert!(
+        current_text.chars().count() < original_text.chars().count(),
+        "Text should grow from empty when overflow is disabled"
+    );
+
+    scramble.stop().unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn test_custom_element() {
+    define_custom_element(None).unwrap();
+    // Defining twice is a no-op
+    define_custom_element(None).unwrap();
+
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = document.create_element("scramble-text").unwrap();
+    element.set_attribute("speed", "1").unwrap();
+    element.set_text_content(Some("Custom element"));
+    document.body().unwrap().append_child(&element).unwrap();
+
+    sleep(1000.0).await;
+
+    // The animation is drawn into the shadow root, leaving the light DOM untouched
+    let shadow = element
+        .shadow_root()
+        .expect("Element should have a shadow root");
+    assert_eq!(shadow.text_content().unwrap(), "Custom element");
+    assert_eq!(element.text_content().unwrap(), "Custom element");
+}
+
+#[wasm_bindgen_test]
+async fn test_init_all_from_data_attributes() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let first = setup_test_element(&document);
+    first.set_attribute("data-scramble", "").unwrap();
+    first.set_attribute("data-scramble-speed", "1").unwrap();
+    first.set_text_content(Some("From text content"));
+    let second = setup_test_element(&document);
+    second
+        .set_attribute("data-scramble", "From attribute")
+        .unwrap();
+
+    let mut group = init_all(Some("[data-scramble]".to_string())).unwrap();
+    assert_eq!(group.length(), 2);
+
+    sleep(1000.0).await;
+    assert_eq!(first.text_content().unwrap(), "From text content");
+    assert_eq!(second.text_content().unwrap(), "From attribute");
+
+    group.destroy();
+    assert_eq!(group.length(), 0);
+}
+
+#[wasm_bindgen_test]
+fn test_from_selector() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    element.set_id("from-selector");
+
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "Selected".to_string(),
+        ..Default::default()
+    })
+    .unwrap();
+
+    assert!(ScrambleText::from_selector("#from-selector", props.clone()).is_ok());
+    assert!(ScrambleText::from_selector("#missing-element", props).is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_text_node_target() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let label = document.create_element("b").unwrap();
+    label.set_text_content(Some("Label: "));
+    let text = document.create_text_node("value");
+    element.append_child(&label).unwrap();
+    element.append_child(&text).unwrap();
+
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "scrambled value".to_string(),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let mut scramble = ScrambleText::new(text.clone().into(), props).unwrap();
+    scramble.start().unwrap();
+    sleep(1000.0).await;
+
+    // Only the text node is rewritten; its sibling element survives
+    assert_eq!(text.data(), "scrambled value");
+    assert_eq!(element.first_child().unwrap(), label.into());
+    assert_eq!(element.text_content().unwrap(), "Label: scrambled value");
+}
+
+#[wasm_bindgen_test]
+async fn test_attribute_target() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let input = document.create_element("input").unwrap();
+    document.body().unwrap().append_child(&input).unwrap();
+
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "Search...".to_string(),
+        target: WriteTarget::Attribute("placeholder".to_string()),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let mut scramble = ScrambleText::new(input.clone().into(), props).unwrap();
+    scramble.start().unwrap();
+    sleep(1000.0).await;
+
+    assert_eq!(input.get_attribute("placeholder").unwrap(), "Search...");
+    assert_eq!(input.text_content().unwrap(), "");
+}
+
+#[wasm_bindgen_test]
+async fn test_svg_tspans_are_preserved() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let svg_ns = Some("http://www.w3.org/2000/svg");
+    let svg = document.create_element_ns(svg_ns, "svg").unwrap();
+    let text = document.create_element_ns(svg_ns, "text").unwrap();
+    let first = document.create_element_ns(svg_ns, "tspan").unwrap();
+    first.set_attribute("x", "0 10 20").unwrap();
+    first.set_text_content(Some("abc"));
+    let second = document.create_element_ns(svg_ns, "tspan").unwrap();
+    second.set_attribute("dx", "5").unwrap();
+    second.set_text_content(Some("def"));
+    text.append_child(&first).unwrap();
+    text.append_child(&second).unwrap();
+    svg.append_child(&text).unwrap();
+    document.body().unwrap().append_child(&svg).unwrap();
+
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "abcdef".to_string(),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let mut scramble = ScrambleText::new(text.clone().into(), props).unwrap();
+    scramble.start().unwrap();
+    sleep(1000.0).await;
+
+    assert_eq!(first.text_content().unwrap(), "abc");
+    assert_eq!(second.text_content().unwrap(), "def");
+    assert_eq!(first.get_attribute("x").unwrap(), "0 10 20");
+    assert_eq!(text.child_element_count(), 2);
+}
+
+#[wasm_bindgen_test]
+fn test_svg_tspans_split_frames_by_position() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let svg_ns = Some("http://www.w3.org/2000/svg");
+    let svg = document.create_element_ns(svg_ns, "svg").unwrap();
+    let text = document.create_element_ns(svg_ns, "text").unwrap();
+    let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+    let first = document.create_element_ns(svg_ns, "tspan").unwrap();
+    first.set_text_content(Some(&format!("a{}", family)));
+    let second = document.create_element_ns(svg_ns, "tspan").unwrap();
+    second.set_text_content(Some("def"));
+    text.append_child(&first).unwrap();
+    text.append_child(&second).unwrap();
+    svg.append_child(&text).unwrap();
+    document.body().unwrap().append_child(&svg).unwrap();
+
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: format!("a{}def", family),
+        placeholder_char: Some('_'),
+        ..Default::default()
+    })
+    .unwrap();
+    let mut scramble = ScrambleText::new(text.clone().into(), props).unwrap();
+    scramble.start().unwrap();
+
+    // The emoji scrambles as a single character, without pulling the next tspan's text in
+    while scramble.is_playing() {
+        scramble.run_frames(1);
+        assert_eq!(second.text_content().unwrap().chars().count(), 3);
+    }
+    assert_eq!(first.text_content().unwrap(), format!("a{}", family));
+    assert_eq!(second.text_content().unwrap(), "def");
+    svg.remove();
+}
+
+#[wasm_bindgen_test]
+async fn test_shared_scheduler_drives_many_instances() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let texts = ["First", "Second line", "Third"];
+
+    let mut instances = Vec::new();
+    let mut elements = Vec::new();
+    for text in texts {
+        let element = setup_test_element(&document);
+        let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+            text: text.to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
+        scramble.start().unwrap();
+        instances.push(scramble);
+        elements.push(element);
+    }
+
+    // Stopping one instance must not affect the others on the same loop
+    instances[1].stop().unwrap();
+    sleep(1000.0).await;
+
+    assert_eq!(elements[0].text_content().unwrap(), texts[0]);
+    assert_ne!(elements[1].text_content().unwrap(), texts[1]);
+    assert_eq!(elements[2].text_content().unwrap(), texts[2]);
+}
+
+#[wasm_bindgen_test]
+async fn test_disposes_when_target_is_removed() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "Removed while animating".to_string(),
+        speed: 0.5,
+        ..Default::default()
+    })
+    .unwrap();
+    let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
+    scramble.set_on_dispose(js_sys::Function::new_no_args("dispose_called = true;"));
+    scramble.start().unwrap();
+
+    sleep(100.0).await;
+    element.remove();
+    sleep(100.0).await;
+
+    assert!(js_sys::eval("dispose_called").unwrap().as_bool().unwrap());
+    assert!(scramble.start().is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_dispose() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "Disposed".to_string(),
+        hover_replay: true,
+        ..Default::default()
+    })
+    .unwrap();
+    let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
+    scramble.set_on_dispose(js_sys::Function::new_no_args(
+        "disposed_count = (window.disposed_count || 0) + 1;",
+    ));
+    scramble.start().unwrap();
+
+    scramble.dispose();
+    scramble.destroy();
+    assert_eq!(js_sys::eval("disposed_count").unwrap().as_f64(), Some(1.0));
+    assert!(scramble.start().is_err());
+
+    // Hovering no longer replays the animation
+    element.set_text_content(Some("Untouched"));
+    let event = web_sys::MouseEvent::new("mouseenter").unwrap();
+    element.dispatch_event(&event).unwrap();
+    sleep(100.0).await;
+    assert_eq!(element.text_content().unwrap(), "Untouched");
+}
+
+#[wasm_bindgen_test]
+async fn test_pause_resume_and_stop_all() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let text = "Paused globally";
+
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: text.to_string(),
+        speed: 0.5,
+        ..Default::default()
+    })
+    .unwrap();
+    let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
+    scramble.start().unwrap();
+    sleep(100.0).await;
+
+    // Nothing is drawn while paused
+    pause_all();
+    let frozen = element.text_content().unwrap();
+    sleep(200.0).await;
+    assert_eq!(element.text_content().unwrap(), frozen);
+
+    resume_all();
+    sleep(2000.0).await;
+    assert_eq!(element.text_content().unwrap(), text);
+
+    // Stopping leaves the current frame in place
+    scramble.start().unwrap();
+    sleep(100.0).await;
+    stop_all().unwrap();
+    let stopped = element.text_content().unwrap();
+    sleep(200.0).await;
+    assert_eq!(element.text_content().unwrap(), stopped);
+}
+
+#[wasm_bindgen_test]
+async fn test_start_with_abort_signal() {
+    use wasm_bindgen::JsCast;
+
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "Aborted".to_string(),
+        speed: 0.5,
+        ..Default::default()
+    })
+    .unwrap();
+    let mut scramble = ScrambleText::new(element.into(), props).unwrap();
+    scramble.set_on_animation_end(js_sys::Function::new_no_args("abort_end_called = true;"));
+
+    // A completed run resolves
+    let promise = scramble.start_js(None).unwrap();
+    assert!(JsFuture::from(promise).await.is_ok());
+
+    let controller = js_sys::eval("new AbortController()").unwrap();
+    let signal = js_sys::Reflect::get(&controller, &"signal".into()).unwrap();
+    let abort: js_sys::Function = js_sys::Reflect::get(&controller, &"abort".into())
+        .unwrap()
+        .unchecked_into();
+
+    let promise = scramble.start_js(Some(signal.unchecked_into())).unwrap();
+    sleep(50.0).await;
+    abort.call0(&controller).unwrap();
+
+    assert!(JsFuture::from(promise).await.is_err());
+    assert!(js_sys::eval("abort_end_called").unwrap().as_bool().unwrap());
+}
+
+#[wasm_bindgen_test]
+async fn test_a11y_keeps_final_text_for_screen_readers() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let text = "Accessible";
+
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: text.to_string(),
+        a11y: true,
+        ..Default::default()
+    })
+    .unwrap();
+    let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
+    scramble.start().unwrap();
+
+    assert_eq!(
+        element.get_attribute("aria-hidden").as_deref(),
+        Some("true")
+    );
+    let label = element.next_element_sibling().unwrap();
+    assert_eq!(label.text_content().unwrap(), text);
+
+    // Normal semantics return once the text is revealed
+    sleep(1000.0).await;
+    assert_eq!(element.text_content().unwrap(), text);
+    assert!(element.get_attribute("aria-hidden").is_none());
+    assert!(element.next_element_sibling().is_none());
+}
+
+#[wasm_bindgen_test]
+async fn test_announce_on_complete() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let text = "Announced once";
+
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: text.to_string(),
+        aria_live: Some(AriaLive::Assertive),
+        announce_on_complete: true,
+        ..Default::default()
+    })
+    .unwrap();
+    let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
+
+    // The live region sits next to the target, which itself is not live
+    let region = element.next_element_sibling().unwrap();
+    assert_eq!(
+        region.get_attribute("aria-live").as_deref(),
+        Some("assertive")
+    );
+    assert!(element.get_attribute("aria-live").is_none());
+
+    scramble.start().unwrap();
+    sleep(100.0).await;
+    assert_eq!(region.text_content().unwrap(), "");
+
+    sleep(1000.0).await;
+    assert_eq!(region.text_content().unwrap(), text);
+}
+
+#[wasm_bindgen_test]
+async fn test_click_and_keyboard_triggers() {
+    use wasm_bindgen::JsCast;
+
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let text = "Triggered";
+
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: text.to_string(),
+        triggers: vec![Trigger::Click, Trigger::Keydown],
+        ..Default::default()
+    })
+    .unwrap();
+    let _scramble = ScrambleText::new(element.clone().into(), props).unwrap();
+
+    element
+        .dispatch_event(&web_sys::Event::new("click").unwrap())
+        .unwrap();
+    sleep(50.0).await;
+    assert_ne!(element.text_content().unwrap(), "");
+    sleep(1000.0).await;
+    assert_eq!(element.text_content().unwrap(), text);
+
+    // Only Enter and Space replay from the keyboard
+    element.set_text_content(Some("Idle"));
+    let key = js_sys::eval("new KeyboardEvent('keydown', { key: 'a' })").unwrap();
+    element.dispatch_event(key.unchecked_ref()).unwrap();
+    sleep(50.0).await;
+    assert_eq!(element.text_content().unwrap(), "Idle");
+
+    let key = js_sys::eval("new KeyboardEvent('keydown', { key: 'Enter' })").unwrap();
+    element.dispatch_event(key.unchecked_ref()).unwrap();
+    sleep(50.0).await;
+    assert_ne!(element.text_content().unwrap(), "Idle");
+}
+
+#[wasm_bindgen_test]
+async fn test_bind_to_timeline() {
+    use wasm_bindgen::JsCast;
+
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let text = "Timeline";
+
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: text.to_string(),
+        ..Default::default()
+    })
+    .unwrap();
+    let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
+
+    let animation = js_sys::eval(
+        "(() => { const a = document.body.animate([], { duration: 1000, fill: 'both' }); \
+         a.pause(); a.currentTime = 0; return a; })()",
+    )
+    .unwrap()
+    .unchecked_into::<web_sys::Animation>();
+    scramble.bind_to_timeline(animation.clone()).unwrap();
+
+    // The reveal follows the animation's progress rather than time
+    animation.set_current_time(Some(1000.0));
+    sleep(50.0).await;
+    assert_eq!(element.text_content().unwrap(), text);
+
+    animation.set_current_time(Some(0.0));
+    sleep(50.0).await;
+    assert_ne!(element.text_content().unwrap(), text);
+
+    scramble.stop().unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn test_timeline_staggers_starts() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use wasm_bindgen::prelude::*;
+
+    let document = web_sys::window().unwrap().document().unwrap();
+    let first = setup_test_element(&document);
+    let second = setup_test_element(&document);
+    let props = |text: &str| UseScrambleProps {
+        text: text.to_string(),
+        ..Default::default()
+    };
+
+    let mut timeline = ScrambleTimeline::new()
+        .add_props(first.clone().into(), props("First"), 0.0)
+        .unwrap()
+        .add_props(second.clone().into(), props("Second"), 300.0)
+        .unwrap();
+    assert_eq!(timeline.length(), 2);
+    assert!(timeline.duration() > 300.0);
+
+    let completed = Rc::new(Cell::new(0));
+    let on_complete = {
+        let completed = completed.clone();
+        Closure::<dyn FnMut()>::new(move || completed.set(completed.get() + 1))
+    };
+    timeline.set_on_complete(on_complete.as_ref().clone().into());
+    timeline.play().unwrap();
+
+    sleep(100.0).await;
+    assert_ne!(first.text_content().unwrap(), "");
+    assert_eq!(second.text_content().unwrap(), "");
+
+    sleep(1500.0).await;
+    assert_eq!(first.text_content().unwrap(), "First");
+    assert_eq!(second.text_content().unwrap(), "Second");
+    assert_eq!(completed.get(), 1);
+}
+
+#[wasm_bindgen_test]
+async fn test_then_text_queues_follow_up() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "Headline".to_string(),
+        ..Default::default()
+    })
+    .unwrap();
+    let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
+    scramble.start().unwrap();
+
+    let overrides = js_sys::eval("({ speed: 0.5 })").unwrap();
+    scramble
+        .then_text("Subtitle".to_string(), overrides)
+        .unwrap();
+
+    sleep(1000.0).await;
+    assert_ne!(element.text_content().unwrap(), "Headline");
+    sleep(2000.0).await;
+    assert_eq!(element.text_content().unwrap(), "Subtitle");
+
+    // Stopping drops whatever is still queued
+    scramble.start().unwrap();
+    scramble
+        .then_text("Dropped".to_string(), wasm_bindgen::JsValue::undefined())
+        .unwrap();
+    scramble.stop().unwrap();
+    sleep(1000.0).await;
+    assert_ne!(element.text_content().unwrap(), "Dropped");
+}
+
+#[wasm_bindgen_test]
+async fn test_group_linear_stagger() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let elements: Vec<_> = (0..3)
+        .map(|i| {
+            let element = setup_test_element(&document);
+            element.set_attribute("data-staggered", "").unwrap();
+            element
+                .set_attribute("data-scramble", &format!("Card {}", i))
+                .unwrap();
+            element
+                .set_attribute("data-scramble-play-on-mount", "false")
+                .unwrap();
+            element
+        })
+        .collect();
+
+    let mut group = init_all(Some("[data-staggered]".to_string())).unwrap();
+    group.stagger("linear", 400.0).unwrap();
+    assert!(group.stagger("sideways", 400.0).is_err());
+    group.start().unwrap();
+
+    sleep(100.0).await;
+    assert_ne!(elements[0].text_content().unwrap(), "");
+    assert_eq!(elements[2].text_content().unwrap(), "");
+
+    sleep(1500.0).await;
+    assert_eq!(elements[2].text_content().unwrap(), "Card 2");
+    group.destroy();
+    elements.iter().for_each(Element::remove);
+}
+
+#[wasm_bindgen_test]
+async fn test_stop_all_cancels_staggered_starts() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let elements: Vec<_> = (0..3)
+        .map(|i| {
+            let element = setup_test_element(&document);
+            element.set_attribute("data-stopped", "").unwrap();
+            element
+                .set_attribute("data-scramble", &format!("Card {}", i))
+                .unwrap();
+            element
+                .set_attribute("data-scramble-play-on-mount", "false")
+                .unwrap();
+            element
+        })
+        .collect();
+
+    let mut group = init_all(Some("[data-stopped]".to_string())).unwrap();
+    group.stagger("linear", 400.0).unwrap();
+    group.start().unwrap();
+    sleep(100.0).await;
+
+    // The starts still due never happen
+    stop_all().unwrap();
+    let stopped = elements[0].text_content().unwrap();
+    sleep(1500.0).await;
+    assert_eq!(elements[0].text_content().unwrap(), stopped);
+    assert_eq!(elements[1].text_content().unwrap(), "");
+    assert_eq!(elements[2].text_content().unwrap(), "");
+    group.destroy();
+    elements.iter().for_each(Element::remove);
+}
+
+#[wasm_bindgen_test]
+async fn test_update_props_while_running() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "Updated".to_string(),
+        ..Default::default()
+    })
+    .unwrap();
+    let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
+    scramble.start().unwrap();
+
+    // Pausing through the props holds the current frame
+    scramble
+        .update_props(js_sys::eval("({ speed: 0 })").unwrap())
+        .unwrap();
+    sleep(1000.0).await;
+    assert_ne!(element.text_content().unwrap(), "Updated");
+
+    assert!(scramble
+        .update_props(js_sys::eval("({ chance: 2 })").unwrap())
+        .is_err());
+
+    // A new text restarts the animation
+    scramble
+        .update_props(js_sys::eval("({ speed: 1, text: 'Replaced' })").unwrap())
+        .unwrap();
+    sleep(1000.0).await;
+    assert_eq!(element.text_content().unwrap(), "Replaced");
+}
+
+#[wasm_bindgen_test]
+async fn test_live_setters() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "Adjusted".to_string(),
+        ..Default::default()
+    })
+    .unwrap();
+    let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
+    scramble.start().unwrap();
+    scramble.set_speed(0.0).unwrap();
+    sleep(500.0).await;
+    assert_ne!(element.text_content().unwrap(), "Adjusted");
+
+    assert!(scramble.set_chance(1.5).is_err());
+    assert!(scramble.set_range(126, 33).is_err());
+    scramble.set_range(48, 57).unwrap();
+
+    // Speeding up again resumes the paused run
+    scramble.set_speed(1.0).unwrap();
+    sleep(1000.0).await;
+    assert_eq!(element.text_content().unwrap(), "Adjusted");
+}
+
+#[wasm_bindgen_test]
+async fn test_getters() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "Inspect".to_string(),
+        speed: 0.5,
+        ..Default::default()
+    })
+    .unwrap();
+    let mut scramble = ScrambleText::new(element.into(), props).unwrap();
+    assert_eq!(scramble.text(), "Inspect");
+    assert_eq!(scramble.speed(), 0.5);
+    assert!(!scramble.is_playing());
+
+    // Unset props are read back with their defaults
+    let props: UseScrambleProps =
+        serde_wasm_bindgen::from_value(scramble.props().unwrap()).unwrap();
+    assert_eq!(props.tick, 1);
+
+    scramble.start().unwrap();
+    assert!(scramble.is_playing());
+    sleep(2000.0).await;
+    assert!(!scramble.is_playing());
+    assert_eq!(scramble.progress(), 1.0);
+    assert!(scramble.frame_count() > 0);
+}
+
+#[wasm_bindgen_test]
+fn test_errors_carry_codes() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+
+    let props = js_sys::eval("({ text: 'Coded', speed: 2 })").unwrap();
+    let error: wasm_bindgen::JsValue = ScrambleText::new(element.into(), props)
+        .err()
+        .unwrap()
+        .into();
+    let code = js_sys::Reflect::get(&error, &"code".into()).unwrap();
+    assert_eq!(code.as_string().as_deref(), Some("invalid_speed"));
+}
+
+#[wasm_bindgen_test]
+async fn test_options_class() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+
+    let mut options = ScrambleOptions::new(Some("Typed".to_string()));
+    options.set_speed(0.8);
+    options.set_range("48-57").unwrap();
+    options.set_triggers(vec!["click".to_string()]).unwrap();
+    assert!(options.set_range("digits").is_err());
+    assert_eq!(options.range(), "48-57");
+
+    let options = wasm_bindgen::JsValue::from(options);
+    let mut scramble = ScrambleText::new(element.clone().into(), options.clone()).unwrap();
+    assert_eq!(scramble.speed(), 0.8);
+
+    // The options stay usable after being passed in
+    assert!(ScrambleText::new(setup_test_element(&document).into(), options).is_ok());
+
+    scramble.start().unwrap();
+    sleep(1000.0).await;
+    assert_eq!(element.text_content().unwrap(), "Typed");
+}
+
+#[wasm_bindgen_test]
+fn test_unknown_props_rejected() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+
+    let props = js_sys::eval("({ text: 'Strict', scrample: 4 })").unwrap();
+    let error: wasm_bindgen::JsValue = ScrambleText::new(element.clone().into(), props)
+        .err()
+        .unwrap()
+        .into();
+    let code = js_sys::Reflect::get(&error, &"code".into()).unwrap();
+    assert_eq!(code.as_string().as_deref(), Some("unknown_prop"));
+
+    // Opting out ignores the typo
+    let props = js_sys::eval("({ text: 'Lenient', scrample: 4, strict: false })").unwrap();
+    assert!(ScrambleText::new(element.into(), props).is_ok());
+}
+
+#[cfg(feature = "yew")]
+#[wasm_bindgen_test]
+async fn test_yew_component() {
+    use scramble_text::{Scramble, ScrambleProps};
+
+    let document = web_sys::window().unwrap().document().unwrap();
+    let root = setup_test_element(&document);
+
+    let props = yew::props!(ScrambleProps {
+        text: "Hello Yew",
+        speed: 1.0,
+    });
+    let app = yew::Renderer::<Scramble>::with_root_and_props(root.clone(), props).render();
+    sleep(1000.0).await;
+
+    let span = root.first_element_child().unwrap();
+    assert_eq!(span.text_content().unwrap(), "Hello Yew");
+
+    // Unmounting stops the animation and removes the span
+    app.destroy();
+    sleep(50.0).await;
+    assert!(root.first_element_child().is_none());
+}
+
+#[cfg(feature = "leptos")]
+#[wasm_bindgen_test]
+async fn test_leptos_hook() {
+    use leptos::prelude::*;
+    use scramble_text::leptos::use_scramble;
+    use wasm_bindgen::JsCast;
+
+    let document = web_sys::window().unwrap().document().unwrap();
+    let root = setup_test_element(&document);
+
+    let (text, set_text) = signal("Hello".to_string());
+    let handle = leptos::mount::mount_to(root.clone().unchecked_into(), move || {
+        let (node_ref, _play, _stop) =
+            use_scramble::<leptos::html::Span>(UseScrambleProps::default(), text);
+        view! { <span node_ref=node_ref></span> }
+    });
+    sleep(1000.0).await;
+    assert_eq!(root.text_content().unwrap(), "Hello");
+
+    // Changing the signal replays the animation with the new text
+    set_text.set("Leptos".to_string());
+    sleep(1000.0).await;
+    assert_eq!(root.text_content().unwrap(), "Leptos");
+
+    drop(handle);
+}
+
+#[wasm_bindgen_test]
+async fn test_fluent_api() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use wasm_bindgen::prelude::*;
+
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    element.set_id("fluent-target");
+    element.set_text_content(Some("Fluent"));
+
+    let frames = Rc::new(Cell::new(0));
+    let on_frame = {
+        let frames = frames.clone();
+        Closure::<dyn FnMut(String)>::new(move |_text: String| frames.set(frames.get() + 1))
+    };
+
+    // The text defaults to the target's current text
+    let _scramble = ScrambleText::create("#fluent-target".into(), JsValue::UNDEFINED)
+        .unwrap()
+        .on_frame(
+            on_frame
+                .as_ref()
+                .unchecked_ref::<js_sys::Function>()
+                .clone(),
+        )
+        .play()
+        .unwrap();
+
+    sleep(1000.0).await;
+    assert_eq!(element.text_content().unwrap(), "Fluent");
+    assert!(frames.get() > 0);
+}
+
+#[wasm_bindgen_test]
+async fn test_frame_player() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+
+    let recording = js_sys::JSON::parse(
+        r#"{ "frames": [
+            { "time": 0, "text": "a_c" },
+            { "time": 100, "text": "ab_" },
+            { "time": 200, "text": "abc" }
+        ] }"#,
+    )
+    .unwrap();
+    let mut player = FramePlayer::new(element.clone().into(), recording).unwrap();
+    assert_eq!(player.length(), 3);
+    assert_eq!(player.duration(), 200.0);
+
+    player.play().unwrap();
+    assert_eq!(element.text_content().unwrap(), "a_c");
+    assert!(player.is_playing());
+
+    sleep(500.0).await;
+    assert_eq!(element.text_content().unwrap(), "abc");
+    assert!(!player.is_playing());
+}
+
+#[wasm_bindgen_test]
+async fn test_hydrate() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    element
+        .set_attribute("data-scramble-final", "Hydrated")
+        .unwrap();
+    element.set_text_content(Some("Hyd#####"));
+
+    let scramble = ScrambleText::hydrate(element.clone()).unwrap();
+    assert_eq!(scramble.text(), "Hydrated");
+    assert!(scramble.is_playing());
+    // Resumes from the revealed prefix rather than the first frame
+    assert!(scramble.progress() >= 3.0 / 8.0);
+
+    sleep(1000.0).await;
+    assert_eq!(element.text_content().unwrap(), "Hydrated");
+}
+
+#[wasm_bindgen_test]
+fn test_unchanged_frames_are_not_rewritten() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "Hello".to_string(),
+        ..Default::default()
+    })
+    .unwrap();
+    let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
+
+    let observer = web_sys::MutationObserver::new(&js_sys::Function::new_no_args("")).unwrap();
+    let options = web_sys::MutationObserverInit::new();
+    options.set_child_list(true);
+    options.set_character_data(true);
+    options.set_subtree(true);
+    observer.observe_with_options(&element, &options).unwrap();
+
+    scramble.seek(1.0).unwrap();
+    assert_eq!(observer.take_records().length(), 1);
+
+    // The same frame again leaves the DOM alone
+    scramble.seek(1.0).unwrap();
+    assert_eq!(observer.take_records().length(), 0);
+    assert_eq!(element.text_content().unwrap(), "Hello");
+    observer.disconnect();
+}
+
+#[wasm_bindgen_test]
+async fn test_idle_runs_complete() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "Idle".to_string(),
+        idle: true,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
+    scramble.start().unwrap();
+    assert!(scramble.is_playing());
+
+    sleep(1500.0).await;
+    assert_eq!(element.text_content().unwrap(), "Idle");
+    assert!(!scramble.is_playing());
+}
+
+#[wasm_bindgen_test]
+async fn test_virtualized_long_text() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let text = "Lorem ipsum dolor sit amet.\n".repeat(40);
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: text.clone(),
+        virtualize: true,
+        step: 20,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
+    scramble.start().unwrap();
+    // One chunk per line
+    assert_eq!(element.child_element_count(), 40);
+
+    sleep(1500.0).await;
+    assert_eq!(element.text_content().unwrap(), text);
+}
+
+#[cfg(feature = "overlay")]
+#[wasm_bindgen_test]
+fn test_overlay_panel() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "Overlay".to_string(),
+        overlay: true,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let mut scramble = ScrambleText::new(element.into(), props).unwrap();
+    let panel = document
+        .query_selector("[data-scramble-overlay] pre")
+        .unwrap();
+    assert!(panel
+        .unwrap()
+        .text_content()
+        .unwrap()
+        .contains("\"Overlay\""));
+
+    scramble.dispose();
+    assert!(document
+        .query_selector("[data-scramble-overlay]")
+        .unwrap()
+        .is_none());
+}
+
+#[cfg(feature = "test-hooks")]
+#[wasm_bindgen_test]
+fn test_fake_clock_and_seeded_runs() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "Hello".to_string(),
+        speed: 0.5,
+        ..Default::default()
+    })
+    .unwrap();
+    let run = || {
+        let element = setup_test_element(&document);
+        let mut scramble = ScrambleText::new(element.clone().into(), props.clone()).unwrap();
+        scramble.use_fake_clock();
+        scramble.seed_random(42);
+        scramble.start().unwrap();
+
+        // At half speed a frame is due every 33.3ms
+        assert!(!scramble.advance_clock(20.0));
+        assert!(scramble.advance_clock(20.0));
+        let mut frames = vec![element.text_content().unwrap()];
+        while scramble.is_playing() {
+            frames.push(scramble.step_frame());
+        }
+        frames
+    };
+
+    let frames = run();
+    assert_eq!(frames.last().unwrap(), "Hello");
+    assert_eq!(frames, run());
+}
+
+#[cfg(feature = "test-hooks")]
+#[wasm_bindgen_test]
+fn test_worker_protocol() {
+    use scramble_text::ScrambleWorker;
+    use wasm_bindgen::JsCast;
+
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "Worker".to_string(),
+        ..Default::default()
+    })
+    .unwrap();
+    js_sys::eval("worker_ended = [];").unwrap();
+    let ended = || js_sys::eval("worker_ended.join(',')").unwrap().as_string();
+
+    let mut worker = ScrambleWorker::in_thread();
+    worker.set_on_animation_end(js_sys::Function::new_with_args(
+        "id",
+        "worker_ended.push(id);",
+    ));
+
+    // Frames come back to the main thread and are written to the target
+    let id = worker
+        .scramble(element.clone().into(), props.clone())
+        .unwrap();
+    worker.advance_clock(20.0).unwrap();
+    assert_ne!(element.text_content().unwrap(), "");
+    for _ in 0..1000 {
+        worker.advance_clock(20.0).unwrap();
+    }
+    assert_eq!(element.text_content().unwrap(), "Worker");
+    assert_eq!(ended(), Some(id.to_string()));
+
+    // A stopped animation keeps its last frame and never reports an end
+    let stopped = worker
+        .scramble(element.clone().into(), props.clone())
+        .unwrap();
+    worker.advance_clock(20.0).unwrap();
+    worker.stop(stopped).unwrap();
+    let frame = element.text_content().unwrap();
+    worker.advance_clock(1000.0).unwrap();
+    assert_eq!(element.text_content().unwrap(), frame);
+    assert_eq!(ended(), Some(id.to_string()));
+
+    // Canvas frames stay with the jobs, and only the end is posted back
+    let canvas = document
+        .create_element("canvas")
+        .unwrap()
+        .unchecked_into::<web_sys::HtmlCanvasElement>();
+    let on_canvas = worker
+        .scramble_canvas(canvas, props, Some("24px monospace".into()), None)
+        .unwrap();
+    worker.advance_clock(20.0).unwrap();
+    assert_eq!(ended(), Some(id.to_string()));
+    for _ in 0..1000 {
+        worker.advance_clock(20.0).unwrap();
+    }
+    assert_eq!(ended(), Some(format!("{},{}", id, on_canvas)));
+    worker.terminate();
+}
+
+#[wasm_bindgen_test]
+fn test_run_frames() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "Frames".to_string(),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
+    // Nothing to draw before a run starts
+    scramble.run_frames(3);
+    assert_eq!(scramble.frame_count(), 0);
+    scramble.start().unwrap();
+    let text = scramble.run_frames(3);
+    assert_eq!(scramble.frame_count(), 3);
+    assert_eq!(element.text_content().unwrap(), text);
+
+    assert_eq!(scramble.run_frames(1000), "Frames");
+    assert!(!scramble.is_playing());
+}
+
+#[wasm_bindgen_test]
+fn test_export_and_import_state() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "Route change".to_string(),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let mut scramble =
+        ScrambleText::new(setup_test_element(&document).into(), props.clone()).unwrap();
+    scramble.start().unwrap();
+    scramble.run_frames(5);
+    let state = scramble.export_state().unwrap();
+    scramble.dispose();
+
+    let element = setup_test_element(&document);
+    let mut resumed = ScrambleText::new(element.clone().into(), props).unwrap();
+    resumed.import_state(state).unwrap();
+    assert_eq!(element.text_content().unwrap().chars().count(), 12);
+    resumed.start().unwrap();
+    assert_eq!(resumed.frame_count(), 5);
+    assert_eq!(resumed.run_frames(1000), "Route change");
+}
+
+#[wasm_bindgen_test]
+fn test_obfuscate_contact() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let link = document.create_element("a").unwrap();
+    link.set_attribute("href", "mailto:jane@example.com")
+        .unwrap();
+    document.body().unwrap().append_child(&link).unwrap();
+
+    let value = "jane@example.com";
+    let mut scramble =
+        scramble_text::obfuscate_contact(link.clone(), value.into(), None, None).unwrap();
+    // Neither the text nor the link gives the value away before the reveal
+    let noise = link.text_content().unwrap();
+    assert_eq!(noise.chars().count(), value.chars().count());
+    assert!(!noise.contains('@'));
+    assert!(!link.has_attribute("href"));
+
+    link.dispatch_event(&web_sys::Event::new("click").unwrap())
+        .unwrap();
+    assert_eq!(scramble.run_frames(1000), value);
+    assert_eq!(
+        link.get_attribute("href").unwrap(),
+        "mailto:jane@example.com"
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_spoiler() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "Snape kills Dumbledore".to_string(),
+        spoiler: true,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
+    assert_eq!(element.get_attribute("role").unwrap(), "button");
+    assert_eq!(element.get_attribute("aria-pressed").unwrap(), "false");
+    assert_ne!(element.text_content().unwrap(), "Snape kills Dumbledore");
+
+    element
+        .dispatch_event(&web_sys::Event::new("click").unwrap())
+        .unwrap();
+    assert_eq!(element.get_attribute("aria-pressed").unwrap(), "true");
+    assert_eq!(scramble.run_frames(1000), "Snape kills Dumbledore");
+
+    // Leaving the spoiler scrambles it back, from the end of the text
+    element
+        .dispatch_event(&web_sys::Event::new("mouseleave").unwrap())
+        .unwrap();
+    assert_eq!(element.get_attribute("aria-pressed").unwrap(), "false");
+    assert!(scramble.run_frames(3).starts_with("Snape"));
+    scramble.run_frames(1000);
+    assert!(!scramble.is_playing());
+    let concealed = element.text_content().unwrap();
+    assert_eq!(concealed.chars().count(), 22);
+    assert_ne!(concealed, "Snape kills Dumbledore");
+}
+
+#[wasm_bindgen_test]
+fn test_set_masked() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "hunter2".to_string(),
+        mask: Some('•'),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
+    scramble.start().unwrap();
+    scramble.run_frames(2);
+    assert!(scramble.masked());
+
+    // Unmasking jumps to the end of the run
+    scramble.set_masked(false).unwrap();
+    assert!(!scramble.is_playing());
+    assert_eq!(element.text_content().unwrap(), "hunter2");
+    scramble.set_masked(true).unwrap();
+    assert_eq!(element.text_content().unwrap(), "•••••••");
+}
+
+#[wasm_bindgen_test]
+async fn test_transition_text() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    element.set_text_content(Some("Loading"));
+
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        speed: 1.0,
+        ..Default::default()
+    })
+    .unwrap();
+    let done = transition_text(element.clone(), "Welcome back".into(), props).unwrap();
+    sleep(50.0).await;
+    assert_ne!(element.text_content().unwrap(), "Loading");
+
+    JsFuture::from(done).await.unwrap();
+    assert_eq!(element.text_content().unwrap(), "Welcome back");
+}
+
+#[wasm_bindgen_test]
+fn test_js_plugin() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "Hello".to_string(),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
+    let plugin = js_sys::Function::new_with_args(
+        "text, states",
+        "return text.toLowerCase() + states.length;",
+    );
+    scramble.add_plugin(plugin);
+    scramble.start().unwrap();
+    assert_eq!(scramble.run_frames(1000), "hello5");
+    assert_eq!(element.text_content().unwrap(), "hello5");
+}
+
+#[wasm_bindgen_test]
+fn test_settle_sound() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use wasm_bindgen::prelude::*;
+
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "Hello".to_string(),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let mut scramble = ScrambleText::new(element.into(), props).unwrap();
+    let settled = Rc::new(RefCell::new(Vec::new()));
+    let on_settle = {
+        let settled = settled.clone();
+        Closure::<dyn FnMut(Vec<u32>)>::new(move |indices: Vec<u32>| {
+            settled.borrow_mut().extend(indices)
+        })
+    };
+    scramble.set_on_settle_sound(on_settle.as_ref().clone().into());
+    scramble.start().unwrap();
+    scramble.run_frames(1000);
+
+    let mut indices = settled.borrow().clone();
+    indices.sort();
+    indices.dedup();
+    assert_eq!(indices, vec![0, 1, 2, 3, 4]);
+}
+
+#[wasm_bindgen_test]
+fn test_css_vars() {
+    use wasm_bindgen::JsCast;
+
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "Hello".to_string(),
+        css_vars: true,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
+    let style = element.dyn_into::<web_sys::HtmlElement>().unwrap().style();
+    scramble.start().unwrap();
+    scramble.run_frames(2);
+    let progress: f64 = style
+        .get_property_value("--scramble-progress")
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(progress < 1.0);
+
+    scramble.run_frames(1000);
+    assert_eq!(
+        style.get_property_value("--scramble-progress").unwrap(),
+        "1"
+    );
+    assert_eq!(
+        style.get_property_value("--scramble-active-index").unwrap(),
+        "5"
+    );
+
+    scramble.dispose();
+    assert_eq!(style.get_property_value("--scramble-progress").unwrap(), "");
+}
+
+#[wasm_bindgen_test]
+fn test_spans() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "Hello".to_string(),
+        spans: true,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
+    scramble.start().unwrap();
+    scramble.run_frames(1);
+    let scrambling = element
+        .query_selector_all("span[data-state=scrambling]")
+        .unwrap();
+    assert!(scrambling.length() > 0);
+
+    scramble.run_frames(1000);
+    assert_eq!(element.text_content().unwrap(), "Hello");
+    assert_eq!(element.child_element_count(), 5);
+    let settled = element
+        .query_selector_all("span[data-state=settled]")
+        .unwrap();
+    assert_eq!(settled.length(), 5);
+    // The last character to settle did so in the final frame
+    assert!(element
+        .query_selector("span[data-age='0']")
+        .unwrap()
+        .is_some());
+}
+
+#[wasm_bindgen_test]
+fn test_chance_function() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let props = js_sys::eval("({ text: 'abcd', chance: (i, n) => (i < n / 2 ? 1 : 0) })").unwrap();
+
+    let scramble = ScrambleText::new(element.into(), props).unwrap();
+    let props: UseScrambleProps =
+        serde_wasm_bindgen::from_value(scramble.props().unwrap()).unwrap();
+    assert_eq!(props.chance, vec![1.0, 1.0, 0.0, 0.0].into());
+}
+
+#[wasm_bindgen_test]
+async fn test_end_fires_on_natural_completion() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "Done".to_string(),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
+    js_sys::eval("natural_end_calls = 0;").unwrap();
+    scramble.set_on_animation_end(js_sys::Function::new_no_args("natural_end_calls += 1;"));
+    let promise = scramble.start_js(None).unwrap();
+
+    // Never stopped: the run ends by itself once every character has settled
+    scramble.run_frames(1000);
+    assert_eq!(element.text_content().unwrap(), "Done");
+    assert!(!scramble.is_playing());
+    assert_eq!(
+        js_sys::eval("natural_end_calls").unwrap().as_f64(),
+        Some(1.0)
+    );
+    assert!(JsFuture::from(promise).await.is_ok());
+
+    // Stopping afterwards doesn't end the run a second time
+    scramble.stop().unwrap();
+    assert_eq!(
+        js_sys::eval("natural_end_calls").unwrap().as_f64(),
+        Some(1.0)
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_play_state() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "State".to_string(),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let mut scramble = ScrambleText::new(element.into(), props).unwrap();
+    assert_eq!(scramble.play_state(), PlayState::Idle);
+
+    scramble.start().unwrap();
+    assert_eq!(scramble.play_state(), PlayState::Playing);
+    pause_all();
+    assert_eq!(scramble.play_state(), PlayState::Paused);
+    resume_all();
+    assert_eq!(scramble.play_state(), PlayState::Playing);
+
+    scramble.run_frames(1000);
+    assert_eq!(scramble.play_state(), PlayState::Completed);
+    assert_eq!(scramble.play_state_js(), "completed");
+
+    scramble.start().unwrap();
+    scramble.stop().unwrap();
+    assert_eq!(scramble.play_state(), PlayState::Idle);
+
+    scramble.dispose();
+    assert_eq!(scramble.play_state(), PlayState::Disposed);
+}
+
+#[wasm_bindgen_test]
+async fn test_pause_when_hidden() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let props = |pause_when_hidden| {
+        serde_wasm_bindgen::to_value(&UseScrambleProps {
+            text: "A hidden tab keeps its place in the text".to_string(),
+            speed: 0.5,
+            pause_when_hidden,
+            ..Default::default()
+        })
+        .unwrap()
+    };
+    // Browsers under test are never really hidden, so `document.hidden` is stubbed
+    let set_hidden = |hidden: bool| {
+        js_sys::eval(&format!(
+            "Object.defineProperty(document, 'hidden', {{ configurable: true, get: () => {} }});",
+            hidden
+        ))
+        .unwrap();
+        let event = web_sys::Event::new("visibilitychange").unwrap();
+        document.dispatch_event(&event).unwrap();
+    };
+
+    let mut paused = ScrambleText::new(setup_test_element(&document).into(), props(true)).unwrap();
+    let mut running =
+        ScrambleText::new(setup_test_element(&document).into(), props(false)).unwrap();
+    paused.start().unwrap();
+    running.start().unwrap();
+    sleep(100.0).await;
+
+    set_hidden(true);
+    assert_eq!(paused.play_state(), PlayState::Paused);
+    assert_eq!(running.play_state(), PlayState::Playing);
+    let (paused_frames, running_frames) = (paused.frame_count(), running.frame_count());
+    sleep(300.0).await;
+    // Runs that don't pause are driven by a timer while hidden
+    assert_eq!(paused.frame_count(), paused_frames);
+    assert!(running.frame_count() > running_frames);
+
+    // The paused run picks up where it left off, without catching up on the hidden time
+    set_hidden(false);
+    assert_eq!(paused.play_state(), PlayState::Playing);
+    sleep(50.0).await;
+    assert!(paused.frame_count() > paused_frames);
+    assert!(paused.frame_count() <= paused_frames + 4);
+
+    js_sys::eval("delete document.hidden;").unwrap();
+    paused.dispose();
+    running.dispose();
+}
+
+#[wasm_bindgen_test]
+async fn test_pause_on_blur() {
+    let window = web_sys::window().unwrap();
+    let document = window.document().unwrap();
+    let element = setup_test_element(&document);
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "Blurred window".to_string(),
+        speed: 0.5,
+        pause_on_blur: true,
+        ..Default::default()
+    })
+    .unwrap();
+    let focus = |event: &str| {
+        let event = web_sys::Event::new(event).unwrap();
+        window.dispatch_event(&event).unwrap();
+    };
+
+    let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
+    scramble.start().unwrap();
+    focus("focus");
+    assert_eq!(scramble.play_state(), PlayState::Playing);
+
+    // Nothing is drawn while the window is blurred
+    focus("blur");
+    assert_eq!(scramble.play_state(), PlayState::Paused);
+    let frozen = element.text_content().unwrap();
+    sleep(200.0).await;
+    assert_eq!(element.text_content().unwrap(), frozen);
+
+    focus("focus");
+    assert_eq!(scramble.play_state(), PlayState::Playing);
+    scramble.dispose();
+}
+
+#[wasm_bindgen_test]
+fn test_shared_target() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let props = |text: &str, shared_target| {
+        serde_wasm_bindgen::to_value(&UseScrambleProps {
+            text: text.to_string(),
+            shared_target,
+            ..Default::default()
+        })
+        .unwrap()
+    };
+
+    let mut first =
+        ScrambleText::new(element.clone().into(), props("First", SharedTarget::Queue)).unwrap();
+    assert!(
+        ScrambleText::new(element.clone().into(), props("Second", SharedTarget::Error)).is_err()
+    );
+
+    // A queued run starts once the run drawing into the node ends
+    let mut second =
+        ScrambleText::new(element.clone().into(), props("Second", SharedTarget::Queue)).unwrap();
+    first.start().unwrap();
+    second.start().unwrap();
+    assert_eq!(second.play_state(), PlayState::Delayed);
+    first.run_frames(1000);
+    assert_eq!(second.play_state(), PlayState::Playing);
+    second.run_frames(1000);
+    assert_eq!(element.text_content().unwrap(), "Second");
+
+    // Adopting controls the existing animation with the new props
+    second.dispose();
+    let mut adopted = ScrambleText::new(
+        element.clone().into(),
+        props("Adopted", SharedTarget::Adopt),
+    )
+    .unwrap();
+    assert_eq!(first.text(), "Adopted");
+    adopted.start().unwrap();
+    assert!(first.is_playing());
+    drop(adopted);
+    assert!(first.is_playing());
+    first.run_frames(1000);
+    assert_eq!(element.text_content().unwrap(), "Adopted");
+}
+
+#[wasm_bindgen_test]
+fn test_range_accepts_code_point_strings() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let props = js_sys::eval("({ text: 'ab', range: { Range: ['0x2591', 9619] } })").unwrap();
+
+    let scramble = ScrambleText::new(element.into(), props).unwrap();
+    let props: UseScrambleProps =
+        serde_wasm_bindgen::from_value(scramble.props().unwrap()).unwrap();
+    assert_eq!(props.range.to_string(), "9617-9619");
+
+    let element = setup_test_element(&document);
+    let props = js_sys::eval("({ text: 'ab', range: { codes: ['a', '#', 'あ'] } })").unwrap();
+    let scramble = ScrambleText::new(element.into(), props).unwrap();
+    let props: UseScrambleProps =
+        serde_wasm_bindgen::from_value(scramble.props().unwrap()).unwrap();
+    assert_eq!(props.range.to_string(), "97,35,12354");
+
+    // Single characters in a list are characters, digits included
+    let element = setup_test_element(&document);
+    let props = js_sys::eval("({ text: 'ab', range: { range: ['0', '9'] } })").unwrap();
+    let scramble = ScrambleText::new(element.into(), props).unwrap();
+    let props: UseScrambleProps =
+        serde_wasm_bindgen::from_value(scramble.props().unwrap()).unwrap();
+    assert_eq!(props.range.to_string(), "48-57");
+}
+
+#[wasm_bindgen_test]
+fn test_ignore_as_a_string() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let ignore_of = |ignore: &str| {
+        let element = setup_test_element(&document);
+        let props = js_sys::eval(&format!("({{ text: 'a, b!', ignore: {} }})", ignore)).unwrap();
+        let scramble = ScrambleText::new(element.into(), props).unwrap();
+        let props = scramble.props().unwrap();
+        let ignore = js_sys::Reflect::get(&props, &"ignore".into()).unwrap();
+        serde_wasm_bindgen::from_value::<Vec<String>>(ignore).unwrap()
+    };
+
+    assert_eq!(ignore_of("'., !?'"), vec![" ", "!", ",", ".", "?"]);
+    // Strings in a list stand for each of their characters
+    assert_eq!(ignore_of("['ab', 'a', ' ']"), vec![" ", "a", "b"]);
+}
+
+#[wasm_bindgen_test]
+fn test_content_selector() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    element.set_inner_html("<h2>Breaking: <span class=\"em\">news</span></h2>");
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "story".to_string(),
+        content_selector: Some("h2 .em".to_string()),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
+    scramble.start().unwrap();
+    scramble.run_frames(1000);
+    // Only the matching descendant is written, the markup around it is kept
+    assert_eq!(element.text_content().unwrap(), "Breaking: story");
+
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        content_selector: Some(".missing".to_string()),
+        ..Default::default()
+    })
+    .unwrap();
+    assert!(ScrambleText::new(element.clone().into(), props).is_err());
+
+    // Several matches share the text in document order
+    element.set_inner_html("<p><b>ab</b>-<b>cd</b></p>");
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "wxyz".to_string(),
+        content_selector: Some("b".to_string()),
+        ..Default::default()
+    })
+    .unwrap();
+    let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
+    scramble.start().unwrap();
+    scramble.run_frames(1000);
+    assert_eq!(element.text_content().unwrap(), "wx-yz");
+}
+
+#[wasm_bindgen_test]
+fn test_across_elements() {
+    use wasm_bindgen::JsCast;
+
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    element.set_inner_html("<span>one </span><span>two </span><span>six</span>");
+    let spans = element.query_selector_all("span").unwrap();
+    let lines: Vec<Element> = (0..spans.length())
+        .filter_map(|i| spans.item(i))
+        .map(|span| span.unchecked_into())
+        .collect();
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "red blue violet".to_string(),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let mut scramble = ScrambleText::across(lines.clone(), props.clone()).unwrap();
+    scramble.start().unwrap();
+    scramble.run_frames(1000);
+    // Each element keeps its length, and the last one takes the rest
+    let slices: Vec<String> = lines
+        .iter()
+        .map(|line| line.text_content().unwrap())
+        .collect();
+    assert_eq!(slices, vec!["red ", "blue", " violet"]);
+    assert_eq!(element.text_content().unwrap(), "red blue violet");
+
+    assert!(ScrambleText::across(Vec::new(), props).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_split_target() {
+    use wasm_bindgen::JsValue;
+
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    element.set_inner_html("Hello <em>big</em> world");
+
+    let mut group = split_target(element.clone(), "words", JsValue::UNDEFINED, None).unwrap();
+    assert_eq!(group.length(), 3);
+    assert_eq!(
+        element.inner_html(),
+        "<span class=\"scramble-word\" aria-hidden=\"true\">Hello</span> \
+         <span class=\"scramble-word\" aria-hidden=\"true\">big</span> \
+         <span class=\"scramble-word\" aria-hidden=\"true\">world</span>"
+    );
+    assert_eq!(
+        element.get_attribute("aria-label").as_deref(),
+        Some("Hello big world")
+    );
+    group.stagger("linear", 0.0).unwrap();
+    group.start().unwrap();
+    group.destroy();
+    // The original markup comes back
+    assert_eq!(element.inner_html(), "Hello <em>big</em> world");
+    assert_eq!(element.get_attribute("aria-label"), None);
+
+    let mut group = split_target(element.clone(), "chars", JsValue::UNDEFINED, None).unwrap();
+    assert_eq!(group.length(), 13);
+    group.destroy();
+
+    // Emoji sequences and combining marks stay in one span
+    element.set_inner_html("a\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}e\u{301}");
+    let mut group = split_target(element.clone(), "chars", JsValue::UNDEFINED, None).unwrap();
+    assert_eq!(group.length(), 3);
+    let spans = element.query_selector_all(".scramble-char").unwrap();
+    assert_eq!(
+        spans.item(1).unwrap().text_content(),
+        Some("\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}".to_string())
+    );
+    assert_eq!(
+        spans.item(2).unwrap().text_content(),
+        Some("e\u{301}".to_string())
+    );
+    group.destroy();
+
+    element.set_inner_html("one two");
+    let group = split_target(element.clone(), "lines", JsValue::UNDEFINED, None).unwrap();
+    assert_eq!(group.length(), 1);
+    assert_eq!(
+        element
+            .query_selector(".scramble-line")
+            .unwrap()
+            .unwrap()
+            .text_content(),
+        Some("one two".to_string())
+    );
+
+    assert!(split_target(element, "sentences", JsValue::UNDEFINED, None).is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_split_lines_follow_resizes() {
+    use wasm_bindgen::JsCast;
+
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let style = element.unchecked_ref::<web_sys::HtmlElement>().style();
+    style.set_property("width", "2000px").unwrap();
+    element.set_text_content(Some("one two three four"));
+    let line_texts = |element: &Element| {
+        let lines = element.query_selector_all(".scramble-line").unwrap();
+        (0..lines.length())
+            .filter_map(|i| lines.item(i))
+            .map(|line| line.text_content().unwrap())
+            .collect::<Vec<_>>()
+    };
+
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        speed: 0.0,
+        ..Default::default()
+    })
+    .unwrap();
+    let mut group = split_target(element.clone(), "lines", props, Some("restart".into())).unwrap();
+    assert_eq!(line_texts(&element), vec!["one two three four"]);
+    group.start().unwrap();
+
+    // Narrowing the element wraps the words onto more lines, split again with new animations
+    style.set_property("width", "1px").unwrap();
+    sleep(50.0).await;
+    assert_eq!(group.length(), 4);
+    assert_eq!(line_texts(&element), vec!["one", "two", "three", "four"]);
+    assert_eq!(element.text_content().unwrap().chars().count(), 18);
+    group.destroy();
+    assert_eq!(element.text_content().unwrap(), "one two three four");
+
+    assert!(split_target(
+        element,
+        "lines",
+        wasm_bindgen::JsValue::UNDEFINED,
+        Some("pause".into())
+    )
+    .is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_split_lines_continue_after_resize() {
+    use wasm_bindgen::JsCast;
+
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    let style = element.unchecked_ref::<web_sys::HtmlElement>().style();
+    style.set_property("width", "2000px").unwrap();
+    let text = "one two three four";
+    element.set_text_content(Some(text));
+    let revealed = |element: &Element| {
+        let shown = element.text_content().unwrap();
+        shown
+            .chars()
+            .zip(text.chars())
+            .take_while(|(a, b)| a == b)
+            .count()
+    };
+
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        tick: 6,
+        step: 1,
+        scramble: 0,
+        ..Default::default()
+    })
+    .unwrap();
+    let mut group = split_target(element.clone(), "lines", props, None).unwrap();
+    group.start().unwrap();
+    sleep(400.0).await;
+    let before = revealed(&element);
+    assert!(before > 0 && before < text.len());
+
+    // Narrowing the element mid-run splits it again, the new lines carrying on from the frame
+    style.set_property("width", "1px").unwrap();
+    sleep(50.0).await;
+    assert_eq!(group.length(), 4);
+    let shown = element.text_content().unwrap();
+    assert_eq!(shown.chars().count(), text.chars().count());
+    assert_eq!(shown[..before], text[..before]);
+    group.destroy();
+}
+
+#[wasm_bindgen_test]
+fn test_hover_scrub() {
+    use wasm_bindgen::JsCast;
+
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = setup_test_element(&document);
+    element.set_id("hover-scrub");
+    let props = serde_wasm_bindgen::to_value(&UseScrambleProps {
+        text: "Hello world".to_string(),
+        hover_scrub: true,
+        hover_scrub_settle: 100.0,
+        ..Default::default()
+    })
+    .unwrap();
+    let states = |element: &Element| {
+        let spans = element.query_selector_all("span").unwrap();
+        (0..spans.length())
+            .filter_map(|i| spans.item(i))
+            .map(|span| {
+                let span: Element = span.unchecked_into();
+                span.get_attribute("data-state").unwrap()
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut scramble = ScrambleText::new(element.clone().into(), props).unwrap();
+    scramble.start().unwrap();
+    scramble.run_frames(1000);
+    assert_eq!(scramble.play_state(), PlayState::Completed);
+
+    // The pointer over the third character scrambles it and its neighbors again
+    js_sys::eval(
+        "document.querySelector('#hover-scrub span:nth-child(3)')
+            .dispatchEvent(new Event('pointermove', { bubbles: true }))",
+    )
+    .unwrap();
+    scramble.run_frames(1);
+    assert_eq!(
+        states(&element)[..5],
+        [
+            "settled",
+            "scrambling",
+            "scrambling",
+            "scrambling",
+            "settled"
+        ]
+    );
+    assert_eq!(scramble.play_state(), PlayState::Completed);
+
+    scramble.run_frames(1000);
+    assert_eq!(element.text_content().unwrap(), "Hello world");
+    assert!(states(&element).iter().all(|state| state == "settled"));
+}